@@ -0,0 +1,53 @@
+//! Bucket rounds: a categorical market on which range the round's
+//! invocation count falls in. `edges` split the counts into
+//! `edges.len() + 1` buckets — bucket 0 is below `edges[0]`, bucket `i`
+//! is `edges[i - 1]..edges[i]` and the last is open‑ended — and the
+//! winning bucket's backers split the pot. Bet with `Side::Bucket(i)`.
+
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Vec};
+
+use crate::{
+    create_round, default_rules, Deadline, Error, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, Market,
+};
+
+/// Most buckets a round may have.
+pub const MAX_BUCKETS: u32 = 8;
+
+/// Index of the bucket `count` falls in.
+pub(crate) fn bucket_of(edges: &Vec<u32>, count: u32) -> u32 {
+    edges.iter().take_while(|&edge| count >= edge).count() as u32
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Start a bucket round on the invocations from now to finality, with
+    /// the default rules. `edges` must be strictly increasing and make
+    /// between two and `MAX_BUCKETS` buckets.
+    pub fn start_bucket_round(
+        env: Env,
+        admin: Address,
+        edges: Vec<u32>,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        let increasing = edges
+            .iter()
+            .zip(edges.iter().skip(1))
+            .all(|(lower, upper)| lower < upper);
+        if edges.is_empty() || edges.len() >= MAX_BUCKETS || !increasing {
+            panic_with_error!(env, Error::InvalidBuckets);
+        }
+
+        let rules = default_rules(&env);
+        create_round(
+            &env,
+            &admin,
+            Market::Buckets(edges),
+            0,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        )
+    }
+}
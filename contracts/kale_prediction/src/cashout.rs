@@ -0,0 +1,106 @@
+//! Early cash‑out. Until finality, the holder of an over/under stake may
+//! sell its main side back to the book. The price is what the stake would
+//! win, weighted by the chance the rest of the book gives its side — the
+//! side's pool without the stake over the pot without it — less the
+//! `cash_out_haircut_bps` haircut. That never exceeds the stake, so the
+//! stake leaves its pool and whatever it does not fetch stays in the pot,
+//! owned by nobody: the winning side splits it, or every stake shares it
+//! if the round pushes or is voided.
+
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env};
+
+use crate::roles::{require_role, Role};
+use crate::state::{advance, load_round_advanced};
+use crate::timelock::{self, Fee};
+use crate::{
+    adjust_pool, checked_add, duels, has_maker, load_round, load_stake, mul_div, pay, pool,
+    require_live, round_token, save_round, seasons, shrink_stake, store_fee, total_pool, DataKey,
+    Error, KalePrediction, KalePredictionArgs, KalePredictionClient, Market, Round, RoundState,
+    Stake,
+};
+
+/// Haircut on cash‑outs until the admin sets one (basis points).
+const DEFAULT_CASH_OUT_HAIRCUT_BPS: u32 = 200;
+
+/// Upper bound for the cash‑out haircut (basis points).
+pub(crate) const MAX_CASH_OUT_HAIRCUT_BPS: u32 = 5_000;
+
+/// What the main side of `stake` fetches now.
+fn price(e: &Env, round: &Round, stake: &Stake) -> i128 {
+    let side_pool = pool(round, stake.side);
+    let total = total_pool(round);
+    if total == stake.amount {
+        return 0;
+    }
+    let fair = stake
+        .amount
+        .checked_mul(total)
+        .and_then(|n| n.checked_mul(side_pool - stake.amount))
+        .zip(side_pool.checked_mul(total - stake.amount))
+        .map(|(n, d)| n / d)
+        .unwrap_or_else(|| panic_with_error!(e, Error::MathOverflow));
+    let haircut_bps: u32 = e
+        .storage()
+        .instance()
+        .get(&DataKey::CashOutHaircutBps)
+        .unwrap_or(DEFAULT_CASH_OUT_HAIRCUT_BPS);
+    fair - mul_div(e, fair, haircut_bps as i128, 10_000)
+}
+
+/// `player`'s stake on an over/under round that may still be cashed out.
+fn cashable(e: &Env, round_id: u32, round: &Round, player: &Address) -> Stake {
+    if !matches!(round.state, RoundState::Open | RoundState::Locked) || round.finality.reached(e) {
+        panic_with_error!(e, Error::BettingClosed);
+    }
+    if !matches!(round.market, Market::Invocations | Market::Price(_))
+        || has_maker(e, round_id)
+        || duels::is_duel(e, round_id)
+    {
+        panic_with_error!(e, Error::WrongMarket);
+    }
+    load_stake(e, round_id, player)
+        .unwrap_or_else(|| panic_with_error!(e, Error::InsufficientShares))
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Set the cash‑out haircut, in basis points of the fair price (at
+    /// most `MAX_CASH_OUT_HAIRCUT_BPS`).
+    pub fn set_cash_out_haircut_bps(env: Env, manager: Address, bps: u32) {
+        require_live(&env);
+        require_role(&env, Role::FeeManager, &manager);
+        timelock::require_unlocked(&env);
+        store_fee(&env, Fee::CashOutHaircut, bps);
+    }
+
+    /// Sell the main side of `player`'s stake back to the book before
+    /// finality; a hedge stays as the stake. Returns what was paid.
+    pub fn cash_out(env: Env, player: Address, round_id: u32) -> i128 {
+        require_live(&env);
+        player.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        let stake = cashable(&env, round_id, &round, &player);
+        let paid = price(&env, &round, &stake);
+        let amount = stake.amount;
+
+        adjust_pool(&env, &mut round, stake.side, -amount);
+        round.forfeited = checked_add(&env, round.forfeited, amount - paid);
+        shrink_stake(&env, round_id, &mut round, &player, stake, amount);
+        save_round(&env, round_id, &round);
+        seasons::record_bet(&env, round_id, &player, -paid, false);
+
+        if paid > 0 {
+            pay(&env, &round_token(&env, &round), &player, paid);
+        }
+        paid
+    }
+
+    /// What `cash_out` would pay `player` right now.
+    pub fn get_cash_out_quote(env: Env, player: Address, round_id: u32) -> i128 {
+        let mut round = load_round(&env, round_id);
+        advance(&env, round_id, &mut round, false);
+        let stake = cashable(&env, round_id, &round, &player);
+        price(&env, &round, &stake)
+    }
+}
@@ -0,0 +1,131 @@
+//! Compliance controls for deployments that need them. The admin can block
+//! an account from betting (`RiskRejected`), and optionally freeze what it
+//! already has staked so it cannot be claimed or refunded either, nor
+//! paid out from the side books or its operator deposit. A round
+//! can also be made private: only accounts on its allowlist, or approved by
+//! an allowlist contract, may bet on it (`Unauthorized` for the rest).
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env, Vec};
+
+use crate::events;
+use crate::oracle::AllowlistClient;
+use crate::state::load_round_advanced;
+use crate::{
+    require_admin, require_live, DataKey, Error, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, RoundState,
+};
+
+/// Most accounts a round's own allowlist may name.
+const MAX_ALLOWLIST: u32 = 100;
+
+/// Who may bet on a private round.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum RoundAllowlist {
+    Accounts(Vec<Address>),
+    Contract(Address), // asked through `oracle::Allowlist`
+}
+
+/// Whether `account`'s claims are frozen (`None` if it is not blocked).
+fn blocked(e: &Env, account: &Address) -> Option<bool> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Blocked(account.clone()))
+}
+
+/// Blocked accounts may not bet.
+pub(crate) fn require_unblocked(e: &Env, player: &Address) {
+    if blocked(e, player).is_some() {
+        panic_with_error!(e, Error::RiskRejected);
+    }
+}
+
+/// Whether `player`'s stakes are frozen.
+pub(crate) fn is_frozen(e: &Env, player: &Address) -> bool {
+    blocked(e, player).unwrap_or(false)
+}
+
+/// Frozen accounts may not be paid out (`RiskRejected`).
+pub(crate) fn require_unfrozen(e: &Env, player: &Address) {
+    if is_frozen(e, player) {
+        panic_with_error!(e, Error::RiskRejected);
+    }
+}
+
+/// On a private round, only allowed accounts may bet.
+pub(crate) fn require_allowed(e: &Env, round_id: u32, player: &Address) {
+    let allowed = match e
+        .storage()
+        .persistent()
+        .get::<DataKey, RoundAllowlist>(&DataKey::Allowlist(round_id))
+    {
+        None => true,
+        Some(RoundAllowlist::Accounts(accounts)) => accounts.contains(player),
+        Some(RoundAllowlist::Contract(registry)) => {
+            AllowlistClient::new(e, &registry).is_allowed(player)
+        }
+    };
+    if !allowed {
+        panic_with_error!(e, Error::Unauthorized);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Block `account` from betting, also freezing its stakes with
+    /// `freeze_claims`. Evented as `("blocked", account) → freeze_claims`.
+    pub fn block_account(env: Env, admin: Address, account: Address, freeze_claims: bool) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        env.storage()
+            .persistent()
+            .set(&DataKey::Blocked(account.clone()), &freeze_claims);
+        events::publish(&env, "blocked", (account,), freeze_claims);
+    }
+
+    /// Lift a block. Evented as `("unblocked", account)`.
+    pub fn unblock_account(env: Env, admin: Address, account: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Blocked(account.clone()));
+        events::publish(&env, "unblocked", (account,), ());
+    }
+
+    /// Make an open round private to `allowlist`, or open it to everyone
+    /// with `None`. Stakes already placed stand. An account list holds at
+    /// most `MAX_ALLOWLIST` entries.
+    pub fn set_round_allowlist(
+        env: Env,
+        admin: Address,
+        round_id: u32,
+        allowlist: Option<RoundAllowlist>,
+    ) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if load_round_advanced(&env, round_id).state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let key = DataKey::Allowlist(round_id);
+        match allowlist {
+            Some(RoundAllowlist::Accounts(a)) if a.len() > MAX_ALLOWLIST => {
+                panic_with_error!(env, Error::InvalidRules)
+            }
+            Some(list) => env.storage().persistent().set(&key, &list),
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// A private round's allowlist, if it has one.
+    pub fn get_round_allowlist(env: Env, round_id: u32) -> Option<RoundAllowlist> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Allowlist(round_id))
+    }
+
+    /// Whether `account` is blocked from betting.
+    pub fn is_blocked(env: Env, account: Address) -> bool {
+        blocked(&env, &account).is_some()
+    }
+}
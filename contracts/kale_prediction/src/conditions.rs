@@ -0,0 +1,112 @@
+//! Conditional rounds. A round may carry a precondition — another round
+//! deciding a given way, or a minimum pot — checked when it is resolved.
+//! If the precondition fails, the round is voided instead and every stake
+//! comes back through `claim`. A round waiting on an undecided round cannot
+//! be resolved until that round is decided (or it expires).
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::state::load_round_advanced;
+use crate::{
+    hold_round, load_round, release_round, require_admin, require_live, total_pool, DataKey, Error,
+    KalePrediction, KalePredictionArgs, KalePredictionClient, Outcome, Round, RoundState,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Precondition {
+    Outcome(u32, Outcome), // that round decided this way
+    MinPot(i128),          // at least this much staked on the round
+}
+
+/// Whether `round`'s precondition holds, or `None` while it depends on a
+/// round not decided yet. Rounds without one always hold.
+pub(crate) fn check(e: &Env, round_id: u32, round: &Round) -> Option<bool> {
+    let Some(precondition) = e
+        .storage()
+        .persistent()
+        .get::<DataKey, Precondition>(&DataKey::Precondition(round_id))
+    else {
+        return Some(true);
+    };
+    match precondition {
+        Precondition::MinPot(min) => Some(total_pool(round) >= min),
+        Precondition::Outcome(other_id, outcome) => {
+            let other = load_round_advanced(e, other_id);
+            match other.state {
+                RoundState::Created | RoundState::Open | RoundState::Locked => None,
+                _ => Some(other.outcome == outcome),
+            }
+        }
+    }
+}
+
+/// The round another round's outcome is conditional on, if any.
+fn depends_on(e: &Env, round_id: u32) -> Option<u32> {
+    match e
+        .storage()
+        .persistent()
+        .get::<DataKey, Precondition>(&DataKey::Precondition(round_id))
+    {
+        Some(Precondition::Outcome(other_id, _)) => Some(other_id),
+        _ => None,
+    }
+}
+
+/// A settled round no longer needs the round it was conditional on.
+pub(crate) fn release(e: &Env, round_id: u32) {
+    if let Some(other_id) = depends_on(e, round_id) {
+        release_round(e, other_id);
+    }
+}
+
+/// `check` for a round about to be resolved.
+pub(crate) fn holds(e: &Env, round_id: u32, round: &Round) -> bool {
+    check(e, round_id, round).unwrap_or_else(|| panic_with_error!(e, Error::PreconditionPending))
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Make an open round conditional on `precondition`, or lift the
+    /// condition with `None`.
+    pub fn set_precondition(
+        env: Env,
+        admin: Address,
+        round_id: u32,
+        precondition: Option<Precondition>,
+    ) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if load_round_advanced(&env, round_id).state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let key = DataKey::Precondition(round_id);
+        let before = depends_on(&env, round_id);
+        match precondition {
+            Some(Precondition::Outcome(other_id, _)) if other_id == round_id => {
+                panic_with_error!(env, Error::InvalidRules)
+            }
+            Some(Precondition::MinPot(min)) if min <= 0 => {
+                panic_with_error!(env, Error::ZeroAmount)
+            }
+            Some(precondition) => {
+                if let Precondition::Outcome(other_id, _) = precondition {
+                    load_round(&env, other_id);
+                    hold_round(&env, other_id);
+                }
+                env.storage().persistent().set(&key, &precondition);
+            }
+            None => env.storage().persistent().remove(&key),
+        }
+        if let Some(other_id) = before {
+            release_round(&env, other_id);
+        }
+    }
+
+    /// A round's precondition, if it has one.
+    pub fn get_precondition(env: Env, round_id: u32) -> Option<Precondition> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Precondition(round_id))
+    }
+}
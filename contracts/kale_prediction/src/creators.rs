@@ -0,0 +1,167 @@
+//! Permissionless rounds. Once the admin publishes `CreatorTerms`, anyone
+//! may start a count round by paying the listing fee to the admin and
+//! escrowing a bond. The bond is slashed to the admin if the round is
+//! cancelled (the admin's remedy for a badly specified round); otherwise it
+//! comes back to the creator once claims open, together with
+//! `creator_share_bps` of the round's protocol fee.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::state::load_round_advanced;
+use crate::{
+    checked_add, default_rules, default_token, get_admin, mul_div, open_round, pay, pull,
+    require_admin, require_live, require_paying, token_client, DataKey, Deadline, Error, FeeCurve,
+    KalePrediction, KalePredictionArgs, KalePredictionClient, Market, Round, MAX_PROTOCOL_FEE_BPS,
+};
+
+/// What it costs to start a round without the admin.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreatorTerms {
+    pub bond: i128,
+    pub listing_fee: i128,
+    pub fee_curve: FeeCurve,    // protocol fee on creators' rounds
+    pub creator_share_bps: u32, // of that fee, paid back with the bond
+}
+
+/// A creator's escrowed bond on one round.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreatorBond {
+    pub creator: Address,
+    pub bond: i128,
+    pub share_bps: u32,
+    pub settled: bool, // returned or slashed
+}
+
+fn load_bond(e: &Env, round_id: u32) -> Option<CreatorBond> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::CreatorBond(round_id))
+}
+
+/// The creator's cut of `round`'s protocol fee; `collect_fee` pays the
+/// admin the rest.
+pub(crate) fn creator_share(e: &Env, round_id: u32, round: &Round) -> i128 {
+    load_bond(e, round_id).map_or(0, |b| {
+        mul_div(e, round.protocol_fee, b.share_bps as i128, 10_000)
+    })
+}
+
+/// Whether the round's creator bond has yet to be returned or slashed.
+pub(crate) fn is_bonded(e: &Env, round_id: u32) -> bool {
+    load_bond(e, round_id).is_some_and(|b| !b.settled)
+}
+
+/// Forfeit a cancelled round's bond to the admin.
+pub(crate) fn slash(e: &Env, round_id: u32) {
+    let Some(mut bond) = load_bond(e, round_id) else {
+        return;
+    };
+    bond.settled = true;
+    e.storage()
+        .persistent()
+        .set(&DataKey::CreatorBond(round_id), &bond);
+    if bond.bond > 0 {
+        pay(e, &token_client(e), &get_admin(e), bond.bond);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Let anyone start rounds on `terms`, or keep it to the admin with
+    /// `None`. Rounds already started keep their bond terms.
+    pub fn set_creator_terms(env: Env, admin: Address, terms: Option<CreatorTerms>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let Some(terms) = terms else {
+            env.storage().instance().remove(&DataKey::CreatorTerms);
+            return;
+        };
+        if terms.bond < 0
+            || terms.listing_fee < 0
+            || terms.creator_share_bps > 10_000
+            || terms.fee_curve.min_bps > terms.fee_curve.max_bps
+            || terms.fee_curve.max_bps > MAX_PROTOCOL_FEE_BPS
+        {
+            panic_with_error!(env, Error::InvalidFee);
+        }
+        env.storage().instance().set(&DataKey::CreatorTerms, &terms);
+    }
+
+    /// Start a count round as `creator` under the published terms, paying
+    /// the listing fee and escrowing the bond.
+    pub fn start_round_bonded(
+        env: Env,
+        creator: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        require_live(&env);
+        creator.require_auth();
+        let terms: CreatorTerms = env
+            .storage()
+            .instance()
+            .get(&DataKey::CreatorTerms)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Unauthorized));
+
+        let mut rules = default_rules(&env);
+        rules.fee_curve = terms.fee_curve;
+        let round_id = open_round(
+            &env,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+            default_token(&env),
+        );
+
+        let token = token_client(&env);
+        if terms.listing_fee > 0 {
+            token.transfer(&creator, &get_admin(&env), &terms.listing_fee);
+        }
+        if terms.bond > 0 {
+            pull(&env, &creator, terms.bond);
+        }
+        env.storage().persistent().set(
+            &DataKey::CreatorBond(round_id),
+            &CreatorBond {
+                creator,
+                bond: terms.bond,
+                share_bps: terms.creator_share_bps,
+                settled: false,
+            },
+        );
+        round_id
+    }
+
+    /// Return the bond and fee share to the creator of a round that was not
+    /// cancelled, once its claims are open.
+    pub fn reclaim_bond(env: Env, round_id: u32) {
+        require_live(&env);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let mut bond = load_bond(&env, round_id)
+            .unwrap_or_else(|| panic_with_error!(env, Error::RoundNotFound));
+        if bond.settled {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        bond.settled = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::CreatorBond(round_id), &bond);
+
+        let payout = checked_add(&env, bond.bond, creator_share(&env, round_id, &round));
+        if payout > 0 {
+            pay(&env, &token_client(&env), &bond.creator, payout);
+        }
+    }
+
+    /// Bond escrowed by a round's creator, if anyone but the admin started it.
+    pub fn get_creator_bond(env: Env, round_id: u32) -> Option<CreatorBond> {
+        load_bond(&env, round_id)
+    }
+}
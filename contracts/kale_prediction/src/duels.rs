@@ -0,0 +1,152 @@
+//! Head‑to‑head rounds. A challenger opens a private over/under round
+//! against one opponent, staking a fixed amount on their side of the line;
+//! if the opponent accepts by matching the stake on the other side, the
+//! winner takes the pot through the usual `claim`. Nobody else may bet or
+//! take over a stake. Until accepted, the challenger may withdraw and
+//! `claim` their stake back.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::state::{load_round_advanced, transition};
+use crate::{
+    add_stake, check_risk, default_rules, default_token, open_round, pull, require_live,
+    require_min_bet, save_round, DataKey, Deadline, Error, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, Market, Outcome, RoundState, Side,
+};
+
+/// What a challenger offers.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DuelTerms {
+    pub opponent: Address,
+    pub side: Side, // the challenger's; `Higher` or `Lower`
+    pub stake: i128,
+    pub predicted_count: u32,
+    pub deadline_ledger: u32,
+    pub finality_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Duel {
+    pub challenger: Address,
+    pub opponent: Address,
+    pub side: Side, // the challenger's
+    pub stake: i128,
+    pub accepted: bool,
+}
+
+pub(crate) fn is_duel(e: &Env, round_id: u32) -> bool {
+    e.storage().persistent().has(&DataKey::Duel(round_id))
+}
+
+fn load_duel(e: &Env, round_id: u32) -> Duel {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Duel(round_id))
+        .unwrap_or_else(|| panic_with_error!(e, Error::WrongMarket))
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Open a head‑to‑head round on `terms`, escrowing the challenger's
+    /// stake.
+    pub fn open_duel(env: Env, challenger: Address, terms: DuelTerms) -> u32 {
+        require_live(&env);
+        require_min_bet(&env, terms.stake);
+        if !matches!(terms.side, Side::Higher | Side::Lower) {
+            panic_with_error!(env, Error::InvalidSide);
+        }
+        if terms.opponent == challenger {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        challenger.require_auth();
+
+        let round_id = open_round(
+            &env,
+            Market::Invocations,
+            terms.predicted_count,
+            Deadline::Ledger(terms.deadline_ledger),
+            Deadline::Ledger(terms.finality_ledger),
+            default_rules(&env),
+            default_token(&env),
+        );
+        check_risk(&env, &challenger, round_id, terms.stake);
+        pull(&env, &challenger, terms.stake);
+        let mut round = load_round_advanced(&env, round_id);
+        add_stake(
+            &env,
+            round_id,
+            &mut round,
+            &challenger,
+            terms.side,
+            terms.stake,
+        );
+        save_round(&env, round_id, &round);
+        env.storage().persistent().set(
+            &DataKey::Duel(round_id),
+            &Duel {
+                challenger,
+                opponent: terms.opponent,
+                side: terms.side,
+                stake: terms.stake,
+                accepted: false,
+            },
+        );
+        round_id
+    }
+
+    /// Take up a challenge by matching its stake on the other side.
+    pub fn accept_duel(env: Env, opponent: Address, round_id: u32) {
+        require_live(&env);
+        let mut duel = load_duel(&env, round_id);
+        if opponent != duel.opponent {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        opponent.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open || duel.accepted {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let side = if duel.side == Side::Higher {
+            Side::Lower
+        } else {
+            Side::Higher
+        };
+        require_min_bet(&env, duel.stake);
+        check_risk(&env, &opponent, round_id, duel.stake);
+        pull(&env, &opponent, duel.stake);
+        add_stake(&env, round_id, &mut round, &opponent, side, duel.stake);
+        save_round(&env, round_id, &round);
+        duel.accepted = true;
+        env.storage()
+            .persistent()
+            .set(&DataKey::Duel(round_id), &duel);
+    }
+
+    /// Withdraw a challenge nobody has accepted yet; the round is voided
+    /// and the challenger claims their stake back.
+    pub fn cancel_duel(env: Env, challenger: Address, round_id: u32) {
+        require_live(&env);
+        let duel = load_duel(&env, round_id);
+        if challenger != duel.challenger {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        challenger.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        if duel.accepted || !matches!(round.state, RoundState::Open | RoundState::Locked) {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+        round.outcome = Outcome::Voided;
+        round.resolved_by = Some(challenger);
+        round.resolution_ledger = env.ledger().sequence();
+        transition(&env, round_id, &mut round, RoundState::Voided);
+        save_round(&env, round_id, &round);
+    }
+
+    pub fn get_duel(env: Env, round_id: u32) -> Duel {
+        load_duel(&env, round_id)
+    }
+}
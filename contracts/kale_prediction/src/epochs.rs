@@ -0,0 +1,104 @@
+//! Recurring rounds. With an epoch schedule in place, deciding the chain's
+//! latest round (resolved, voided or expired) immediately starts the next
+//! one on the same market and rules, its windows shifted by
+//! `interval_ledgers`, so a daily or weekly market runs without manual
+//! `start_round` calls.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::{
+    at_capacity, has_maker, load_round, open_round, require_admin, require_live, sunset_active,
+    DataKey, Error, KalePrediction, KalePredictionArgs, KalePredictionClient, Outcome, Round,
+    RoundState,
+};
+
+/// Where each chained round's line comes from.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LineSource {
+    Fixed(u32), // always this count
+    LastResult, // the previous round's count (its line if it had none)
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EpochSchedule {
+    pub round_id: u32, // latest round of the chain
+    pub interval_ledgers: u32,
+    pub line: LineSource,
+}
+
+/// Start the successor of `round` if it heads the epoch chain. The chain
+/// ends quietly once the sunset is in effect or the open‑round cap is
+/// reached, and never extends to fixed‑odds or LMSR rounds.
+pub(crate) fn chain_next(e: &Env, round_id: u32, round: &Round) {
+    let Some(mut schedule) = e
+        .storage()
+        .instance()
+        .get::<DataKey, EpochSchedule>(&DataKey::Epochs)
+    else {
+        return;
+    };
+    if schedule.round_id != round_id {
+        return;
+    }
+    if sunset_active(e) || at_capacity(e) || has_maker(e, round_id) {
+        e.storage().instance().remove(&DataKey::Epochs);
+        return;
+    }
+
+    let predicted_count = match schedule.line {
+        LineSource::Fixed(count) => count,
+        LineSource::LastResult if matches!(round.outcome, Outcome::Voided | Outcome::Expired) => {
+            round.predicted_count
+        }
+        LineSource::LastResult => round.actual_count,
+    };
+    // a late resolution skips whole intervals rather than open a round
+    // whose betting has already closed
+    let interval = schedule.interval_ledgers;
+    let mut deadline = round.deadline.plus(interval);
+    let mut finality = round.finality.plus(interval);
+    while deadline.passed(e) {
+        deadline = deadline.plus(interval);
+        finality = finality.plus(interval);
+    }
+
+    schedule.round_id = open_round(
+        e,
+        round.market.clone(),
+        predicted_count,
+        deadline,
+        finality,
+        round.rules.clone(),
+        round.token.clone(),
+    );
+    e.storage().instance().set(&DataKey::Epochs, &schedule);
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Chain rounds from `schedule.round_id` on, or stop chaining with
+    /// `None`. The round must still be undecided.
+    pub fn configure_epochs(env: Env, admin: Address, schedule: Option<EpochSchedule>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let Some(schedule) = schedule else {
+            env.storage().instance().remove(&DataKey::Epochs);
+            return;
+        };
+        if schedule.interval_ledgers == 0 {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        let round = load_round(&env, schedule.round_id);
+        if !matches!(round.state, RoundState::Open | RoundState::Locked) {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+        env.storage().instance().set(&DataKey::Epochs, &schedule);
+    }
+
+    /// Current epoch schedule, if rounds are being chained.
+    pub fn get_epochs(env: Env) -> Option<EpochSchedule> {
+        env.storage().instance().get(&DataKey::Epochs)
+    }
+}
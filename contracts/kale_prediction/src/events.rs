@@ -0,0 +1,69 @@
+//! Event schema. Every event goes out through `publish`, so all share one
+//! shape: the topics are the event's name, `SCHEMA_VERSION`, then the ids
+//! listed below; the data is the listed tuple (or single value). The
+//! version is bumped whenever a name, an id or a data layout changes, so
+//! indexers can tell which layout an event has across upgrades.
+//!
+//! Rounds and bets:
+//!
+//! | name             | ids                  | data                                                   |
+//! |------------------|----------------------|--------------------------------------------------------|
+//! | `round_started`  | round                | `(predicted_count, deadline, finality, token)`         |
+//! | `bet_placed`     | round, player        | `(side, amount, side_pool, total_pool)` after the bet  |
+//! | `state`          | round                | `(from, to)` round states                              |
+//! | `round_resolved` | round                | `(outcome, actual_count, total_pool)`, amends included |
+//! | `claimed`        | round, player        | payout, net of fees (0 for a losing stake)             |
+//! | `refunded`       | round, player        | amount returned                                        |
+//! | `cancelled`      | round                | resolver                                               |
+//! | `pruned`         | round                | `(caller, bounty)`                                     |
+//! | `memo`           | memo, player         | `(round, side, amount)`                                |
+//! | `sponsor`        | sponsor, beneficiary | `(round, amount)`                                      |
+//! | `stake`          | from, to             | `(round, amount)` of a transferred stake               |
+//! | `shares`         | from, to             | `(round, side, amount)` of transferred shares          |
+//! | `risk`           | player               | `(round, amount, score, flagged)`                      |
+//! | `referral`       | referrer, player     | `(round, amount)`                                      |
+//! | `referred`       | referrer             | rewards withdrawn                                      |
+//! | `insured`        | player               | `(round, paid)`                                        |
+//! | `propose`        | round                | `(proposer, observed_count)`                           |
+//! | `challenge`      | round                | challenger                                             |
+//! | `season`         | season               | prize pool paid                                        |
+//!
+//! Administration:
+//!
+//! | name        | ids           | data                      |
+//! |-------------|---------------|---------------------------|
+//! | `role`      | role, account | granted                   |
+//! | `renounce`  |               | former admin              |
+//! | `paused`    |               | paused                    |
+//! | `operator`  | player        | operator                  |
+//! | `blocked`   | account       | claims frozen             |
+//! | `unblocked` | account       | `()`                      |
+//! | `queued`    | change        | `(change, eta)`           |
+//! | `executed`  | change        | `()`                      |
+//! | `dropped`   | change        | `()`                      |
+//! | `motion`    | motion        | `(change, closes)`        |
+//! | `carried`   | motion        | votes for                 |
+//! | `upgraded`  |               | wasm hash                 |
+//! | `migrated`  | from version  | new version               |
+//! | `sunset`    |               | ledger new rounds stop at |
+//! | `retired`   | successor     | balance moved             |
+
+use soroban_sdk::{vec, Env, IntoVal, Symbol, Val, Vec};
+
+/// Layout of the events described above.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// Publish `name` with its `ids` as topics after the schema version.
+pub(crate) fn publish<I, D>(e: &Env, name: &str, ids: I, data: D)
+where
+    I: IntoVal<Env, Vec<Val>>,
+    D: IntoVal<Env, Val>,
+{
+    let mut topics: Vec<Val> = vec![
+        e,
+        Symbol::new(e, name).into_val(e),
+        SCHEMA_VERSION.into_val(e),
+    ];
+    topics.append(&ids.into_val(e));
+    e.events().publish(topics, data);
+}
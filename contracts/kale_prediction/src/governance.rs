@@ -0,0 +1,206 @@
+//! Token‑holder governance. While the admin has it switched on, anyone may
+//! table a motion to make one of the timelock's `Change`s (fee rates, the
+//! grace period, resolvers…) and KALE holders vote by locking tokens for
+//! or against it. A motion carries when the locked votes reach the quorum
+//! and more are for it than against; it can then be executed by anyone
+//! once voting has closed and the timelock delay has passed too. Votes
+//! are unlocked with `withdraw_vote` after voting closes.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::events;
+use crate::timelock::{self, Change};
+use crate::{
+    checked_add, pay, pull, require_admin, require_live, token_client, DataKey, Error,
+    KalePrediction, KalePredictionArgs, KalePredictionClient,
+};
+
+/// Votes needed for a motion to count, and how long voting runs.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct GovernanceConfig {
+    pub quorum: i128,
+    pub voting_ledgers: u32,
+}
+
+/// A change put to a vote.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Motion {
+    pub change: Change,
+    pub closes: u32, // last ledger votes are taken
+    pub yes: i128,
+    pub no: i128,
+    pub executed: bool,
+}
+
+/// Tokens a voter has locked on a motion, and which way.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Ballot {
+    pub support: bool,
+    pub amount: i128,
+}
+
+fn load_motion(e: &Env, id: u32) -> Motion {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Motion(id))
+        .unwrap_or_else(|| panic_with_error!(e, Error::NoProposal))
+}
+
+fn save_motion(e: &Env, id: u32, motion: &Motion) {
+    e.storage().persistent().set(&DataKey::Motion(id), motion);
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Let token holders vote on changes under `config`, or stop taking
+    /// new motions with `None` (motions already tabled run their course).
+    pub fn set_governance(env: Env, admin: Address, config: Option<GovernanceConfig>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        match config {
+            Some(cfg) if cfg.quorum <= 0 => panic_with_error!(env, Error::ZeroAmount),
+            Some(cfg) => env.storage().instance().set(&DataKey::Governance, &cfg),
+            None => env.storage().instance().remove(&DataKey::Governance),
+        }
+    }
+
+    /// Table a motion to make `change`. Evented as `("motion", id) →
+    /// (change, closes)`. Returns the motion's id.
+    pub fn propose_change(env: Env, proposer: Address, change: Change) -> u32 {
+        require_live(&env);
+        proposer.require_auth();
+        let cfg: GovernanceConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Governance)
+            .unwrap_or_else(|| panic_with_error!(env, Error::WrongMarket));
+
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextMotionId)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextMotionId, &(id + 1));
+        let closes = env.ledger().sequence().saturating_add(cfg.voting_ledgers);
+        save_motion(
+            &env,
+            id,
+            &Motion {
+                change: change.clone(),
+                closes,
+                yes: 0,
+                no: 0,
+                executed: false,
+            },
+        );
+        events::publish(&env, "motion", (id,), (change, closes));
+        id
+    }
+
+    /// Lock `amount` of the deployment's token for (`support`) or against
+    /// a motion while voting is open. A voter may add to their vote but
+    /// not switch sides (`InvalidSide`).
+    pub fn vote(env: Env, voter: Address, motion_id: u32, support: bool, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        voter.require_auth();
+
+        let mut motion = load_motion(&env, motion_id);
+        if env.ledger().sequence() > motion.closes {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let key = DataKey::Ballot(motion_id, voter.clone());
+        let held = match env.storage().persistent().get::<DataKey, Ballot>(&key) {
+            Some(b) if b.support != support => panic_with_error!(env, Error::InvalidSide),
+            Some(b) => b.amount,
+            None => 0,
+        };
+
+        pull(&env, &voter, amount);
+        if support {
+            motion.yes = checked_add(&env, motion.yes, amount);
+        } else {
+            motion.no = checked_add(&env, motion.no, amount);
+        }
+        save_motion(&env, motion_id, &motion);
+        env.storage().persistent().set(
+            &key,
+            &Ballot {
+                support,
+                amount: checked_add(&env, held, amount),
+            },
+        );
+    }
+
+    /// Make a carried motion's change, once voting has closed and the
+    /// timelock delay has passed. Anyone may call this. Fails with
+    /// `ProposalDisputed` if the motion missed the quorum or was voted
+    /// down.
+    pub fn execute_motion(env: Env, motion_id: u32) {
+        require_live(&env);
+        let mut motion = load_motion(&env, motion_id);
+        if motion.executed {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+        if env.ledger().sequence() <= motion.closes.saturating_add(timelock::delay(&env)) {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        let cfg: GovernanceConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Governance)
+            .unwrap_or_else(|| panic_with_error!(env, Error::WrongMarket));
+        if checked_add(&env, motion.yes, motion.no) < cfg.quorum || motion.yes <= motion.no {
+            panic_with_error!(env, Error::ProposalDisputed);
+        }
+
+        motion.executed = true;
+        save_motion(&env, motion_id, &motion);
+        timelock::apply(&env, motion.change);
+        events::publish(&env, "carried", (motion_id,), motion.yes);
+    }
+
+    /// Unlock `voter`'s tokens once voting on the motion has closed.
+    /// Returns the amount.
+    pub fn withdraw_vote(env: Env, voter: Address, motion_id: u32) -> i128 {
+        require_live(&env);
+        voter.require_auth();
+        let motion = load_motion(&env, motion_id);
+        if env.ledger().sequence() <= motion.closes {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        let key = DataKey::Ballot(motion_id, voter.clone());
+        let ballot: Ballot = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&key);
+        pay(&env, &token_client(&env), &voter, ballot.amount);
+        ballot.amount
+    }
+
+    /// The governance settings, if token holders may vote.
+    pub fn get_governance(env: Env) -> Option<GovernanceConfig> {
+        env.storage().instance().get(&DataKey::Governance)
+    }
+
+    /// A motion and its tally.
+    pub fn get_motion(env: Env, motion_id: u32) -> Motion {
+        load_motion(&env, motion_id)
+    }
+
+    /// `voter`'s locked vote on a motion, if any.
+    pub fn get_ballot(env: Env, motion_id: u32, voter: Address) -> Option<Ballot> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Ballot(motion_id, voter))
+    }
+}
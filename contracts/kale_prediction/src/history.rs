@@ -0,0 +1,56 @@
+//! Betting history. Every round a player takes a position in is listed
+//! under their address, oldest first, in pages of `PAGE_SIZE`, so wallets
+//! can show it with `get_player_rounds` instead of scanning every round.
+
+use soroban_sdk::{contractimpl, Address, Env, Vec};
+
+use crate::{DataKey, KalePrediction, KalePredictionArgs, KalePredictionClient};
+
+/// Round ids per page of a player's history.
+pub(crate) const PAGE_SIZE: u32 = 50;
+
+fn round_count(e: &Env, player: &Address) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::PlayerRoundCount(player.clone()))
+        .unwrap_or(0)
+}
+
+fn page(e: &Env, player: &Address, page: u32) -> Vec<u32> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::PlayerRounds(player.clone(), page))
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+/// List `round_id` in `player`'s history, unless the latest page already
+/// has it (a position closed and reopened).
+pub(crate) fn record(e: &Env, player: &Address, round_id: u32) {
+    let count = round_count(e, player);
+    let index = count / PAGE_SIZE;
+    let mut rounds = page(e, player, index);
+    if rounds.contains(round_id) {
+        return;
+    }
+    rounds.push_back(round_id);
+    e.storage()
+        .persistent()
+        .set(&DataKey::PlayerRounds(player.clone(), index), &rounds);
+    e.storage()
+        .persistent()
+        .set(&DataKey::PlayerRoundCount(player.clone()), &(count + 1));
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Page `page` (from 0) of the rounds `player` has bet on, oldest
+    /// first; empty past the last one.
+    pub fn get_player_rounds(env: Env, player: Address, page: u32) -> Vec<u32> {
+        self::page(&env, &player, page)
+    }
+
+    /// How many rounds `player` has bet on.
+    pub fn get_player_round_count(env: Env, player: Address) -> u32 {
+        round_count(&env, &player)
+    }
+}
@@ -0,0 +1,219 @@
+//! Bet insurance. While the admin offers terms, an over/under bettor may
+//! pay a premium on top of their stake for cover: a fraction of the stake
+//! paid back out of the insurance reserve if their side loses. Premiums
+//! go to the reserve, and a policy is refused when the reserve could no
+//! longer pay every open policy at once. Pushes, voids and expiries return
+//! the stake through `claim` and the cover lapses. An insured stake cannot
+//! be reduced, cashed out, transferred or hedged, and a hedged one cannot
+//! be insured (`WrongMarket`): the hedge would win what the cover pays for.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::events;
+use crate::state::load_round_advanced;
+use crate::{
+    checked_add, compliance, hold_round, in_default_token, load_round, load_stake, mul_div, pay,
+    place_bet, pull, release_round, require_admin, require_live, require_min_bet, require_paying,
+    token_client, DataKey, Error, KalePrediction, KalePredictionArgs, KalePredictionClient, Side,
+};
+
+/// What insurance costs and covers, in basis points of the stake.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct InsuranceTerms {
+    pub premium_bps: u32,
+    pub cover_bps: u32,
+}
+
+/// The insurance reserve. `exposure` is the cover of every open policy,
+/// and is never allowed above `assets`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Reserve {
+    pub assets: i128,
+    pub exposure: i128,
+}
+
+/// Cover held on a player's stake in a round.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Policy {
+    pub side: Side,
+    pub cover: i128, // paid if `side` loses
+}
+
+fn load_reserve(e: &Env) -> Reserve {
+    e.storage()
+        .instance()
+        .get(&DataKey::InsuranceReserve)
+        .unwrap_or_default()
+}
+
+fn save_reserve(e: &Env, reserve: &Reserve) {
+    e.storage()
+        .instance()
+        .set(&DataKey::InsuranceReserve, reserve);
+}
+
+/// Whether `player`'s stake in the round carries cover.
+pub(crate) fn is_insured(e: &Env, round_id: u32, player: &Address) -> bool {
+    e.storage()
+        .persistent()
+        .has(&DataKey::Policy(round_id, player.clone()))
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Offer insurance on these terms, or stop selling it with `None`
+    /// (policies already sold stand). Both rates must be in 1..=10 000.
+    pub fn set_insurance_terms(env: Env, admin: Address, terms: Option<InsuranceTerms>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        match terms {
+            Some(t)
+                if !(1..=10_000).contains(&t.premium_bps)
+                    || !(1..=10_000).contains(&t.cover_bps) =>
+            {
+                panic_with_error!(env, Error::InvalidFee)
+            }
+            Some(t) => env.storage().instance().set(&DataKey::InsuranceTerms, &t),
+            None => env.storage().instance().remove(&DataKey::InsuranceTerms),
+        }
+    }
+
+    /// Add `amount` to the insurance reserve.
+    pub fn fund_insurance(env: Env, from: Address, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        from.require_auth();
+        pull(&env, &from, amount);
+        let mut reserve = load_reserve(&env);
+        reserve.assets = checked_add(&env, reserve.assets, amount);
+        save_reserve(&env, &reserve);
+    }
+
+    /// Take `amount` out of the reserve, as far as open policies do not
+    /// need it.
+    pub fn withdraw_insurance(env: Env, admin: Address, amount: i128) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        let mut reserve = load_reserve(&env);
+        let assets = checked_add(&env, reserve.assets, -amount);
+        if assets < reserve.exposure {
+            panic_with_error!(env, Error::InsufficientLiquidity);
+        }
+        reserve.assets = assets;
+        save_reserve(&env, &reserve);
+        pay(&env, &token_client(&env), &admin, amount);
+    }
+
+    /// `bet` on `Higher` or `Lower` with cover, paying the premium on top
+    /// of `amount`. Fails with `InsufficientLiquidity` if the reserve
+    /// could not pay the cover, and with `WrongMarket` if the stake is
+    /// hedged.
+    pub fn bet_insured(env: Env, player: Address, round_id: u32, side: Side, amount: i128) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+
+        let terms: InsuranceTerms = env
+            .storage()
+            .instance()
+            .get(&DataKey::InsuranceTerms)
+            .unwrap_or_else(|| panic_with_error!(env, Error::WrongMarket));
+        if !matches!(side, Side::Higher | Side::Lower) {
+            panic_with_error!(env, Error::InvalidSide);
+        }
+        // the reserve is in the deployment's token
+        if !in_default_token(&env, &load_round(&env, round_id)) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        if load_stake(&env, round_id, &player).is_some_and(|s| s.hedge > 0 || s.side != side) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        let key = DataKey::Policy(round_id, player.clone());
+        let held = match env.storage().persistent().get::<DataKey, Policy>(&key) {
+            Some(p) if p.side != side => panic_with_error!(env, Error::InvalidSide),
+            Some(p) => p.cover,
+            None => 0,
+        };
+
+        let premium = mul_div(&env, amount, i128::from(terms.premium_bps), 10_000);
+        let cover = mul_div(&env, amount, i128::from(terms.cover_bps), 10_000);
+        let mut reserve = load_reserve(&env);
+        reserve.assets = checked_add(&env, reserve.assets, premium);
+        reserve.exposure = checked_add(&env, reserve.exposure, cover);
+        if reserve.exposure > reserve.assets {
+            panic_with_error!(env, Error::InsufficientLiquidity);
+        }
+
+        place_bet(&env, Some(&player), &player, round_id, side, amount);
+        pull(&env, &player, premium);
+        save_reserve(&env, &reserve);
+        if !is_insured(&env, round_id, &player) {
+            hold_round(&env, round_id);
+        }
+        env.storage().persistent().set(
+            &key,
+            &Policy {
+                side,
+                cover: checked_add(&env, held, cover),
+            },
+        );
+    }
+
+    /// Settle `player`'s policy on a resolved round: the cover is paid if
+    /// their side lost, and released back to the reserve otherwise.
+    /// Anyone may call it. Returns what was paid.
+    pub fn settle_insurance(env: Env, player: Address, round_id: u32) -> i128 {
+        require_live(&env);
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        compliance::require_unfrozen(&env, &player);
+
+        let key = DataKey::Policy(round_id, player.clone());
+        let policy: Policy = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&key);
+        release_round(&env, round_id);
+
+        let paid = match round.outcome.winner() {
+            Some(side) if side != policy.side => policy.cover,
+            _ => 0,
+        };
+        let mut reserve = load_reserve(&env);
+        reserve.exposure = checked_add(&env, reserve.exposure, -policy.cover);
+        reserve.assets = checked_add(&env, reserve.assets, -paid);
+        save_reserve(&env, &reserve);
+        if paid > 0 {
+            pay(&env, &token_client(&env), &player, paid);
+            events::publish(&env, "insured", (player,), (round_id, paid));
+        }
+        paid
+    }
+
+    /// The insurance terms on offer, if any.
+    pub fn get_insurance_terms(env: Env) -> Option<InsuranceTerms> {
+        env.storage().instance().get(&DataKey::InsuranceTerms)
+    }
+
+    /// The insurance reserve and the cover it stands behind.
+    pub fn get_insurance_reserve(env: Env) -> Reserve {
+        load_reserve(&env)
+    }
+
+    /// `player`'s cover in a round, if any.
+    pub fn get_policy(env: Env, round_id: u32, player: Address) -> Option<Policy> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Policy(round_id, player))
+    }
+}
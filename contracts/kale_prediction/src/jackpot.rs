@@ -0,0 +1,212 @@
+//! Exact‑count jackpot riding alongside an over/under count round. Tickets
+//! name the exact number of invocations the round will see; whoever hits
+//! it splits the jackpot pro rata. Unwon jackpots (and the carried part of
+//! a voided round's) roll over into the next count round once
+//! `roll_jackpot` is called.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::state::load_round_advanced;
+use crate::{
+    check_risk, checked_add, compliance, in_default_token, load_round, mul_div, pay, pull,
+    require_live, require_min_bet, require_paying, token_client, DataKey, Error, KalePrediction,
+    KalePredictionArgs, KalePredictionClient, Market, Outcome, Round, RoundState,
+};
+
+/// A round's jackpot.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Jackpot {
+    pub carried: i128, // rolled over from earlier rounds
+    pub staked: i128,  // this round's tickets
+    pub rolled: bool,  // unwon remainder handed to the next round
+    pub claimed: i128, // tickets paid out on (hits, or every ticket if voided)
+}
+
+#[contracttype]
+#[derive(Clone, Copy)]
+pub struct JackpotTicket {
+    pub count: u32,
+    pub amount: i128,
+}
+
+fn load_jackpot(e: &Env, round_id: u32) -> Jackpot {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Jackpot(round_id))
+        .unwrap_or_default()
+}
+
+fn save_jackpot(e: &Env, round_id: u32, jackpot: &Jackpot) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::Jackpot(round_id), jackpot);
+}
+
+fn picks(e: &Env, round_id: u32, count: u32) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::JackpotPick(round_id, count))
+        .unwrap_or(0)
+}
+
+/// Seed a new count round's jackpot with whatever is waiting to roll over.
+pub(crate) fn carry_over(e: &Env, round_id: u32) {
+    let rollover: i128 = e.storage().instance().get(&DataKey::Rollover).unwrap_or(0);
+    if rollover > 0 {
+        e.storage().instance().remove(&DataKey::Rollover);
+        let jackpot = Jackpot {
+            carried: rollover,
+            ..Jackpot::default()
+        };
+        save_jackpot(e, round_id, &jackpot);
+    }
+}
+
+/// Whether the round has a jackpot still owing tickets, or with an unwon
+/// remainder that has not rolled over yet.
+pub(crate) fn is_pending(e: &Env, round_id: u32, round: &Round) -> bool {
+    let Some(jackpot) = e
+        .storage()
+        .persistent()
+        .get::<DataKey, Jackpot>(&DataKey::Jackpot(round_id))
+    else {
+        return false;
+    };
+    let owed = if counted(round) {
+        picks(e, round_id, round.actual_count)
+    } else {
+        jackpot.staked
+    };
+    jackpot.claimed < owed || (!jackpot.rolled && unwon(e, round_id, round, &jackpot) > 0)
+}
+
+/// Whether the round was decided on a count (rather than voided/expired).
+fn counted(round: &Round) -> bool {
+    !matches!(round.outcome, Outcome::Voided | Outcome::Expired)
+}
+
+/// What no ticket won and rolls over: with a voided or expired round,
+/// only what it had carried in.
+fn unwon(e: &Env, round_id: u32, round: &Round, jackpot: &Jackpot) -> i128 {
+    if !counted(round) {
+        jackpot.carried
+    } else if picks(e, round_id, round.actual_count) == 0 {
+        checked_add(e, jackpot.carried, jackpot.staked)
+    } else {
+        0
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Buy (or top up) a jackpot ticket on `count` invocations — the same
+    /// delta the round is decided on — while the round takes bets.
+    pub fn bet_jackpot(env: Env, player: Address, round_id: u32, count: u32, amount: i128) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+
+        let round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        if round.market != Market::Invocations || !in_default_token(&env, &round) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        let ticket_key = DataKey::JackpotTicket(round_id, player.clone());
+        let held = match env
+            .storage()
+            .persistent()
+            .get::<DataKey, JackpotTicket>(&ticket_key)
+        {
+            Some(t) if t.count != count => panic_with_error!(env, Error::InvalidSide),
+            Some(t) => t.amount,
+            None => 0,
+        };
+        check_risk(&env, &player, round_id, amount);
+
+        pull(&env, &player, amount);
+        env.storage().persistent().set(
+            &ticket_key,
+            &JackpotTicket {
+                count,
+                amount: checked_add(&env, held, amount),
+            },
+        );
+        env.storage().persistent().set(
+            &DataKey::JackpotPick(round_id, count),
+            &checked_add(&env, picks(&env, round_id, count), amount),
+        );
+        let mut jackpot = load_jackpot(&env, round_id);
+        jackpot.staked = checked_add(&env, jackpot.staked, amount);
+        save_jackpot(&env, round_id, &jackpot);
+    }
+
+    /// Collect a hit's share of the jackpot, or the ticket back if the
+    /// round was voided or expired. A miss pays nothing.
+    pub fn claim_jackpot(env: Env, player: Address, round_id: u32) {
+        require_live(&env);
+        player.require_auth();
+        compliance::require_unfrozen(&env, &player);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let ticket_key = DataKey::JackpotTicket(round_id, player.clone());
+        let ticket: JackpotTicket = env
+            .storage()
+            .persistent()
+            .get(&ticket_key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&ticket_key);
+
+        let mut jackpot = load_jackpot(&env, round_id);
+        let payout = if !counted(&round) {
+            ticket.amount
+        } else if ticket.count == round.actual_count {
+            mul_div(
+                &env,
+                ticket.amount,
+                checked_add(&env, jackpot.carried, jackpot.staked),
+                picks(&env, round_id, ticket.count),
+            )
+        } else {
+            return;
+        };
+        jackpot.claimed = checked_add(&env, jackpot.claimed, ticket.amount);
+        save_jackpot(&env, round_id, &jackpot);
+        pay(&env, &token_client(&env), &player, payout);
+    }
+
+    /// Hand a settled round's unwon jackpot on to the next count round
+    /// (with a voided or expired round, only what it had carried in).
+    /// Anyone may call this.
+    pub fn roll_jackpot(env: Env, round_id: u32) {
+        require_live(&env);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let mut jackpot = load_jackpot(&env, round_id);
+        let unwon = unwon(&env, round_id, &round, &jackpot);
+        if jackpot.rolled || unwon == 0 {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+
+        jackpot.rolled = true;
+        save_jackpot(&env, round_id, &jackpot);
+        let rollover: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::Rollover)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::Rollover, &checked_add(&env, rollover, unwon));
+    }
+
+    /// A round's jackpot so far (all zero if it has none).
+    pub fn get_jackpot(env: Env, round_id: u32) -> Jackpot {
+        load_round(&env, round_id);
+        load_jackpot(&env, round_id)
+    }
+}
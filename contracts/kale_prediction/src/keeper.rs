@@ -0,0 +1,121 @@
+//! Keeper hook: `tick` walks the unsettled rounds a batch at a time and
+//! applies whatever is due, so a generic keeper bot can run the market on a timer
+//! without knowing its rules. `extend_instance_ttl` keeps a quiet
+//! deployment's instance storage from expiring between rounds.
+
+use soroban_sdk::{contractimpl, Env};
+
+use crate::conditions;
+use crate::price::{fresh_price, settle_price};
+use crate::state::advance;
+use crate::{
+    extend_instance, finish_resolution, require_live, save_round, try_oracle_count, DataKey,
+    KalePrediction, KalePredictionArgs, KalePredictionClient, Market, Round, RoundState,
+};
+
+/// Most rounds a single `tick` looks at.
+const TICK_BATCH: u32 = 8;
+
+/// Resolve a locked round from its oracle when that is open to anyone:
+/// price rounds from finality, count rounds once the admin's window is
+/// over. Missing, stale or diverging data, or a precondition still pending,
+/// leaves the round for later.
+fn resolve_due(e: &Env, round_id: u32, round: &mut Round) {
+    if !round.finality.reached(e) || conditions::check(e, round_id, round).is_none() {
+        return;
+    }
+    match round.market.clone() {
+        Market::Price(feed) => {
+            if let Some(price) = fresh_price(e, &feed) {
+                settle_price(e, round_id, round, &feed, price);
+            }
+        }
+        Market::Invocations | Market::Buckets(_) | Market::Scalar | Market::Ladder(_) => {
+            if !round
+                .finality
+                .plus(round.rules.public_resolve_ledgers)
+                .passed(e)
+            {
+                return;
+            }
+            if let Some(Ok(count)) = try_oracle_count(e, round.rules.metric) {
+                if count >= round.baseline_count {
+                    finish_resolution(e, round_id, round, count);
+                }
+            }
+        }
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Apply every due move to up to `TICK_BATCH` unsettled rounds: close
+    /// betting, resolve from the oracle where anyone may, expire, open
+    /// claims. Each call picks up where the last one stopped and wraps
+    /// back to the oldest unsettled round, so a long‑dated round does not
+    /// hold back the ones after it. No keeper fee is paid; calling it when
+    /// nothing is due changes nothing. Returns how many rounds moved.
+    pub fn tick(env: Env) -> u32 {
+        require_live(&env);
+        extend_instance(&env);
+        let next_id: u32 = env.storage().instance().get(&DataKey::NextRoundId).unwrap();
+        let start: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TickCursor)
+            .unwrap_or(0);
+        let mut scan: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TickScan)
+            .unwrap_or(start)
+            .max(start);
+
+        let mut cursor = start;
+        let mut moved = 0;
+        for _ in 0..TICK_BATCH.min(next_id - start) {
+            if scan >= next_id {
+                scan = cursor;
+            }
+            let round_id = scan;
+            scan += 1;
+            let Some(mut round) = env
+                .storage()
+                .persistent()
+                .get::<DataKey, Round>(&DataKey::Round(round_id))
+            else {
+                // pruned
+                if cursor == round_id {
+                    cursor += 1;
+                }
+                continue;
+            };
+            let from = round.state;
+            advance(&env, round_id, &mut round, true);
+            if round.state == RoundState::Locked {
+                resolve_due(&env, round_id, &mut round);
+            }
+            if round.state != from {
+                save_round(&env, round_id, &round);
+                moved += 1;
+            }
+            // settled rounds only wait on claims; skip them from now on
+            if cursor == round_id
+                && matches!(round.state, RoundState::Settled | RoundState::Archived)
+            {
+                cursor += 1;
+            }
+        }
+        if cursor != start {
+            env.storage().instance().set(&DataKey::TickCursor, &cursor);
+        }
+        env.storage().instance().set(&DataKey::TickScan, &scan);
+        moved
+    }
+
+    /// Extend the instance storage's TTL (admin, token, counters) back to
+    /// `ENTRY_TTL_LEDGERS` once it is running low. Anyone may call this.
+    pub fn extend_instance_ttl(env: Env) {
+        extend_instance(&env);
+    }
+}
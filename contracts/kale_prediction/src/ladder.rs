@@ -0,0 +1,109 @@
+//! Ladder rounds: an over/under on each of several lines ("rungs") in one
+//! round, so a frontend can show an odds ladder. Each rung has its own
+//! pools and is decided on its own line from the round's single count —
+//! its winning side splits that rung's pot, and a rung on its line (per the
+//! tie policy) or with nobody on the winning side hands its stakes back.
+//! Bet with `Side::Over(i)` / `Side::Under(i)`; as in any round, a player
+//! holds one position. Ladder rounds charge no protocol fee.
+
+use core::cmp::Ordering;
+
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Vec};
+
+use crate::{
+    against_line, create_round, default_rules, mul_div, pool, Deadline, Error, KalePrediction,
+    KalePredictionArgs, KalePredictionClient, Market, Outcome, Round, Side, Stake, TiePolicy,
+};
+
+/// Most rungs a round may have.
+pub const MAX_RUNGS: u32 = 8;
+
+/// Index in `bucket_pools` of a rung side's pool: rung `i` keeps its
+/// under pool at `2i` and its over pool at `2i + 1`.
+pub(crate) fn slot(side: Side) -> u32 {
+    match side {
+        Side::Under(i) => 2 * i,
+        Side::Over(i) => 2 * i + 1,
+        _ => unreachable!(),
+    }
+}
+
+/// Every rung is decided when it is claimed on.
+pub(crate) fn decide(round: &mut Round) {
+    round.outcome = Outcome::Laddered;
+    round.protocol_fee = 0;
+}
+
+/// The winning side of `rung`, or `None` if its stakes are returned.
+fn rung_winner(round: &Round, rung: u32) -> Option<Side> {
+    let Market::Ladder(lines) = &round.market else {
+        return None;
+    };
+    let result = against_line(
+        i128::from(round.actual_count),
+        i128::from(lines.get_unchecked(rung)),
+        round.rules.spread,
+    );
+    let winner = match (result, round.rules.tie_policy) {
+        (Ordering::Greater, _) | (Ordering::Equal, TiePolicy::Higher) => Side::Over(rung),
+        (Ordering::Less, _) | (Ordering::Equal, TiePolicy::Lower) => Side::Under(rung),
+        (Ordering::Equal, TiePolicy::Push) => return None,
+    };
+    (pool(round, winner) > 0).then_some(winner)
+}
+
+/// `stake`'s payout from its rung, scaled down by any bounty taken from
+/// the round's pot.
+pub(crate) fn share(
+    e: &Env,
+    round: &Round,
+    stake: &Stake,
+    payable_pool: i128,
+    total_pool: i128,
+) -> i128 {
+    let (Side::Over(rung) | Side::Under(rung)) = stake.side else {
+        return 0;
+    };
+    let won = match rung_winner(round, rung) {
+        Some(side) if side != stake.side => return 0,
+        Some(side) => {
+            let rung_pot = pool(round, Side::Over(rung)) + pool(round, Side::Under(rung));
+            mul_div(e, stake.amount, rung_pot, pool(round, side))
+        }
+        None => stake.amount,
+    };
+    mul_div(e, won, payable_pool, total_pool)
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Start a ladder round on the invocations from now to finality, with
+    /// the default rules. `lines` must be strictly increasing, with between
+    /// two and `MAX_RUNGS` rungs.
+    pub fn start_ladder_round(
+        env: Env,
+        admin: Address,
+        lines: Vec<u32>,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        let increasing = lines
+            .iter()
+            .zip(lines.iter().skip(1))
+            .all(|(lower, upper)| lower < upper);
+        if lines.len() < 2 || lines.len() > MAX_RUNGS || !increasing {
+            panic_with_error!(env, Error::InvalidBuckets);
+        }
+
+        let rules = default_rules(&env);
+        create_round(
+            &env,
+            &admin,
+            Market::Ladder(lines),
+            0,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        )
+    }
+}
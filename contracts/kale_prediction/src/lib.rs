@@ -2,18 +2,129 @@
 //! Kale‑Prediction — over/under prediction‑market for **Kale‑contract
 //! invocation counts**.
 //!
-//! * One active round at a time (fits hackathon scope).
+//! * Rounds run side by side unless the admin caps how many may be
+//!   unresolved at once (`set_max_open_rounds`; a cap of 1 keeps to one
+//!   active round at a time).
 //! * Bets are placed in a **SEP‑41 token** chosen at deployment (e.g. KALE).
 //! * Losers lose their stake; winners split the total pot proportionally.
-//! * If the admin never resolves, participants can refund after a grace
-//!   period.
+//!   By default an exact hit on the line is a **push**: every stake is
+//!   returned. Windows, keeper fee and tie policy are per‑round `Rules`.
+//! * If the admin never resolves, anyone may settle the round from the
+//!   configured count oracle (earning a small bounty); failing that, the
+//!   round expires after a grace period and `claim` returns every stake.
+//! * Count rounds may follow KALE harvest volume or total stake instead of
+//!   invocations (`Rules::metric`).
+//! * Price rounds run the same over/under on a SEP‑40 price feed; bucket
+//!   rounds instead split the count into up to eight ranges to bet on, and
+//!   scalar rounds pay exact guesses by how close they came.
+//! * Ladder rounds run an over/under on several lines at once, each rung
+//!   with its own pools, all settled on one count (see `ladder`).
+//! * Results can also come from a signed off‑chain attestation or a bonded
+//!   optimistic proposal (see `optimistic`).
+//! * Once the admin publishes creator terms, anyone may start a round
+//!   against a bond that a cancellation forfeits (see `creators`).
+//! * Head‑to‑head rounds pit two addresses against each other for a fixed
+//!   stake (see `duels`).
+//! * A round can be made conditional on another round's outcome or a
+//!   minimum pot, and voids at resolution if that fails (see `conditions`).
+//! * Fixed‑odds rounds pay locked‑in odds out of a house vault instead of
+//!   splitting the pot (see `vault`).
+//! * LMSR rounds quote a moving price from an automated market maker
+//!   (see `lmsr`).
+//! * Collateral splits into transferable HIGH/LOW share sets that redeem
+//!   after resolution (see `sets`).
+//! * Over/under stakes can be cashed out before finality at a price set by
+//!   the pools (see `cashout`).
+//! * Over/under bettors may buy cover that pays part of a losing stake
+//!   back out of an insurance reserve (see `insurance`).
+//! * Count rounds carry an optional exact‑count jackpot that rolls over
+//!   until someone hits it (see `jackpot`).
+//! * Best‑of‑N series bets ride on how a run of rounds turns out (see
+//!   `series`).
+//! * An epoch schedule can chain rounds so each one starts its successor
+//!   (see `epochs`).
+//! * Seasons score players across the rounds they take in and pay a
+//!   sponsored prize pool to the top of the leaderboard (see `seasons`).
+//! * Sealed rounds take commit‑reveal bets that hide which side the money
+//!   is on until betting closes (see `sealed`).
+//! * Players may let an operator (a bot) bet from a deposited balance,
+//!   within a per‑round limit (see `operators`).
+//! * Bets may name a referrer, who earns a share of the protocol fee on
+//!   the volume they bring in (see `referrals`).
+//! * The admin may hand resolving, fee tuning and pausing to separate
+//!   keys (see `roles`).
+//! * The admin can upgrade the code in place and migrate storage to the
+//!   new layout (see `upgrade`).
+//! * Sensitive admin changes can be held behind a timelock so users can
+//!   leave before they apply (see `timelock`).
+//! * KALE holders can vote on such changes by locking tokens (see
+//!   `governance`).
+//! * Accounts can be blocked from betting, with their stakes frozen if
+//!   need be, and rounds made private to an allowlist (see `compliance`).
+//! * Each player's betting history is indexed for wallets (see
+//!   `history`).
+//! * Fast‑turnover rounds can keep their stakes in cheaper temporary
+//!   storage until their claims close (`set_short_lived`).
+//! * Per‑side pools, bettors, implied odds and payout multipliers are
+//!   available in one view, as are players' lifetime records (see
+//!   `stats`).
+//! * Every event follows one versioned schema (see `events`).
+//! * A permissionless `tick` applies whatever is due (locking, oracle
+//!   resolution, expiry, claims) for generic keeper bots.
+//!
+//! Stakes may be in any SEP‑41 token, native XLM included through its
+//! Stellar Asset Contract; amounts are always in the token's minor units
+//! (stroops for XLM). See `get_token_info`. Over/under rounds may also be
+//! staked in another admin‑approved token (`start_round_in`).
 //!
 //! Built against **soroban‑sdk 22.0.x**.
 
+use core::cmp::Ordering;
+
 use soroban_sdk::{
-    contract, contracterror, contractimpl, contracttype, panic_with_error, token, Address, Env,
+    contract, contracterror, contractimpl, contracttype, panic_with_error, token, xdr::ToXdr,
+    Address, Bytes, BytesN, Env, Map, Symbol, Vec, I256,
 };
 
+pub mod buckets;
+mod cashout;
+pub mod compliance;
+pub mod conditions;
+pub mod creators;
+pub mod duels;
+pub mod epochs;
+pub mod events;
+pub mod governance;
+mod history;
+pub mod insurance;
+pub mod jackpot;
+mod keeper;
+pub mod ladder;
+pub mod lmsr;
+pub mod operators;
+pub mod optimistic;
+pub mod oracle;
+mod price;
+pub mod referrals;
+pub mod roles;
+pub mod scalar;
+pub mod sealed;
+pub mod seasons;
+pub mod series;
+pub mod sets;
+mod state;
+pub mod stats;
+pub mod templates;
+pub mod timelock;
+mod upgrade;
+pub mod vault;
+
+use oracle::{Asset, CountOracleClient, FarmEpochsClient, RiskOracleClient};
+use roles::{require_role, Role};
+pub use state::RoundState;
+use state::{advance, load_round_advanced, transition};
+use timelock::Fee;
+
 // ──────────────────────────────────────────────────────────────────────────
 // Storage keys
 // ──────────────────────────────────────────────────────────────────────────
@@ -21,44 +132,443 @@ use soroban_sdk::{
 #[contracttype]
 enum DataKey {
     Admin,
-    Token,               // KALE token contract address
-    NextRoundId,         // u32 counter
-    Round(u32),          // Round data
-    Stake(u32, Address), // bettor stakes
+    Role(Role, Address),              // bool: account holds an operational role
+    Paused,                           // bool: new funds are refused
+    Blocked(Address),                 // bool: account may not bet; stakes frozen too
+    Allowlist(u32),                   // RoundAllowlist: who may bet on a private round
+    Token,                            // KALE token contract address
+    Tokens,                           // Vec<Address>: other tokens rounds may use
+    Oracle,                           // invocation‑count oracle address
+    Feeds,                            // FeedSet: several count oracles, read by median
+    Farm,                             // Kale farm epoch schedule address
+    Attestor,                         // BytesN<32>: ed25519 key signing counts off‑chain
+    Risk,                             // RiskConfig for the bet acceptance hook
+    BetCaps,                          // BetCaps: stake limits per player and round
+    Exposure(Address),                // Map<u32, i128>: stakes in unresolved rounds
+    Config,                           // Config: deployment‑wide tunables
+    MinBet,                           // pre‑v2 only, now in `Config`
+    ReferralBps,                      // u32: referrers' share of the protocol fee
+    Committee,                        // M‑of‑N resolver committee
+    OpenRounds,                       // u32: rounds not yet resolved or voided
+    MaxOpenRounds,                    // pre‑v2 only, now in `Config`
+    LastSettled,                      // u32: ledger the latest round was settled at
+    Sunset,                           // u32: ledger from which no new rounds start
+    Retired,                          // bool: funds handed to the successor
+    Version,                          // u32: storage layout version
+    TimelockDelay,                    // u32: ledgers a sensitive change waits
+    NextChangeId,                     // u32 counter for queued changes
+    QueuedChange(u32),                // QueuedChange awaiting its delay
+    Governance,                       // GovernanceConfig, while holders may vote
+    NextMotionId,                     // u32 counter for motions
+    Motion(u32),                      // Motion put to token holders
+    Ballot(u32, Address),             // Ballot: a voter's locked vote
+    KeeperFeeBps,                     // u32: default public resolver's cut of the pot
+    GraceLedgers,                     // pre‑v2 only, now in `Config`
+    ClaimWindow,                      // pre‑v2 only, now in `Config`
+    CancelFeeBps,                     // u32: admin's cut of stakes taken back early
+    ClaimFeeBps,                      // pre‑v2 only, now in `Config`
+    FeeCollector,                     // Address: receives the claim fee, admin if unset
+    AccruedFees(Address),             // i128: claim fees held, per token
+    Liabilities(Address),             // i128: held for others, per token
+    CashOutHaircutBps,                // u32: haircut on early cash‑outs
+    InsuranceTerms,                   // InsuranceTerms on offer, if any
+    InsuranceReserve,                 // Reserve backing bet insurance
+    NextRoundId,                      // u32 counter
+    Template(Symbol),                 // Template: named round preset
+    LiveRounds,                       // Map<u32, Deadline>: rounds taking bets
+    TickCursor,                       // u32: oldest round `tick` has not seen settle
+    TickScan,                         // u32: round the next `tick` starts from
+    Round(u32),                       // Round data
+    Stake(u32, Address),              // bettor stakes
+    ShortLived(u32),                  // u32: ledger a round's temporary stakes last to
+    Bettors(u32),                     // Map<Side, u32>: players with money on each side
+    PlayerStats(Address),             // PlayerStats: a player's lifetime record
+    PlayerRoundCount(Address),        // u32: rounds a player has bet on
+    PlayerRounds(Address, u32),       // Vec<u32>: a page of those round ids
+    Policy(u32, Address),             // Policy: cover on a player's stake
+    Deposit(Address),                 // i128: a player's balance held for operators
+    Operator(Address),                // Operator a player has delegated betting to
+    OperatorSpent(u32, Address),      // i128: operator bets for a player in a round
+    Sealed(u32),                      // bool: bets are committed, then revealed
+    SealedEscrow(u32),                // i128: stakes committed and not yet revealed
+    SealedBet(u32, Address),          // SealedBet: a commitment awaiting reveal
+    Referrals(u32),                   // Map<Address, i128>: volume per referrer
+    ReferralRewards(Address),         // i128: credited to a referrer, unclaimed
+    Votes(u32),                       // committee votes: resolver → count
+    Optimistic,                       // OptimisticConfig: proposal bond and liveness
+    Proposal(u32),                    // pending optimistic proposal
+    Precondition(u32),                // Precondition a round voids without
+    MaxImbalance(u32),                // u32: largest side‑to‑side pool ratio, in bps
+    CreatorTerms,                     // CreatorTerms: bond and fee for anyone starting rounds
+    CreatorBond(u32),                 // CreatorBond escrowed by a round's creator
+    Duel(u32),                        // Duel: the two parties to a head‑to‑head round
+    Epochs,                           // EpochSchedule: rounds that start their successor
+    CurrentSeason,                    // u32: latest season started
+    Season(u32),                      // Season: prize pool and leaderboard
+    SeasonOf(u32),                    // u32: season a round counts towards
+    SeasonScore(u32, Address),        // SeasonScore: a player's season record
+    Rollover,                         // i128: unwon jackpot awaiting the next round
+    Jackpot(u32),                     // round's exact‑count side pool
+    JackpotPick(u32, u32),            // i128: tickets on one exact count
+    JackpotTicket(u32, Address),      // a player's jackpot ticket
+    NextSeriesId,                     // u32 counter
+    Series(u32),                      // best‑of‑N series over several rounds
+    SeriesBet(u32, Address),          // a player's series bet
+    Vault,                            // house liquidity backing fixed‑odds books
+    VaultShares(Address),             // i128: a provider's vault shares
+    Book(u32),                        // fixed‑odds round's book against the vault
+    FixedBet(u32, Address),           // a player's fixed‑odds bet
+    Amm(u32),                         // LMSR maker of an LMSR round
+    Holding(u32, Address),            // a player's LMSR shares
+    ShareBalance(u32, Side, Address), // i128: outcome shares from complete sets
+    ShareSupply(u32, Side),           // i128: a round's outcome shares of one side
+    Liens(u32),                       // u32: policies, series, rounds still needing a round
 }
 
 // ──────────────────────────────────────────────────────────────────────────
-// Config
+// Config (defaults for `Rules` unless noted)
 // ──────────────────────────────────────────────────────────────────────────
 
-/// Ledgers after `finality_ledger` before refunds become possible.
+/// Ledgers after `finality_ledger` before an unresolved round expires and
+/// refunds become possible, unless the admin sets another default.
 const GRACE_LEDGERS: u32 = 100;
 
+/// Longest grace period a round may have (about 7 days).
+const MAX_GRACE_LEDGERS: u32 = 120_960;
+
+/// Ledgers a round or stake entry outlives its last write (about 30 days)
+/// before archival, and the TTL left below which a read extends it again.
+/// The instance (admin, token, counters) is kept alive the same way.
+const ENTRY_TTL_LEDGERS: u32 = 518_400;
+const ENTRY_TTL_THRESHOLD: u32 = 120_960;
+
+/// Most unresolved rounds one player's exposure is tracked over.
+const MAX_EXPOSURE_ROUNDS: u32 = 20;
+
+/// Ledgers after `finality_ledger` before anyone may resolve from the oracle.
+const PUBLIC_RESOLVE_LEDGERS: u32 = 50;
+
+/// Ledgers after resolution during which the admin may still correct the
+/// count; claims open once this window has passed.
+const AMEND_WINDOW_LEDGERS: u32 = 20;
+
+/// Default share of the pot paid to whoever calls `public_resolve`
+/// (basis points); the admin may tune it up to `MAX_KEEPER_FEE_BPS`.
+const DEFAULT_KEEPER_FEE_BPS: u32 = 50;
+const MAX_KEEPER_FEE_BPS: u32 = 1_000;
+
+/// Nominal ledger close time, used to stretch `Rules` ledger windows over
+/// rounds scheduled by timestamp.
+const SECS_PER_LEDGER: u64 = 5;
+
+/// Most count oracles a `FeedSet` may hold.
+const MAX_FEEDS: u32 = 7;
+
+/// Upper bound for a round's protocol fee curve (basis points of winnings).
+const MAX_PROTOCOL_FEE_BPS: u32 = 1_000;
+
+/// Most tokens besides the deployment's own that rounds may be staked in.
+const MAX_TOKENS: u32 = 8;
+
+/// Most bets `bet_many` places in one call.
+const MAX_BET_LEGS: u32 = 10;
+
+/// Most rounds `claim_all` settles in one call.
+const MAX_CLAIM_ROUNDS: u32 = 20;
+
+/// Most players `distribute` pays in one call.
+const MAX_DISTRIBUTE_BATCH: u32 = 25;
+
+/// Upper bound for the cancellation fee on `reduce_bet` (basis points).
+const MAX_CANCEL_FEE_BPS: u32 = 500;
+
+/// Upper bound for the fee on claimed winnings (basis points).
+const MAX_CLAIM_FEE_BPS: u32 = 1_000;
+
+/// Timelock between scheduling a sunset and it taking effect (~1 day).
+const SUNSET_DELAY_LEDGERS: u32 = 17_280;
+
+/// Ledgers after the last settlement left for claims before the remaining
+/// balance may move to a successor (~1 week).
+const SUNSET_CLAIM_LEDGERS: u32 = 120_960;
+
 // ──────────────────────────────────────────────────────────────────────────
 // Types
 // ──────────────────────────────────────────────────────────────────────────
 
+/// What a stake is on: under/over the line, or one bucket of a bucket round.
 #[contracttype]
-#[derive(Clone, Copy, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum Side {
-    Lower = 0,
-    Higher = 1,
+    Lower,
+    Higher,
+    Bucket(u32),
+    Guess(u32), // scalar rounds: the predicted count
+    Over(u32),  // ladder rounds: above the rung's line
+    Under(u32), // ladder rounds: below it
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    Pending, // not decided yet
+    Lower,
+    Higher,
+    Push,        // on the line, or nobody backed the winning side
+    Voided,      // cancelled, or a side was empty when betting closed
+    Expired,     // unresolved past `finality + grace_ledgers`
+    Bucket(u32), // bucket rounds: index of the bucket the count fell in
+    Scored,      // scalar rounds: paid out by closeness of each guess
+    Laddered,    // ladder rounds: each rung decided on its own line
+}
+
+impl Side {
+    /// The other side of an over/under line; other markets' sides have none.
+    fn opposite(self) -> Option<Side> {
+        match self {
+            Side::Higher => Some(Side::Lower),
+            Side::Lower => Some(Side::Higher),
+            _ => None,
+        }
+    }
+}
+
+impl Outcome {
+    /// Side whose stakes share the pot, or `None` when stakes are returned.
+    fn winner(self) -> Option<Side> {
+        match self {
+            Outcome::Lower => Some(Side::Lower),
+            Outcome::Higher => Some(Side::Higher),
+            Outcome::Bucket(i) => Some(Side::Bucket(i)),
+            Outcome::Pending
+            | Outcome::Push
+            | Outcome::Voided
+            | Outcome::Expired
+            | Outcome::Scored
+            | Outcome::Laddered => None,
+        }
+    }
+}
+
+/// How a result on the line (or within the round's spread of it) is
+/// settled.
+#[contracttype]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum TiePolicy {
+    Lower = 0,  // the line counts as under
+    Higher = 1, // the line counts as over
+    Push = 2,   // every stake is returned
+}
+
+/// What a round is an over/under on.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Market {
+    Invocations,       // Kale invocations since `baseline_count`
+    Price(PriceFeed),  // an asset price from a SEP‑40 oracle
+    Buckets(Vec<u32>), // which range the invocations fall in; see `buckets`
+    Scalar,            // exact guesses paid by closeness; see `scalar`
+    Ladder(Vec<u32>),  // an over/under on each of several lines; see `ladder`
+}
+
+/// Kale statistic a count round (invocations, bucket or scalar) reads
+/// from its oracles, in whole units (KALE for volumes).
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Metric {
+    Invocations = 0,   // Kale‑contract invocations since the round opened
+    HarvestVolume = 1, // KALE harvested, idem
+    TotalStaked = 2,   // KALE staked at resolution (a level: no baseline)
+}
+
+/// Price line of a price round, in the oracle's `decimals()`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PriceFeed {
+    pub oracle: Address,
+    pub asset: Asset,
+    pub line: i128,
+}
+
+/// A point in a round's schedule: a ledger sequence, or a UNIX time checked
+/// against the ledger close time.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Deadline {
+    Ledger(u32),
+    Timestamp(u64),
+}
+
+impl Deadline {
+    /// The point `ledgers` later, at `SECS_PER_LEDGER` for timestamps.
+    fn plus(self, ledgers: u32) -> Deadline {
+        match self {
+            Deadline::Ledger(l) => Deadline::Ledger(l.saturating_add(ledgers)),
+            Deadline::Timestamp(t) => {
+                Deadline::Timestamp(t.saturating_add(u64::from(ledgers) * SECS_PER_LEDGER))
+            }
+        }
+    }
+
+    /// Whether the point lies behind us `ledgers` from now.
+    fn passed_in(self, e: &Env, ledgers: u32) -> bool {
+        match self {
+            Deadline::Ledger(l) => e.ledger().sequence().saturating_add(ledgers) > l,
+            Deadline::Timestamp(t) => {
+                e.ledger()
+                    .timestamp()
+                    .saturating_add(u64::from(ledgers) * SECS_PER_LEDGER)
+                    > t
+            }
+        }
+    }
+
+    /// The ledger the point falls on, estimated at `SECS_PER_LEDGER` for
+    /// timestamps.
+    fn ledger(self, e: &Env) -> u32 {
+        match self {
+            Deadline::Ledger(l) => l,
+            Deadline::Timestamp(t) => {
+                let secs = t.saturating_sub(e.ledger().timestamp());
+                e.ledger().sequence() + secs.div_ceil(SECS_PER_LEDGER) as u32
+            }
+        }
+    }
+
+    fn passed(self, e: &Env) -> bool {
+        self.passed_in(e, 0)
+    }
+
+    fn reached(self, e: &Env) -> bool {
+        match self {
+            Deadline::Ledger(l) => e.ledger().sequence() >= l,
+            Deadline::Timestamp(t) => e.ledger().timestamp() >= t,
+        }
+    }
 }
 
 #[contracttype]
 #[derive(Clone)]
 pub struct Round {
     // parameters
-    predicted_count: u32,
-    deadline_ledger: u32,
-    finality_ledger: u32,
+    market: Market,
+    predicted_count: u32, // invocation rounds only
+    deadline: Deadline,   // betting closes once passed
+    finality: Deadline,   // resolvable from here on
+    baseline_count: u32,  // oracle's invocation count when the round started
     // liquidity pools (token minor‑units)
     high_pool: i128,
     low_pool: i128,
+    bucket_pools: Vec<i128>, // bucket rounds only, one pool per bucket
+    guesses: Map<u32, i128>, // scalar rounds only, stake per guessed count
+    positions: u32,          // stakes not yet paid out
+    token: Address,          // SEP‑41 token it is staked in
+    forfeited: i128,         // in the pot, nobody's stake (cash‑outs, unrevealed bets)
     // resolution data
-    resolved: bool,
-    winning_side: Side, // meaningful only when `resolved == true`
-    actual_count: u32,  // idem
+    state: RoundState,
+    outcome: Outcome,             // `Pending` until resolved, voided or expired
+    actual_count: u32,            // from resolution on; invocations since `baseline_count`
+    settled_price: i128,          // idem; price rounds' oracle price
+    resolution_ledger: u32,       // idem; starts the amend window
+    resolved_by: Option<Address>, // who settled or voided it, if anyone did
+    evidence: BytesN<32>,         // hash of the data it was settled on; zero if none
+    bounty: i128,                 // paid out of the pot to a public resolver
+    protocol_fee: i128,           // protocol's cut of the winnings, per `fee_curve`
+    paid_out: i128,               // claimed out of the pot so far, claim fees included
+    guess_weight: i128,           // scalar rounds: sum of every stake's weight
+    fee_collected: bool,
+    rules: Rules,
+}
+
+/// Behavioural knobs of a round, fixed when it starts. Ledger windows are
+/// counted from `finality` (grace, public resolve) or from resolution
+/// (amend).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Rules {
+    pub grace_ledgers: u32,          // refunds open / public resolve closes
+    pub public_resolve_ledgers: u32, // public resolve opens
+    pub amend_window_ledgers: u32,   // corrections allowed, claims held
+    pub claim_window_ledgers: u32,   // then claims run this long; 0 for ever
+    pub keeper_fee_bps: u32,         // public resolver's cut of the pot
+    pub tie_policy: TiePolicy,
+    pub spread: u32,         // results within this of the line count as on it
+    pub fee_curve: FeeCurve, // protocol fee, off when all zero
+    pub metric: Metric,      // what count markets read
+}
+
+/// Protocol fee on winnings, interpolated on the winning side's share of
+/// the final pot: `min_bps` for a side that held none of it, `max_bps` for
+/// one that held all of it. Favourites pay more, underdogs less.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FeeCurve {
+    pub min_bps: u32,
+    pub max_bps: u32,
+}
+
+/// Bet acceptance hook: bets of at least `min_amount` are scored by
+/// `oracle`; scores at or above `reject_score` are refused, scores at or
+/// above `flag_score` are accepted but flagged in the `risk` event.
+#[contracttype]
+#[derive(Clone)]
+pub struct RiskConfig {
+    pub oracle: Address,
+    pub min_amount: i128,
+    pub flag_score: u32,
+    pub reject_score: u32,
+}
+
+/// The wager token, as frontends need it to display amounts.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenInfo {
+    pub token: Address,
+    pub decimals: u32, // amounts are in units of 10^-decimals
+    pub native: bool,  // the native asset's (XLM's) Stellar Asset Contract
+}
+
+/// Deployment‑wide tunables, set together with `set_config` or one by one
+/// with their own setters.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Config {
+    pub min_bet: i128,             // smallest bet taken
+    pub fee_bps: u32,              // claim fee on winnings
+    pub grace_ledgers: u32,        // default `Rules::grace_ledgers`
+    pub max_open_rounds: u32,      // cap on unresolved rounds; 0 for none
+    pub claim_window_ledgers: u32, // default `Rules::claim_window_ledgers`
+    pub insiders_may_bet: bool,    // admin, resolvers, committee may bet
+    pub min_betting_ledgers: u32,  // a new round takes bets at least this long
+    pub min_finality_gap: u32,     // ledgers from betting closing to finality
+}
+
+/// Stake limits applied to every bet; zero leaves that limit off.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BetCaps {
+    pub max_stake: i128,    // one player's stake in a round
+    pub max_pool: i128,     // everything staked in a round
+    pub max_exposure: i128, // one player's stake across unresolved rounds
+}
+
+/// Independent invocation‑count oracles. Readings are settled on their
+/// median, and refused outright when they spread over more than
+/// `max_spread` invocations.
+#[contracttype]
+#[derive(Clone)]
+pub struct FeedSet {
+    pub oracles: Vec<Address>,
+    pub max_spread: u32,
+}
+
+/// Resolver committee: a round resolves once `threshold` of `members` have
+/// submitted the same count.
+#[contracttype]
+#[derive(Clone)]
+pub struct Committee {
+    pub members: Vec<Address>,
+    pub threshold: u32,
 }
 
 #[contracttype]
@@ -66,6 +576,24 @@ pub struct Round {
 pub struct Stake {
     amount: i128,
     side: Side,
+    hedge: i128, // on the other side of an over/under line, if any
+}
+
+impl Stake {
+    /// Amount held on `side`.
+    fn on(&self, side: Side) -> i128 {
+        if side == self.side {
+            self.amount
+        } else if Some(side) == self.side.opposite() {
+            self.hedge
+        } else {
+            0
+        }
+    }
+
+    fn total(&self, e: &Env) -> i128 {
+        checked_add(e, self.amount, self.hedge)
+    }
 }
 
 // ──────────────────────────────────────────────────────────────────────────
@@ -86,277 +614,2572 @@ pub enum Error {
     AlreadyClaimed = 8,
     RefundNotAvailable = 9,
     ZeroAmount = 10,
+    OracleNotSet = 11,
+    ResolutionExpired = 12,
+    RiskRejected = 13,
+    ClaimsNotOpen = 14,
+    AmendWindowClosed = 15,
+    DuplicateVote = 16,
+    ConflictingVote = 17,
+    InvalidCommittee = 18,
+    SunsetActive = 19,
+    Retired = 20,
+    InvalidFee = 21,
+    InvalidCount = 22,
+    InvalidRules = 23,
+    FarmNotSet = 24,
+    AttestorNotSet = 25,
+    OptimisticDisabled = 26,
+    ProposalExists = 27,
+    NoProposal = 28,
+    ChallengeClosed = 29,
+    ProposalDisputed = 30,
+    InvalidTransition = 31,
+    WrongMarket = 32,
+    PriceUnavailable = 33,
+    OracleDisagreement = 34,
+    InvalidFeeds = 35,
+    NotOneSided = 36,
+    InvalidSide = 37,
+    InvalidBuckets = 38,
+    TooManyGuesses = 39,
+    InsufficientLiquidity = 40,
+    SlippageExceeded = 41,
+    InsufficientShares = 42,
+    PreconditionPending = 43,
+    TooManyOpenRounds = 44,
+    BetCapExceeded = 45,
+    MathOverflow = 46,
+    Paused = 47,
+    ConflictOfInterest = 48,
+    DeadlineTooSoon = 49,
+    FinalityTooSoon = 50,
 }
 
 // ──────────────────────────────────────────────────────────────────────────
 // Helpers
 // ──────────────────────────────────────────────────────────────────────────
 
-fn token_client(e: &Env) -> token::Client {
-    let addr: Address = e
-        .storage()
-        .instance()
-        .get(&DataKey::Token)
-        .expect("token not set");
-    token::Client::new(e, &addr)
+/// `a + b`, failing with `MathOverflow` instead of trapping.
+fn checked_add(e: &Env, a: i128, b: i128) -> i128 {
+    a.checked_add(b)
+        .unwrap_or_else(|| panic_with_error!(e, Error::MathOverflow))
 }
 
-fn get_admin(e: &Env) -> Address {
+/// `a * b / c` with a single rounding, the product carried in 256 bits
+/// when it does not fit in 128, so large pots neither overflow nor force
+/// an early division. Fails with `MathOverflow` if the quotient does not
+/// fit (or `c` is zero).
+fn mul_div(e: &Env, a: i128, b: i128, c: i128) -> i128 {
+    if c == 0 {
+        panic_with_error!(e, Error::MathOverflow);
+    }
+    let quotient = match a.checked_mul(b) {
+        Some(product) => product.checked_div(c),
+        None => I256::from_i128(e, a)
+            .mul(&I256::from_i128(e, b))
+            .div(&I256::from_i128(e, c))
+            .to_i128(),
+    };
+    quotient.unwrap_or_else(|| panic_with_error!(e, Error::MathOverflow))
+}
+
+/// The deployment's token, set in the constructor.
+fn default_token(e: &Env) -> Address {
     e.storage()
         .instance()
-        .get(&DataKey::Admin)
-        .expect("not initialised")
+        .get(&DataKey::Token)
+        .expect("token not set")
 }
 
-// ──────────────────────────────────────────────────────────────────────────
-// Contract implementation
-// ──────────────────────────────────────────────────────────────────────────
+fn token_client(e: &Env) -> token::Client {
+    token::Client::new(e, &default_token(e))
+}
 
-#[contract]
-pub struct KalePrediction;
+/// The token `round` is staked in.
+fn round_token<'a>(e: &'a Env, round: &Round) -> token::Client<'a> {
+    token::Client::new(e, &round.token)
+}
 
-#[contractimpl]
-impl KalePrediction {
-    // ---------------------------------------------------
-    // Admin / init
-    // ---------------------------------------------------
+/// Side books (jackpots, series, share sets, …) keep their balances in the
+/// deployment's token and only ride on rounds staked in it.
+fn in_default_token(e: &Env, round: &Round) -> bool {
+    round.token == default_token(e)
+}
 
-    /// Initialise contract with `admin` and **token** used for wagering.
-    pub fn __constructor(env: Env, admin: Address, token: Address) {
-        if env.storage().instance().has(&DataKey::Admin) {
-            panic_with_error!(env, Error::AlreadyInitialised);
+fn allowed_tokens(e: &Env) -> Vec<Address> {
+    e.storage()
+        .instance()
+        .get(&DataKey::Tokens)
+        .unwrap_or_else(|| Vec::new(e))
+}
+
+/// List `token` for new rounds (`allowed`) or take it off the list.
+fn allow_token(e: &Env, token: Address, allowed: bool) {
+    let mut tokens = allowed_tokens(e);
+    match (tokens.first_index_of(&token), allowed) {
+        (None, true) if tokens.len() >= MAX_TOKENS => panic_with_error!(e, Error::InvalidRules),
+        (None, true) => tokens.push_back(token),
+        (Some(i), false) => {
+            tokens.remove(i);
         }
+        _ => return,
+    }
+    e.storage().instance().set(&DataKey::Tokens, &tokens);
+}
 
-        admin.require_auth();
-        env.storage().instance().set(&DataKey::Admin, &admin);
-        env.storage().instance().set(&DataKey::Token, &token);
-        env.storage().instance().set(&DataKey::NextRoundId, &0u32);
+fn load_config(e: &Env) -> Config {
+    e.storage()
+        .instance()
+        .get(&DataKey::Config)
+        .unwrap_or(Config {
+            min_bet: 1,
+            fee_bps: 0,
+            grace_ledgers: GRACE_LEDGERS,
+            max_open_rounds: 0,
+            claim_window_ledgers: 0,
+            insiders_may_bet: false,
+            min_betting_ledgers: 0,
+            min_finality_gap: 1,
+        })
+}
+
+/// Validate and store `config`: a minimum bet of at least 1 (else
+/// `ZeroAmount`), a claim fee within `MAX_CLAIM_FEE_BPS` (`InvalidFee`), a
+/// grace period past `PUBLIC_RESOLVE_LEDGERS` and within
+/// `MAX_GRACE_LEDGERS`, and a finality gap of at least one ledger
+/// (`InvalidRules`).
+fn save_config(e: &Env, config: &Config) {
+    if config.min_bet < 1 {
+        panic_with_error!(e, Error::ZeroAmount);
+    }
+    if config.fee_bps > MAX_CLAIM_FEE_BPS {
+        panic_with_error!(e, Error::InvalidFee);
     }
+    if config.grace_ledgers <= PUBLIC_RESOLVE_LEDGERS
+        || config.grace_ledgers > MAX_GRACE_LEDGERS
+        || config.min_finality_gap == 0
+    {
+        panic_with_error!(e, Error::InvalidRules);
+    }
+    e.storage().instance().set(&DataKey::Config, config);
+}
 
-    /// Start a new prediction round.
-    pub fn start_round(
-        env: Env,
-        admin: Address,
-        predicted_count: u32,
-        deadline_ledger: u32,
-        finality_ledger: u32,
-    ) -> u32 {
-        // auth
-        let stored_admin = get_admin(&env);
-        if admin != stored_admin {
-            panic_with_error!(env, Error::Unauthorized);
+/// Set a fee rate, failing with `InvalidFee` above its cap.
+fn store_fee(e: &Env, fee: Fee, bps: u32) {
+    let (key, max) = match fee {
+        Fee::Claim => {
+            let mut config = load_config(e);
+            config.fee_bps = bps;
+            return save_config(e, &config);
         }
-        admin.require_auth();
+        Fee::Keeper => (DataKey::KeeperFeeBps, MAX_KEEPER_FEE_BPS),
+        Fee::Cancel => (DataKey::CancelFeeBps, MAX_CANCEL_FEE_BPS),
+        Fee::Referral => (DataKey::ReferralBps, referrals::MAX_REFERRAL_BPS),
+        Fee::CashOutHaircut => (
+            DataKey::CashOutHaircutBps,
+            cashout::MAX_CASH_OUT_HAIRCUT_BPS,
+        ),
+    };
+    if bps > max {
+        panic_with_error!(e, Error::InvalidFee);
+    }
+    e.storage().instance().set(&key, &bps);
+}
 
-        if deadline_ledger >= finality_ledger {
-            panic_with_error!(env, Error::TooEarly);
-        }
+/// Take `amount` from `from` into the contract: through the allowance
+/// `from` has given the contract when it covers the amount (so contract
+/// and smart‑wallet callers need not sign a nested transfer), by a direct
+/// transfer otherwise.
+fn pull(e: &Env, from: &Address, amount: i128) {
+    pull_token(e, &token_client(e), from, amount);
+}
 
-        // id generation
-        let mut next_id: u32 = env.storage().instance().get(&DataKey::NextRoundId).unwrap();
-        let round_id = next_id;
-        next_id += 1;
-        env.storage()
-            .instance()
-            .set(&DataKey::NextRoundId, &next_id);
+/// `pull` in `token`.
+fn pull_token(e: &Env, token: &token::Client, from: &Address, amount: i128) {
+    roles::require_unpaused(e);
+    let this = e.current_contract_address();
+    if token.allowance(from, &this) >= amount {
+        token.transfer_from(&this, from, &this, &amount);
+    } else {
+        token.transfer(from, &this, &amount);
+    }
+    owe(e, &token.address, amount);
+}
 
-        let round = Round {
-            predicted_count,
-            deadline_ledger,
-            finality_ledger,
-            high_pool: 0,
-            low_pool: 0,
-            resolved: false,
-            winning_side: Side::Lower, // placeholder
-            actual_count: 0,
-        };
+/// Send `amount` of `token` out of the contract to `to`.
+fn pay(e: &Env, token: &token::Client, to: &Address, amount: i128) {
+    owe(e, &token.address, -amount);
+    token.transfer(&e.current_contract_address(), to, &amount);
+}
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Round(round_id), &round);
+/// Move what the contract owes in `token` by `delta`. Everything pulled
+/// in is owed to someone — a bettor, a depositor, the vault, the admin's
+/// fees — until it is paid out again.
+fn owe(e: &Env, token: &Address, delta: i128) {
+    let key = DataKey::Liabilities(token.clone());
+    let owed: i128 = e.storage().instance().get(&key).unwrap_or(0);
+    e.storage()
+        .instance()
+        .set(&key, &checked_add(e, owed, delta));
+}
+
+fn get_admin(e: &Env) -> Address {
+    e.storage()
+        .instance()
+        .get(&DataKey::Admin)
+        .expect("not initialised")
+}
 
-        round_id
+fn require_admin(e: &Env, admin: &Address) {
+    if *admin != get_admin(e) {
+        panic_with_error!(e, Error::Unauthorized);
     }
+    admin.require_auth();
+}
 
-    // ---------------------------------------------------
-    // Betting
-    // ---------------------------------------------------
+/// Once the treasury has moved to a successor only views keep working.
+fn require_live(e: &Env) {
+    if e.storage().instance().has(&DataKey::Retired) {
+        panic_with_error!(e, Error::Retired);
+    }
+}
 
-    pub fn bet(env: Env, player: Address, round_id: u32, side: Side, amount: i128) {
-        if amount <= 0 {
-            panic_with_error!(env, Error::ZeroAmount);
-        }
-        player.require_auth();
+fn open_rounds(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::OpenRounds)
+        .unwrap_or(0)
+}
 
-        // load round
-        let mut round: Round = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Round(round_id))
-            .unwrap_or_else(|| panic_with_error!(env, Error::RoundNotFound));
+/// Whether the configured cap on unresolved rounds has been reached.
+fn at_capacity(e: &Env) -> bool {
+    let max = load_config(e).max_open_rounds;
+    max > 0 && open_rounds(e) >= max
+}
 
-        if env.ledger().sequence() > round.deadline_ledger {
-            panic_with_error!(env, Error::BettingClosed);
+/// Rounds taking bets, with the deadline each stops at.
+fn live_rounds(e: &Env) -> Map<u32, Deadline> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::LiveRounds)
+        .unwrap_or_else(|| Map::new(e))
+}
+
+/// Add or drop `round_id` from the live index as it opens or closes for
+/// betting. Rounds whose deadline has passed unnoticed are dropped too.
+fn index_live(e: &Env, round_id: u32, round: &Round) {
+    let mut live = live_rounds(e);
+    for (id, deadline) in live.iter() {
+        if deadline.passed(e) {
+            live.remove(id);
         }
+    }
+    if round.state == RoundState::Open {
+        live.set(round_id, round.deadline);
+    } else {
+        live.remove(round_id);
+    }
+    e.storage().persistent().set(&DataKey::LiveRounds, &live);
+    extend_written(e, &DataKey::LiveRounds);
+}
 
-        // transfer stake → contract
-        token_client(&env).transfer(&player, &env.current_contract_address(), &amount);
+/// Book‑keeping for a round leaving the unresolved set.
+fn close_round(e: &Env) {
+    e.storage()
+        .instance()
+        .set(&DataKey::OpenRounds, &(open_rounds(e) - 1));
+    e.storage()
+        .instance()
+        .set(&DataKey::LastSettled, &e.ledger().sequence());
+}
 
-        // update pools
-        match side {
-            Side::Higher => round.high_pool += amount,
-            Side::Lower => round.low_pool += amount,
-        }
-        env.storage()
-            .persistent()
-            .set(&DataKey::Round(round_id), &round);
+fn load_round(e: &Env, round_id: u32) -> Round {
+    let key = DataKey::Round(round_id);
+    let round = e
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| panic_with_error!(e, Error::RoundNotFound));
+    extend_read(e, &key);
+    round
+}
 
-        // upsert stake
-        let stake_key = DataKey::Stake(round_id, player.clone());
-        let updated_amount = env
-            .storage()
-            .persistent()
-            .get::<DataKey, Stake>(&stake_key)
-            .map(|s| s.amount + amount)
-            .unwrap_or(amount);
-        env.storage().persistent().set(
-            &stake_key,
-            &Stake {
-                amount: updated_amount,
-                side,
-            },
-        );
-    }
+fn save_round(e: &Env, round_id: u32, round: &Round) {
+    let key = DataKey::Round(round_id);
+    e.storage().persistent().set(&key, round);
+    extend_written(e, &key);
+}
 
-    // ---------------------------------------------------
-    // Resolution
-    // ---------------------------------------------------
+/// Keep a persistent entry from archival for `ENTRY_TTL_LEDGERS`
+/// after it is written.
+fn extend_written(e: &Env, key: &DataKey) {
+    e.storage()
+        .persistent()
+        .extend_ttl(key, ENTRY_TTL_LEDGERS, ENTRY_TTL_LEDGERS);
+}
 
-    pub fn resolve_round(env: Env, admin: Address, round_id: u32, actual_count: u32) {
-        // auth
-        let stored_admin = get_admin(&env);
-        if admin != stored_admin {
-            panic_with_error!(env, Error::Unauthorized);
-        }
-        admin.require_auth();
+/// Likewise after it is read, once less than `ENTRY_TTL_THRESHOLD` is
+/// left.
+fn extend_read(e: &Env, key: &DataKey) {
+    e.storage()
+        .persistent()
+        .extend_ttl(key, ENTRY_TTL_THRESHOLD, ENTRY_TTL_LEDGERS);
+}
 
-        let mut round: Round = env
-            .storage()
-            .persistent()
-            .get(&DataKey::Round(round_id))
-            .unwrap_or_else(|| panic_with_error!(env, Error::RoundNotFound));
+/// Side‑book entries — insurance policies, series with bets, rounds
+/// conditional on it — that still need `round_id`; it is not pruned while
+/// any remain.
+fn liens(e: &Env, round_id: u32) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Liens(round_id))
+        .unwrap_or(0)
+}
 
-        if env.ledger().sequence() < round.finality_ledger {
-            panic_with_error!(env, Error::TooEarly);
-        }
-        if round.resolved {
-            panic_with_error!(env, Error::AlreadyResolved);
+/// Record one more entry needing `round_id`.
+fn hold_round(e: &Env, round_id: u32) {
+    let key = DataKey::Liens(round_id);
+    e.storage()
+        .persistent()
+        .set(&key, &(liens(e, round_id) + 1));
+    extend_written(e, &key);
+}
+
+/// Record that an entry no longer needs `round_id`.
+fn release_round(e: &Env, round_id: u32) {
+    let key = DataKey::Liens(round_id);
+    match liens(e, round_id) {
+        0 | 1 => e.storage().persistent().remove(&key),
+        held => {
+            e.storage().persistent().set(&key, &(held - 1));
+            extend_written(e, &key);
         }
+    }
+}
 
-        round.winning_side = if actual_count > round.predicted_count {
-            Side::Higher
-        } else {
-            Side::Lower
-        };
-        round.actual_count = actual_count;
-        round.resolved = true;
+/// Ledger a short‑lived round's stakes are kept to, if it has one.
+fn stakes_until(e: &Env, round_id: u32) -> Option<u32> {
+    e.storage().persistent().get(&DataKey::ShortLived(round_id))
+}
 
-        env.storage()
-            .persistent()
-            .set(&DataKey::Round(round_id), &round);
+/// `player`'s stake in a round, from the storage the round keeps them in.
+fn load_stake(e: &Env, round_id: u32, player: &Address) -> Option<Stake> {
+    let key = DataKey::Stake(round_id, player.clone());
+    if stakes_until(e, round_id).is_some() {
+        return e.storage().temporary().get(&key);
+    }
+    let stake = e.storage().persistent().get(&key);
+    if stake.is_some() {
+        extend_read(e, &key);
     }
+    stake
+}
 
-    // ---------------------------------------------------
-    // Claim & refund
-    // ---------------------------------------------------
+/// Short‑lived rounds keep stakes in temporary storage until their claims
+/// close; the rest keep them in persistent storage.
+fn save_stake(e: &Env, round_id: u32, player: &Address, stake: &Stake) {
+    let before = load_stake(e, round_id, player);
+    stats::count_bettors(e, round_id, before.as_ref(), Some(stake));
+    let key = DataKey::Stake(round_id, player.clone());
+    match stakes_until(e, round_id) {
+        Some(until) => {
+            let ttl = until - e.ledger().sequence();
+            e.storage().temporary().set(&key, stake);
+            e.storage().temporary().extend_ttl(&key, ttl, ttl);
+        }
+        None => {
+            e.storage().persistent().set(&key, stake);
+            extend_written(e, &key);
+        }
+    }
+}
 
-    pub fn claim(env: Env, player: Address, round_id: u32) {
-        player.require_auth();
+fn remove_stake(e: &Env, round_id: u32, player: &Address) {
+    let key = DataKey::Stake(round_id, player.clone());
+    if stakes_until(e, round_id).is_some() {
+        e.storage().temporary().remove(&key);
+    } else {
+        e.storage().persistent().remove(&key);
+    }
+}
+
+/// Keep the instance entries alive; bumped on every bet, new round, claim
+/// and `tick`.
+fn extend_instance(e: &Env) {
+    e.storage()
+        .instance()
+        .extend_ttl(ENTRY_TTL_THRESHOLD, ENTRY_TTL_LEDGERS);
+}
+
+/// Refuse bets from insiders, blocked accounts and anyone a private round
+/// does not allow, then consult the risk oracle for large bets. Accepted
+/// bets are evented as `("risk", player) → (round_id, amount, score,
+/// flagged)`; a rejection
+/// aborts the invocation, so it surfaces as `RiskRejected` instead.
+fn check_risk(e: &Env, player: &Address, round_id: u32, amount: i128) {
+    roles::require_outsider(e, player);
+    compliance::require_unblocked(e, player);
+    compliance::require_allowed(e, round_id, player);
+    let Some(cfg) = e
+        .storage()
+        .instance()
+        .get::<DataKey, RiskConfig>(&DataKey::Risk)
+    else {
+        return;
+    };
+    if amount < cfg.min_amount {
+        return;
+    }
+
+    let score = RiskOracleClient::new(e, &cfg.oracle).score(player);
+    if score >= cfg.reject_score {
+        panic_with_error!(e, Error::RiskRejected);
+    }
+    events::publish(
+        e,
+        "risk",
+        (player.clone(),),
+        (round_id, amount, score, score >= cfg.flag_score),
+    );
+}
+
+/// Refuse a bet that would take `side`'s pool past the round's imbalance
+/// cap against the other side. Only applies once both sides hold stakes.
+fn check_imbalance(e: &Env, round_id: u32, round: &Round, side: Side, amount: i128) {
+    let Some(max_ratio_bps) = e
+        .storage()
+        .persistent()
+        .get::<DataKey, u32>(&DataKey::MaxImbalance(round_id))
+    else {
+        return;
+    };
+    let Some(other) = side.opposite() else {
+        return;
+    };
+    let against = pool(round, other);
+    let backed = checked_add(e, pool(round, side), amount);
+    if against > 0
+        && mul_div(e, backed, 10_000, 1) > mul_div(e, against, i128::from(max_ratio_bps), 1)
+    {
+        panic_with_error!(e, Error::BetCapExceeded);
+    }
+}
+
+/// Bets below the configured minimum (one minor unit by default) fail
+/// with `ZeroAmount`; dust stakes cost more to settle than they are worth.
+fn require_min_bet(e: &Env, amount: i128) {
+    if amount < load_config(e).min_bet {
+        panic_with_error!(e, Error::ZeroAmount);
+    }
+}
+
+/// Refuse `amount` more in `round` once it would take the player's stake
+/// (`staked` after the bet), the pot (`pot` before it) or the player's
+/// exposure past the configured caps.
+fn check_caps(
+    e: &Env,
+    round_id: u32,
+    round: &Round,
+    player: &Address,
+    staked: i128,
+    pot: i128,
+    amount: i128,
+) {
+    let Some(caps) = e
+        .storage()
+        .instance()
+        .get::<DataKey, BetCaps>(&DataKey::BetCaps)
+    else {
+        return;
+    };
+    if (caps.max_stake > 0 && staked > caps.max_stake)
+        || (caps.max_pool > 0 && checked_add(e, pot, amount) > caps.max_pool)
+    {
+        panic_with_error!(e, Error::BetCapExceeded);
+    }
+    if caps.max_exposure > 0 && in_default_token(e, round) {
+        add_exposure(e, round_id, player, amount, caps.max_exposure);
+    }
+}
+
+/// `player`'s tracked stakes by round, without rounds since settled.
+fn open_exposure(e: &Env, player: &Address) -> Map<u32, i128> {
+    let mut stakes: Map<u32, i128> = e
+        .storage()
+        .persistent()
+        .get(&DataKey::Exposure(player.clone()))
+        .unwrap_or_else(|| Map::new(e));
+    for round_id in stakes.keys().iter() {
+        let round = e
+            .storage()
+            .persistent()
+            .get::<DataKey, Round>(&DataKey::Round(round_id));
+        if !round.is_some_and(|r| {
+            matches!(
+                r.state,
+                RoundState::Created | RoundState::Open | RoundState::Locked
+            )
+        }) {
+            stakes.remove(round_id);
+        }
+    }
+    stakes
+}
+
+/// Count `amount` toward `player`'s exposure: what they have staked in the
+/// deployment's token across unresolved rounds while an exposure cap is
+/// on. Stakes taken back early keep counting until their round settles.
+/// Past `max` or `MAX_EXPOSURE_ROUNDS` rounds, fails with
+/// `BetCapExceeded`.
+fn add_exposure(e: &Env, round_id: u32, player: &Address, amount: i128, max: i128) {
+    let mut stakes = open_exposure(e, player);
+    let held = stakes.get(round_id);
+    let total = stakes
+        .values()
+        .iter()
+        .fold(amount, |total, held| checked_add(e, total, held));
+    if total > max || (held.is_none() && stakes.len() >= MAX_EXPOSURE_ROUNDS) {
+        panic_with_error!(e, Error::BetCapExceeded);
+    }
+    stakes.set(round_id, checked_add(e, held.unwrap_or(0), amount));
+    e.storage()
+        .persistent()
+        .set(&DataKey::Exposure(player.clone()), &stakes);
+}
+
+/// An advanced round can be resolved while `Locked`, from finality on.
+fn require_resolvable(e: &Env, round: &Round) {
+    match round.state {
+        RoundState::Locked if round.finality.reached(e) => {}
+        RoundState::Created | RoundState::Open | RoundState::Locked => {
+            panic_with_error!(e, Error::TooEarly)
+        }
+        _ if round.outcome == Outcome::Expired => panic_with_error!(e, Error::ResolutionExpired),
+        _ => panic_with_error!(e, Error::AlreadyResolved),
+    }
+}
+
+/// Settle on `observed_count` now; the amend window starts here.
+fn finish_resolution(e: &Env, round_id: u32, round: &mut Round, observed_count: u32) {
+    settle(e, round, observed_count);
+    record_resolution(e, round_id, round);
+}
+
+/// Stamp and persist a round whose outcome has just been decided, voiding
+/// it instead if its precondition failed.
+fn record_resolution(e: &Env, round_id: u32, round: &mut Round) {
+    round.resolution_ledger = e.ledger().sequence();
+    if conditions::holds(e, round_id, round) {
+        transition(e, round_id, round, RoundState::Resolved);
+        events::publish(
+            e,
+            "round_resolved",
+            (round_id,),
+            (round.outcome, round.actual_count, total_pool(round)),
+        );
+    } else {
+        round.outcome = Outcome::Voided;
+        round.protocol_fee = 0;
+        transition(e, round_id, round, RoundState::Voided);
+    }
+    save_round(e, round_id, round);
+}
+
+/// Payouts are only made once a round is `Settled`.
+fn require_settled(e: &Env, round: &Round) {
+    match round.state {
+        RoundState::Settled => {}
+        RoundState::Resolved => panic_with_error!(e, Error::ClaimsNotOpen),
+        RoundState::Archived => panic_with_error!(e, Error::AlreadyClaimed),
+        _ => panic_with_error!(e, Error::NotResolved),
+    }
+}
+
+/// Fixed‑odds and LMSR rounds trade against the vault or the maker, not
+/// through the parimutuel pool.
+fn has_maker(e: &Env, round_id: u32) -> bool {
+    e.storage().persistent().has(&DataKey::Book(round_id))
+        || e.storage().persistent().has(&DataKey::Amm(round_id))
+}
+
+/// Last ledger `round`'s claims are guaranteed to be open, if they close.
+fn claim_deadline(round: &Round) -> Option<u32> {
+    let window = round.rules.claim_window_ledgers;
+    (window > 0).then(|| {
+        round
+            .resolution_ledger
+            .saturating_add(round.rules.amend_window_ledgers)
+            .saturating_add(window)
+    })
+}
+
+/// Side books (jackpot, fixed odds) pay out alongside the round's claims,
+/// and keep doing so after the last parimutuel claim archives it.
+fn require_paying(e: &Env, round: &Round) {
+    match round.state {
+        RoundState::Settled | RoundState::Archived => {}
+        RoundState::Resolved => panic_with_error!(e, Error::ClaimsNotOpen),
+        _ => panic_with_error!(e, Error::NotResolved),
+    }
+}
+
+/// Add `amount` on `side` to `player`'s stake (tokens already received)
+/// and to the round's pools. The caller saves the round.
+fn add_stake(
+    e: &Env,
+    round_id: u32,
+    round: &mut Round,
+    player: &Address,
+    side: Side,
+    amount: i128,
+) {
+    // upsert stake; an over/under stake may be hedged on the other side
+    let held = load_stake(e, round_id, player);
+    let stake = match held {
+        Some(s) if s.side == side => Stake {
+            amount: checked_add(e, s.amount, amount),
+            ..s
+        },
+        Some(s) if s.side.opposite() == Some(side) => {
+            if insurance::is_insured(e, round_id, player) {
+                panic_with_error!(e, Error::WrongMarket);
+            }
+            Stake {
+                hedge: checked_add(e, s.hedge, amount),
+                ..s
+            }
+        }
+        Some(_) => panic_with_error!(e, Error::InvalidSide),
+        None => {
+            round.positions += 1;
+            Stake {
+                amount,
+                side,
+                hedge: 0,
+            }
+        }
+    };
+    check_caps(
+        e,
+        round_id,
+        round,
+        player,
+        stake.total(e),
+        total_pool(round),
+        amount,
+    );
+    seasons::record_bet(e, round_id, player, amount, held.is_none());
+    stats::record_wager(e, round, player, amount);
+    if held.is_none() {
+        history::record(e, player, round_id);
+    }
+    save_stake(e, round_id, player, &stake);
+
+    adjust_pool(e, round, side, amount);
+    events::publish(
+        e,
+        "bet_placed",
+        (round_id, player.clone()),
+        (side, amount, pool(round, side), total_pool(round)),
+    );
+}
+
+/// What `amount` more on `side` would pay back per unit if it won with
+/// the pools as they are, in basis points (before fees and bounties).
+/// Scalar guesses have no such price.
+fn odds_bps(e: &Env, round: &Round, side: Side, amount: i128) -> Option<i128> {
+    let pot = match side {
+        Side::Guess(_) => return None,
+        Side::Over(i) | Side::Under(i) => {
+            checked_add(e, pool(round, Side::Over(i)), pool(round, Side::Under(i)))
+        }
+        _ => total_pool(round),
+    };
+    Some(mul_div(
+        e,
+        checked_add(e, pot, amount),
+        10_000,
+        checked_add(e, pool(round, side), amount),
+    ))
+}
+
+/// Stake `amount` paid by `payer` on `side` for `player`, once the round
+/// is taking pool bets. Without a payer the amount is already held in
+/// the round's token (an operator bet from `player`'s deposit, a
+/// rolled‑over payout).
+fn place_bet(
+    e: &Env,
+    payer: Option<&Address>,
+    player: &Address,
+    round_id: u32,
+    side: Side,
+    amount: i128,
+) {
+    roles::require_unpaused(e);
+    extend_instance(e);
+    // load round
+    let mut round = load_round_advanced(e, round_id);
+
+    if round.state != RoundState::Open {
+        panic_with_error!(e, Error::BettingClosed);
+    }
+    if !is_side_of(&round, side) {
+        panic_with_error!(e, Error::InvalidSide);
+    }
+    if has_maker(e, round_id) {
+        panic_with_error!(e, Error::WrongMarket);
+    }
+    if duels::is_duel(e, round_id) {
+        panic_with_error!(e, Error::Unauthorized);
+    }
+    if sealed::is_sealed(e, round_id) {
+        panic_with_error!(e, Error::WrongMarket);
+    }
+
+    check_imbalance(e, round_id, &round, side, amount);
+    check_risk(e, player, round_id, amount);
+
+    // transfer stake → contract
+    if let Some(payer) = payer {
+        pull_token(e, &round_token(e, &round), payer, amount);
+    }
+
+    add_stake(e, round_id, &mut round, player, side, amount);
+    save_round(e, round_id, &round);
+}
+
+/// Move `side`'s pool by `delta` (negative when stakes leave). Fails with
+/// `MathOverflow` rather than let any pool, or the pot, overflow.
+fn adjust_pool(e: &Env, round: &mut Round, side: Side, delta: i128) {
+    match side {
+        Side::Higher => round.high_pool = checked_add(e, round.high_pool, delta),
+        Side::Lower => round.low_pool = checked_add(e, round.low_pool, delta),
+        Side::Bucket(i) => {
+            let backed = round.bucket_pools.get_unchecked(i);
+            round.bucket_pools.set(i, checked_add(e, backed, delta));
+        }
+        Side::Guess(guess) => {
+            let backed = round.guesses.get(guess).unwrap_or(0);
+            if backed == 0 && round.guesses.len() >= scalar::MAX_GUESSES {
+                panic_with_error!(e, Error::TooManyGuesses);
+            }
+            match checked_add(e, backed, delta) {
+                0 => {
+                    round.guesses.remove(guess);
+                }
+                backed => round.guesses.set(guess, backed),
+            }
+        }
+        Side::Over(_) | Side::Under(_) => {
+            let slot = ladder::slot(side);
+            let backed = round.bucket_pools.get_unchecked(slot);
+            round.bucket_pools.set(slot, checked_add(e, backed, delta));
+        }
+    }
+    // every later `total_pool` of the round is then safe to take
+    if try_total_pool(round).is_none() {
+        panic_with_error!(e, Error::MathOverflow);
+    }
+}
+
+/// Take `amount` off the main side of `player`'s `stake` and store what
+/// is left; once the main side runs out a hedge takes its place. The
+/// caller saves the round. Insured stakes stay as they are.
+fn shrink_stake(
+    e: &Env,
+    round_id: u32,
+    round: &mut Round,
+    player: &Address,
+    stake: Stake,
+    amount: i128,
+) {
+    if insurance::is_insured(e, round_id, player) {
+        panic_with_error!(e, Error::WrongMarket);
+    }
+    let mut left = Stake {
+        amount: stake.amount - amount,
+        ..stake
+    };
+    if left.total(e) == 0 {
+        stats::count_bettors(e, round_id, Some(&stake), None);
+        remove_stake(e, round_id, player);
+        round.positions -= 1;
+        return;
+    }
+    if left.amount == 0 {
+        left = Stake {
+            amount: left.hedge,
+            side: left.side.opposite().unwrap(),
+            hedge: 0,
+        };
+    }
+    save_stake(e, round_id, player, &left);
+}
+
+/// Remove `player`'s stake ahead of paying it out; the last one archives
+/// the round.
+fn take_stake(e: &Env, round_id: u32, round: &mut Round, player: &Address) -> Stake {
+    if compliance::is_frozen(e, player) {
+        panic_with_error!(e, Error::RiskRejected);
+    }
+    let stake = load_stake(e, round_id, player)
+        .unwrap_or_else(|| panic_with_error!(e, Error::AlreadyClaimed));
+    remove_stake(e, round_id, player);
+
+    round.positions -= 1;
+    if round.positions == 0 {
+        transition(e, round_id, round, RoundState::Archived);
+    }
+    save_round(e, round_id, round);
+    stake
+}
+
+/// Take `player`'s stake on a settled round and work out what it is owed.
+/// The caller pays it out.
+fn settle_stake(e: &Env, player: &Address, round_id: u32) -> (Round, i128) {
+    let mut round = load_round_advanced(e, round_id);
+    require_settled(e, &round);
+
+    // remove stake first to block re‑entrancy / double claim
+    let stake = take_stake(e, round_id, &mut round, player);
+
+    let payout = gross_payout(e, &round, &stake);
+    if payout == 0 {
+        stats::record_result(e, &round, player, &stake, 0);
+        events::publish(e, "claimed", (round_id, player.clone()), 0i128);
+        return (round, 0); // loser gets nothing
+    }
+    round.paid_out = checked_add(e, round.paid_out, payout);
+    save_round(e, round_id, &round);
+    let fee = claim_fee(e, &round, payout);
+    if fee > 0 {
+        let key = DataKey::AccruedFees(round.token.clone());
+        let held: i128 = e.storage().persistent().get(&key).unwrap_or(0);
+        e.storage()
+            .persistent()
+            .set(&key, &checked_add(e, held, fee));
+    }
+    let payout = payout - fee;
+    seasons::record_payout(e, round_id, &round, player, &stake, payout);
+    stats::record_result(e, &round, player, &stake, payout);
+    events::publish(e, "claimed", (round_id, player.clone()), payout);
+    (round, payout)
+}
+
+/// What `stake` is owed out of a settled round's pot, before the claim fee.
+fn gross_payout(e: &Env, round: &Round, stake: &Stake) -> i128 {
+    let total_pool = total_pool(round);
+    // a public resolver's bounty has already left the pot; the protocol
+    // fee is held back for `collect_fee`
+    let payable_pool = total_pool - round.bounty - round.protocol_fee;
+
+    match round.outcome.winner() {
+        Some(side) if stake.on(side) == 0 => 0,
+        Some(side) => mul_div(e, stake.on(side), payable_pool, pool(round, side)),
+        None if round.outcome == Outcome::Scored => scalar::share(e, round, stake, payable_pool),
+        None if round.outcome == Outcome::Laddered => {
+            ladder::share(e, round, stake, payable_pool, total_pool)
+        }
+        // push / void: every stake comes back (less its share of any
+        // bounty, plus its share of what was forfeited to the pot)
+        None => refund_share(e, round, stake, payable_pool),
+    }
+}
+
+/// `stake`'s share of `pot` by what is still staked, so it carries a
+/// share of any forfeits; nothing once every stake has been forfeited.
+fn refund_share(e: &Env, round: &Round, stake: &Stake, pot: i128) -> i128 {
+    match total_pool(round) - round.forfeited {
+        0 => 0,
+        staked => mul_div(e, stake.total(e), pot, staked),
+    }
+}
+
+/// The claim fee on `payout`, if it is winnings rather than a stake
+/// coming back.
+fn claim_fee(e: &Env, round: &Round, payout: i128) -> i128 {
+    if matches!(
+        round.outcome,
+        Outcome::Push | Outcome::Voided | Outcome::Expired
+    ) {
+        return 0;
+    }
+    let bps = load_config(e).fee_bps;
+    mul_div(e, payout, i128::from(bps), 10_000)
+}
+
+/// Validate and store a new round on the admin's behalf, opening it for
+/// bets.
+fn create_round(
+    e: &Env,
+    admin: &Address,
+    market: Market,
+    predicted_count: u32,
+    deadline: Deadline,
+    finality: Deadline,
+    rules: Rules,
+) -> u32 {
+    require_live(e);
+    require_admin(e, admin);
+    let token = default_token(e);
+    open_round(e, market, predicted_count, deadline, finality, rules, token)
+}
+
+/// Whether the sunset has taken effect, so no new round may start.
+fn sunset_active(e: &Env) -> bool {
+    e.storage()
+        .instance()
+        .get::<DataKey, u32>(&DataKey::Sunset)
+        .is_some_and(|at| e.ledger().sequence() >= at)
+}
+
+/// `create_round` without the caller checks, for rounds the contract
+/// starts itself.
+fn open_round(
+    e: &Env,
+    market: Market,
+    predicted_count: u32,
+    deadline: Deadline,
+    finality: Deadline,
+    rules: Rules,
+    token: Address,
+) -> u32 {
+    if sunset_active(e) {
+        panic_with_error!(e, Error::SunsetActive);
+    }
+    if at_capacity(e) {
+        panic_with_error!(e, Error::TooManyOpenRounds);
+    }
+    extend_instance(e);
+    // betting must stay open a while, and close a while before finality
+    let config = load_config(e);
+    if deadline.passed_in(e, config.min_betting_ledgers + 1) {
+        panic_with_error!(e, Error::DeadlineTooSoon);
+    }
+    let spaced = match (deadline.plus(config.min_finality_gap), finality) {
+        (Deadline::Ledger(d), Deadline::Ledger(f)) => d <= f,
+        (Deadline::Timestamp(d), Deadline::Timestamp(f)) => d <= f,
+        _ => false, // mixed modes
+    };
+    if !spaced {
+        panic_with_error!(e, Error::FinalityTooSoon);
+    }
+    if rules.public_resolve_ledgers >= rules.grace_ledgers
+        || rules.grace_ledgers > MAX_GRACE_LEDGERS
+        || rules.keeper_fee_bps > MAX_KEEPER_FEE_BPS
+        || rules.fee_curve.min_bps > rules.fee_curve.max_bps
+        || rules.fee_curve.max_bps > MAX_PROTOCOL_FEE_BPS
+    {
+        panic_with_error!(e, Error::InvalidRules);
+    }
+
+    // id generation
+    let mut next_id: u32 = e.storage().instance().get(&DataKey::NextRoundId).unwrap();
+    let round_id = next_id;
+    next_id += 1;
+    e.storage().instance().set(&DataKey::NextRoundId, &next_id);
+
+    let baseline_count = match market {
+        Market::Price(_) => 0,
+        _ if rules.metric == Metric::TotalStaked => 0,
+        Market::Invocations | Market::Buckets(_) | Market::Scalar | Market::Ladder(_) => {
+            oracle_count(e, rules.metric).unwrap_or(0)
+        }
+    };
+    let mut bucket_pools = Vec::new(e);
+    let slots = match &market {
+        Market::Buckets(edges) => edges.len() + 1,
+        Market::Ladder(lines) => lines.len() * 2,
+        _ => 0,
+    };
+    for _ in 0..slots {
+        bucket_pools.push_back(0);
+    }
+    let mut round = Round {
+        market,
+        predicted_count,
+        deadline,
+        finality,
+        baseline_count,
+        high_pool: 0,
+        low_pool: 0,
+        bucket_pools,
+        guesses: Map::new(e),
+        positions: 0,
+        token,
+        state: RoundState::Created,
+        outcome: Outcome::Pending,
+        actual_count: 0,
+        settled_price: 0,
+        resolution_ledger: 0,
+        resolved_by: None,
+        evidence: BytesN::from_array(e, &[0; 32]),
+        bounty: 0,
+        protocol_fee: 0,
+        forfeited: 0,
+        guess_weight: 0,
+        paid_out: 0,
+        fee_collected: false,
+        rules,
+    };
+    e.storage()
+        .instance()
+        .set(&DataKey::OpenRounds, &(open_rounds(e) + 1));
+    transition(e, round_id, &mut round, RoundState::Open);
+    save_round(e, round_id, &round);
+    events::publish(
+        e,
+        "round_started",
+        (round_id,),
+        (
+            round.predicted_count,
+            round.deadline,
+            round.finality,
+            round.token.clone(),
+        ),
+    );
+    if in_default_token(e, &round) {
+        if round.market == Market::Invocations {
+            jackpot::carry_over(e, round_id);
+        }
+        seasons::enroll(e, round_id);
+    }
+
+    round_id
+}
+
+/// Rules a round gets from `start_round`: the contract constants and the
+/// currently configured keeper fee.
+fn default_rules(e: &Env) -> Rules {
+    let config = load_config(e);
+    Rules {
+        grace_ledgers: config.grace_ledgers,
+        public_resolve_ledgers: PUBLIC_RESOLVE_LEDGERS,
+        amend_window_ledgers: AMEND_WINDOW_LEDGERS,
+        claim_window_ledgers: config.claim_window_ledgers,
+        keeper_fee_bps: e
+            .storage()
+            .instance()
+            .get(&DataKey::KeeperFeeBps)
+            .unwrap_or(DEFAULT_KEEPER_FEE_BPS),
+        tie_policy: TiePolicy::Push,
+        spread: 0,
+        metric: Metric::Invocations,
+        fee_curve: FeeCurve {
+            min_bps: 0,
+            max_bps: 0,
+        },
+    }
+}
+
+/// Oracle reading, if one is configured: the feed set's median when there
+/// is one, the single oracle otherwise.
+fn oracle_count(e: &Env, metric: Metric) -> Option<u32> {
+    try_oracle_count(e, metric)
+        .map(|reading| reading.unwrap_or_else(|err| panic_with_error!(e, err)))
+}
+
+/// `oracle_count` that hands back diverging feeds instead of aborting.
+fn try_oracle_count(e: &Env, metric: Metric) -> Option<Result<u32, Error>> {
+    feed_count(e, metric).or_else(|| {
+        e.storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::Oracle)
+            .map(|oracle| Ok(read_metric(e, &oracle, metric)))
+    })
+}
+
+/// One oracle's current reading of `metric`.
+fn read_metric(e: &Env, oracle: &Address, metric: Metric) -> u32 {
+    let client = CountOracleClient::new(e, oracle);
+    match metric {
+        Metric::Invocations => client.invocation_count(),
+        Metric::HarvestVolume => client.harvest_volume(),
+        Metric::TotalStaked => client.total_staked(),
+    }
+}
+
+/// Median of the feed set's readings (the lower one for an even set), if
+/// a set is configured.
+fn feed_count(e: &Env, metric: Metric) -> Option<Result<u32, Error>> {
+    let feeds: FeedSet = e.storage().instance().get(&DataKey::Feeds)?;
+    let mut readings = [0u32; MAX_FEEDS as usize];
+    for (slot, oracle) in readings.iter_mut().zip(feeds.oracles.iter()) {
+        *slot = read_metric(e, &oracle, metric);
+    }
+    let readings = &mut readings[..feeds.oracles.len() as usize];
+    readings.sort_unstable();
+
+    let (low, high) = (readings[0], readings[readings.len() - 1]);
+    if high - low > feeds.max_spread {
+        return Some(Err(Error::OracleDisagreement));
+    }
+    Some(Ok(readings[(readings.len() - 1) / 2]))
+}
+
+/// Invocation‑count resolution paths refuse price rounds up front, before
+/// any vote or bond is recorded.
+fn require_count_market(e: &Env, round: &Round) {
+    if matches!(round.market, Market::Price(_)) {
+        panic_with_error!(e, Error::WrongMarket);
+    }
+}
+
+/// Turn an invocation‑count reading into the round's delta and derive the
+/// outcome from it.
+fn settle(e: &Env, round: &mut Round, observed_count: u32) {
+    require_count_market(e, round);
+    let actual_count = observed_count
+        .checked_sub(round.baseline_count)
+        .unwrap_or_else(|| panic_with_error!(e, Error::InvalidCount));
+    round.actual_count = actual_count;
+    match &round.market {
+        Market::Buckets(edges) => decide_bucket(e, round, buckets::bucket_of(edges, actual_count)),
+        Market::Scalar => scalar::score(round),
+        Market::Ladder(_) => ladder::decide(round),
+        _ => {
+            let result = against_line(
+                i128::from(actual_count),
+                i128::from(round.predicted_count),
+                round.rules.spread,
+            );
+            decide(e, round, result)
+        }
+    }
+}
+
+/// Bucket rounds: the bucket the count fell in wins; if nobody backed it
+/// every stake is returned.
+fn decide_bucket(e: &Env, round: &mut Round, bucket: u32) {
+    round.outcome = if pool(round, Side::Bucket(bucket)) > 0 {
+        Outcome::Bucket(bucket)
+    } else {
+        Outcome::Push
+    };
+    round.protocol_fee = protocol_fee(e, round);
+}
+
+/// Stakes on `side`.
+fn pool(round: &Round, side: Side) -> i128 {
+    match side {
+        Side::Higher => round.high_pool,
+        Side::Lower => round.low_pool,
+        Side::Bucket(i) => round.bucket_pools.get(i).unwrap_or(0),
+        Side::Guess(_) => round.guesses.values().iter().sum(),
+        Side::Over(_) | Side::Under(_) => round.bucket_pools.get(ladder::slot(side)).unwrap_or(0),
+    }
+}
+
+/// Every stake in the round.
+fn total_pool(round: &Round) -> i128 {
+    round.high_pool
+        + round.low_pool
+        + round.bucket_pools.iter().sum::<i128>()
+        + round.guesses.values().iter().sum::<i128>()
+        + round.forfeited
+}
+
+/// `total_pool`, or `None` if it does not fit.
+fn try_total_pool(round: &Round) -> Option<i128> {
+    [round.high_pool, round.low_pool, round.forfeited]
+        .into_iter()
+        .chain(round.bucket_pools.iter())
+        .chain(round.guesses.values())
+        .try_fold(0i128, i128::checked_add)
+}
+
+/// Whether a side a stake could be on is a side of this round.
+fn is_side_of(round: &Round, side: Side) -> bool {
+    match (&round.market, side) {
+        (Market::Buckets(_), Side::Bucket(i)) => i < round.bucket_pools.len(),
+        (Market::Scalar, Side::Guess(_)) => true,
+        (Market::Ladder(lines), Side::Over(i) | Side::Under(i)) => i < lines.len(),
+        (Market::Buckets(_) | Market::Scalar | Market::Ladder(_), _)
+        | (_, Side::Bucket(_) | Side::Guess(_) | Side::Over(_) | Side::Under(_)) => false,
+        _ => true,
+    }
+}
+
+/// Where `value` fell against `line`; anything within `spread` of it
+/// counts as on the line.
+fn against_line(value: i128, line: i128, spread: u32) -> Ordering {
+    let spread = i128::from(spread);
+    if value > line + spread {
+        Ordering::Greater
+    } else if value < line - spread {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// Set the outcome from where the result fell against the line.
+fn decide(e: &Env, round: &mut Round, result: Ordering) {
+    round.outcome = match result {
+        Ordering::Greater => Outcome::Higher,
+        Ordering::Less => Outcome::Lower,
+        Ordering::Equal => match round.rules.tie_policy {
+            TiePolicy::Lower => Outcome::Lower,
+            TiePolicy::Higher => Outcome::Higher,
+            TiePolicy::Push => Outcome::Push,
+        },
+    };
+    // stakes lost to a side nobody backed go back instead
+    if let Some(side) = round.outcome.winner() {
+        if pool(round, side) == 0 && total_pool(round) > 0 {
+            round.outcome = Outcome::Push;
+        }
+    }
+    round.protocol_fee = protocol_fee(e, round);
+}
+
+/// Protocol fee owed on the losing pool under the round's fee curve.
+fn protocol_fee(e: &Env, round: &Round) -> i128 {
+    let curve = round.rules.fee_curve;
+    let Some(side) = round.outcome.winner() else {
+        return 0;
+    };
+    let winning_pool = pool(round, side);
+    let losing_pool = total_pool(round) - winning_pool;
+    if losing_pool == 0 {
+        return 0;
+    }
+    // the interpolated rate is kept as a fraction of the pot, not rounded
+    // to whole basis points
+    let total = total_pool(round);
+    let spread = i128::from(curve.max_bps - curve.min_bps);
+    let rate = checked_add(
+        e,
+        mul_div(e, i128::from(curve.min_bps), total, 1),
+        mul_div(e, spread, winning_pool, 1),
+    );
+    mul_div(e, losing_pool, rate, mul_div(e, total, 10_000, 1))
+}
+
+// ──────────────────────────────────────────────────────────────────────────
+// Contract implementation
+// ──────────────────────────────────────────────────────────────────────────
+
+#[contract]
+pub struct KalePrediction;
+
+#[contractimpl]
+impl KalePrediction {
+    // ---------------------------------------------------
+    // Admin / init
+    // ---------------------------------------------------
+
+    /// Initialise contract with `admin` and **token** used for wagering.
+    pub fn __constructor(env: Env, admin: Address, token: Address) {
+        if env.storage().instance().has(&DataKey::Admin) {
+            panic_with_error!(env, Error::AlreadyInitialised);
+        }
+
+        admin.require_auth();
+        env.storage().instance().set(&DataKey::Admin, &admin);
+        env.storage().instance().set(&DataKey::Token, &token);
+        env.storage().instance().set(&DataKey::NextRoundId, &0u32);
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &upgrade::STORAGE_VERSION);
+    }
+
+    /// Start a new prediction round. With an oracle configured its current
+    /// invocation count becomes the round's baseline, so `predicted_count`
+    /// is the number of invocations expected from here to finality.
+    pub fn start_round(
+        env: Env,
+        admin: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        let rules = default_rules(&env);
+        Self::start_round_with_rules(
+            env,
+            admin,
+            predicted_count,
+            deadline_ledger,
+            finality_ledger,
+            rules,
+        )
+    }
+
+    /// `start_round` with custom `rules`. The public resolve window must
+    /// open before the grace period ends, the keeper fee is capped at
+    /// `MAX_KEEPER_FEE_BPS` and a fee curve at `MAX_PROTOCOL_FEE_BPS`.
+    pub fn start_round_with_rules(
+        env: Env,
+        admin: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+        rules: Rules,
+    ) -> u32 {
+        create_round(
+            &env,
+            &admin,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        )
+    }
+
+    /// `start_round_with_rules` on a schedule given either in ledgers or,
+    /// for wall‑clock rounds, in UNIX seconds; both points must use the
+    /// same mode. `None` takes the default rules, whose ledger windows are
+    /// stretched at `SECS_PER_LEDGER` on a timestamp schedule.
+    pub fn start_round_at(
+        env: Env,
+        admin: Address,
+        predicted_count: u32,
+        deadline: Deadline,
+        finality: Deadline,
+        rules: Option<Rules>,
+    ) -> u32 {
+        let rules = rules.unwrap_or_else(|| default_rules(&env));
+        create_round(
+            &env,
+            &admin,
+            Market::Invocations,
+            predicted_count,
+            deadline,
+            finality,
+            rules,
+        )
+    }
+
+    /// `start_round` staked in `token` instead of the deployment's token;
+    /// it must be on the `set_token_allowed` list. Jackpots, series,
+    /// share sets, referrals and seasons stay on the deployment's token and
+    /// leave such rounds out.
+    pub fn start_round_in(
+        env: Env,
+        admin: Address,
+        token: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if token != default_token(&env) && !allowed_tokens(&env).contains(&token) {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        let rules = default_rules(&env);
+        open_round(
+            &env,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+            token,
+        )
+    }
+
+    /// Start a round on the invocations during Kale farm epoch `epoch`:
+    /// betting closes as the epoch starts and the round finalises as it
+    /// ends. The baseline is taken now, so open it just ahead of the epoch.
+    pub fn start_epoch_round(env: Env, admin: Address, predicted_count: u32, epoch: u32) -> u32 {
+        let farm: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Farm)
+            .unwrap_or_else(|| panic_with_error!(env, Error::FarmNotSet));
+        let bounds = FarmEpochsClient::new(&env, &farm).epoch_bounds(&epoch);
+        if bounds.start_ledger <= env.ledger().sequence() {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        Self::start_round(
+            env,
+            admin,
+            predicted_count,
+            bounds.start_ledger - 1,
+            bounds.end_ledger,
+        )
+    }
+
+    /// Accept counts signed by the ed25519 key `attestor` (see
+    /// `resolve_attested`), or stop accepting them with `None`.
+    pub fn set_attestor(env: Env, admin: Address, attestor: Option<BytesN<32>>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        timelock::require_unlocked(&env);
+        match attestor {
+            Some(key) => env.storage().instance().set(&DataKey::Attestor, &key),
+            None => env.storage().instance().remove(&DataKey::Attestor),
+        }
+    }
+
+    /// Hand resolution to an M‑of‑N committee, or back to the admin alone
+    /// with `None`.
+    pub fn set_committee(env: Env, admin: Address, committee: Option<Committee>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        timelock::require_unlocked(&env);
+        match committee {
+            Some(c) => {
+                if c.threshold == 0 || c.threshold > c.members.len() {
+                    panic_with_error!(env, Error::InvalidCommittee);
+                }
+                env.storage().instance().set(&DataKey::Committee, &c);
+            }
+            None => env.storage().instance().remove(&DataKey::Committee),
+        }
+    }
+
+    /// Let rounds be started in `token` (`allowed`) or not. At most
+    /// `MAX_TOKENS` tokens may be listed; rounds already running keep
+    /// their token.
+    pub fn set_token_allowed(env: Env, admin: Address, token: Address, allowed: bool) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        timelock::require_unlocked(&env);
+        allow_token(&env, token, allowed);
+    }
+
+    /// Tokens rounds may be started in besides the deployment's own.
+    pub fn get_allowed_tokens(env: Env) -> Vec<Address> {
+        allowed_tokens(&env)
+    }
+
+    /// Cap how many rounds may be unresolved at once, or lift the cap with
+    /// `None`. Starting a round beyond it fails with `TooManyOpenRounds`;
+    /// rounds already running are unaffected.
+    pub fn set_max_open_rounds(env: Env, admin: Address, max: Option<u32>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let mut config = load_config(&env);
+        config.max_open_rounds = match max {
+            Some(0) => panic_with_error!(env, Error::InvalidRules),
+            Some(max) => max,
+            None => 0,
+        };
+        save_config(&env, &config);
+    }
+
+    /// Set the keeper fee paid to `public_resolve` callers, in basis points
+    /// of the pot (at most `MAX_KEEPER_FEE_BPS`). Applies to rounds started
+    /// afterwards.
+    pub fn set_keeper_fee_bps(env: Env, manager: Address, bps: u32) {
+        require_live(&env);
+        require_role(&env, Role::FeeManager, &manager);
+        timelock::require_unlocked(&env);
+        store_fee(&env, Fee::Keeper, bps);
+    }
+
+    /// Give rounds started afterwards `ledgers` of grace past finality
+    /// before they expire and refund. Must exceed `PUBLIC_RESOLVE_LEDGERS`
+    /// and be at most `MAX_GRACE_LEDGERS`.
+    pub fn set_grace_ledgers(env: Env, admin: Address, ledgers: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let mut config = load_config(&env);
+        config.grace_ledgers = ledgers;
+        save_config(&env, &config);
+    }
+
+    /// Keep claims open `ledgers` past the amend window in rounds started
+    /// afterwards, after which what is left may be swept
+    /// (`sweep_unclaimed`); 0, the default, keeps them open for ever.
+    pub fn set_claim_window(env: Env, admin: Address, ledgers: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let mut config = load_config(&env);
+        config.claim_window_ledgers = ledgers;
+        save_config(&env, &config);
+    }
+
+    /// Charge `bps` (at most `MAX_CANCEL_FEE_BPS`) of any stake taken back
+    /// with `reduce_bet`, paid to the admin. Zero by default.
+    pub fn set_cancel_fee_bps(env: Env, manager: Address, bps: u32) {
+        require_live(&env);
+        require_role(&env, Role::FeeManager, &manager);
+        timelock::require_unlocked(&env);
+        store_fee(&env, Fee::Cancel, bps);
+    }
+
+    /// Take `bps` (at most `MAX_CLAIM_FEE_BPS`) of winnings as they are
+    /// claimed, on top of any round's protocol fee, for the fee collector.
+    /// Returned stakes are not charged. Zero by default.
+    pub fn set_fee_bps(env: Env, manager: Address, bps: u32) {
+        require_live(&env);
+        require_role(&env, Role::FeeManager, &manager);
+        timelock::require_unlocked(&env);
+        store_fee(&env, Fee::Claim, bps);
+    }
+
+    /// Send claim fees to `collector` rather than the admin.
+    pub fn set_fee_collector(env: Env, admin: Address, collector: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        env.storage()
+            .instance()
+            .set(&DataKey::FeeCollector, &collector);
+    }
+
+    /// Stop bets on either side of an open over/under round from taking its
+    /// pool past `max_ratio_bps` of the other (30 000 for 3:1), or lift the
+    /// cap with `None`. Bets past it fail with `BetCapExceeded`.
+    pub fn set_max_imbalance(env: Env, admin: Address, round_id: u32, max_ratio_bps: Option<u32>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        if !matches!(round.market, Market::Invocations | Market::Price(_)) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        let key = DataKey::MaxImbalance(round_id);
+        match max_ratio_bps {
+            Some(bps) if bps < 10_000 => panic_with_error!(env, Error::InvalidRules),
+            Some(bps) => env.storage().persistent().set(&key, &bps),
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// Set every deployment‑wide tunable at once. An out‑of‑range value
+    /// fails with `ZeroAmount`, `InvalidFee` or `InvalidRules`, and a new
+    /// claim fee with `TooEarly` behind a timelock.
+    pub fn set_config(env: Env, admin: Address, config: Config) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if config.fee_bps != load_config(&env).fee_bps {
+            timelock::require_unlocked(&env);
+        }
+        save_config(&env, &config);
+    }
+
+    /// The deployment‑wide tunables.
+    pub fn get_config(env: Env) -> Config {
+        load_config(&env)
+    }
 
-        let round: Round = env
-            .storage()
+    /// Set the smallest bet taken, in token minor units (at least 1).
+    pub fn set_min_bet(env: Env, admin: Address, min_bet: i128) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let mut config = load_config(&env);
+        config.min_bet = min_bet;
+        save_config(&env, &config);
+    }
+
+    /// Limit how much one player may stake in a round, how large a round's
+    /// pot may grow and how much one player may have riding on unresolved
+    /// rounds, or lift every limit with `None`. Bets past any fail with
+    /// `BetCapExceeded`. Exposure counts stakes placed while it is capped.
+    pub fn set_bet_caps(env: Env, admin: Address, caps: Option<BetCaps>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        match caps {
+            Some(c) if c.max_stake < 0 || c.max_pool < 0 || c.max_exposure < 0 => {
+                panic_with_error!(env, Error::ZeroAmount)
+            }
+            Some(c) => env.storage().instance().set(&DataKey::BetCaps, &c),
+            None => env.storage().instance().remove(&DataKey::BetCaps),
+        }
+    }
+
+    /// Point the contract at the invocation‑count oracle used by
+    /// `public_resolve` (unless a feed set is configured).
+    pub fn set_oracle(env: Env, admin: Address, oracle: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        timelock::require_unlocked(&env);
+        env.storage().instance().set(&DataKey::Oracle, &oracle);
+    }
+
+    /// Read counts from several oracles (up to `MAX_FEEDS`) by median, or
+    /// go back to the single `set_oracle` one with `None`.
+    pub fn set_feeds(env: Env, admin: Address, feeds: Option<FeedSet>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        timelock::require_unlocked(&env);
+        match feeds {
+            Some(f) => {
+                if f.oracles.is_empty() || f.oracles.len() > MAX_FEEDS {
+                    panic_with_error!(env, Error::InvalidFeeds);
+                }
+                env.storage().instance().set(&DataKey::Feeds, &f);
+            }
+            None => env.storage().instance().remove(&DataKey::Feeds),
+        }
+    }
+
+    /// Point the contract at the Kale farm epoch schedule used by
+    /// `start_epoch_round`.
+    pub fn set_farm(env: Env, admin: Address, farm: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        env.storage().instance().set(&DataKey::Farm, &farm);
+    }
+
+    /// Keep a new round's stakes in temporary storage, which is far cheaper
+    /// for fast‑turnover markets, until its claims close at the latest.
+    /// The round must be open with no stakes yet and have a claim window,
+    /// and all of it — finality, grace, amendments and claims — must fit
+    /// within the network's longest entry TTL (`InvalidRules` otherwise).
+    /// Stakes, refunds included, are gone once that ledger is past.
+    pub fn set_short_lived(env: Env, admin: Address, round_id: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let rules = round.rules.clone();
+        if round.positions > 0 || rules.claim_window_ledgers == 0 {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        let until = round.finality.ledger(&env)
+            + rules.grace_ledgers
+            + rules.amend_window_ledgers
+            + rules.claim_window_ledgers;
+        if until - env.ledger().sequence() > env.storage().max_ttl() {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        env.storage()
             .persistent()
-            .get(&DataKey::Round(round_id))
-            .unwrap_or_else(|| panic_with_error!(env, Error::RoundNotFound));
+            .set(&DataKey::ShortLived(round_id), &until);
+    }
 
-        if !round.resolved {
-            panic_with_error!(env, Error::NotResolved);
+    /// Install (or, with `None`, remove) the bet acceptance hook.
+    pub fn set_risk_config(env: Env, admin: Address, config: Option<RiskConfig>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        match config {
+            Some(cfg) => env.storage().instance().set(&DataKey::Risk, &cfg),
+            None => env.storage().instance().remove(&DataKey::Risk),
+        }
+    }
+
+    // ---------------------------------------------------
+    // Betting
+    // ---------------------------------------------------
+
+    /// Stake `amount` on `side`. Over/under stakes may be topped up on
+    /// either side of the line (a hedge); otherwise a player keeps to one
+    /// side.
+    pub fn bet(env: Env, player: Address, round_id: u32, side: Side, amount: i128) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+        place_bet(&env, Some(&player), &player, round_id, side, amount);
+    }
+
+    /// `bet` that fails with `SlippageExceeded` if, counting the bet
+    /// itself, a win would now pay back less than `min_odds_bps` per unit
+    /// staked (15 000 for 1.5×) — protection against the pools moving
+    /// before the transaction lands. Not for scalar rounds.
+    pub fn bet_at_odds(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        side: Side,
+        amount: i128,
+        min_odds_bps: u32,
+    ) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+        let round = load_round_advanced(&env, round_id);
+        let odds = odds_bps(&env, &round, side, amount)
+            .unwrap_or_else(|| panic_with_error!(env, Error::WrongMarket));
+        if odds < i128::from(min_odds_bps) {
+            panic_with_error!(env, Error::SlippageExceeded);
+        }
+        place_bet(&env, Some(&player), &player, round_id, side, amount);
+    }
+
+    /// `bet` tagged with a short `memo` (a campaign, a referring channel)
+    /// for attribution. The memo is not stored, only evented as
+    /// `("memo", memo, player) → (round_id, side, amount)`.
+    pub fn bet_with_memo(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        side: Side,
+        amount: i128,
+        memo: Symbol,
+    ) {
+        Self::bet(env.clone(), player.clone(), round_id, side, amount);
+        events::publish(&env, "memo", (memo, player), (round_id, side, amount));
+    }
+
+    /// Place several `(round_id, side, amount)` bets under one
+    /// authorisation, all or none of them (at most `MAX_BET_LEGS`).
+    pub fn bet_many(env: Env, player: Address, bets: Vec<(u32, Side, i128)>) {
+        require_live(&env);
+        if bets.is_empty() || bets.len() > MAX_BET_LEGS {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        player.require_auth();
+        for (round_id, side, amount) in bets.iter() {
+            require_min_bet(&env, amount);
+            place_bet(&env, Some(&player), &player, round_id, side, amount);
+        }
+    }
+
+    /// `bet` paid for by `sponsor` on behalf of `beneficiary`, who holds
+    /// the stake and its claim (promotions, gifts). Evented as
+    /// `("sponsor", sponsor, beneficiary) → (round_id, amount)`.
+    pub fn bet_for(
+        env: Env,
+        sponsor: Address,
+        beneficiary: Address,
+        round_id: u32,
+        side: Side,
+        amount: i128,
+    ) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        sponsor.require_auth();
+        place_bet(&env, Some(&sponsor), &beneficiary, round_id, side, amount);
+        events::publish(&env, "sponsor", (sponsor, beneficiary), (round_id, amount));
+    }
+
+    /// Take `amount` of the main side of `player`'s stake back while
+    /// betting is still open, less the cancellation fee. Taking it all
+    /// leaves any hedge as the stake. Returns what was paid out.
+    pub fn reduce_bet(env: Env, player: Address, round_id: u32, amount: i128) -> i128 {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        player.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        if duels::is_duel(&env, round_id) {
+            panic_with_error!(env, Error::Unauthorized);
         }
+        let stake = load_stake(&env, round_id, &player)
+            .filter(|s| s.amount >= amount)
+            .unwrap_or_else(|| panic_with_error!(env, Error::InsufficientShares));
 
-        let stake_key = DataKey::Stake(round_id, player.clone());
-        let stake: Stake = env
+        adjust_pool(&env, &mut round, stake.side, -amount);
+        shrink_stake(&env, round_id, &mut round, &player, stake, amount);
+        save_round(&env, round_id, &round);
+
+        let fee_bps: u32 = env
             .storage()
-            .persistent()
-            .get(&stake_key)
-            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+            .instance()
+            .get(&DataKey::CancelFeeBps)
+            .unwrap_or(0);
+        let fee = mul_div(&env, amount, i128::from(fee_bps), 10_000);
+        let returned = amount - fee;
+        // a negative bet: the season score gets the money back
+        seasons::record_bet(&env, round_id, &player, -returned, false);
 
-        // remove stake first to block re‑entrancy / double claim
-        env.storage().persistent().remove(&stake_key);
+        let token = round_token(&env, &round);
+        if fee > 0 {
+            pay(&env, &token, &get_admin(&env), fee);
+        }
+        pay(&env, &token, &player, returned);
+        returned
+    }
 
-        if stake.side != round.winning_side {
-            return; // loser gets nothing
+    /// Hand `amount` of `from`'s open stake to `to` (custody moves, OTC
+    /// sales) before the round is decided. Only `from`'s main side moves,
+    /// and `to` ends up on it — as a hedge if their own stake is on the
+    /// other side of the line; any other side is refused. `to` must be
+    /// able to bet on the round, and frozen stakes stay put. Evented as
+    /// `("stake", from, to) → (round_id, amount)`.
+    pub fn transfer_stake(env: Env, from: Address, to: Address, round_id: u32, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        if from == to {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        from.require_auth();
+        if compliance::is_frozen(&env, &from) {
+            panic_with_error!(env, Error::RiskRejected);
         }
+        roles::require_outsider(&env, &to);
+        compliance::require_unblocked(&env, &to);
+        compliance::require_allowed(&env, round_id, &to);
 
-        let side_pool = match round.winning_side {
-            Side::Higher => round.high_pool,
-            Side::Lower => round.low_pool,
+        let mut round = load_round_advanced(&env, round_id);
+        if !matches!(round.state, RoundState::Open | RoundState::Locked) {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+        if duels::is_duel(&env, round_id) {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        let sent = load_stake(&env, round_id, &from)
+            .filter(|s| s.amount >= amount)
+            .unwrap_or_else(|| panic_with_error!(env, Error::InsufficientShares));
+        let received = match load_stake(&env, round_id, &to) {
+            Some(s) if s.side == sent.side => Stake {
+                amount: checked_add(&env, s.amount, amount),
+                ..s
+            },
+            Some(s) if s.side.opposite() == Some(sent.side) => {
+                if insurance::is_insured(&env, round_id, &to) {
+                    panic_with_error!(env, Error::WrongMarket);
+                }
+                Stake {
+                    hedge: checked_add(&env, s.hedge, amount),
+                    ..s
+                }
+            }
+            Some(_) => panic_with_error!(env, Error::InvalidSide),
+            None => {
+                round.positions += 1;
+                history::record(&env, &to, round_id);
+                Stake {
+                    amount,
+                    side: sent.side,
+                    hedge: 0,
+                }
+            }
         };
-        let total_pool = round.high_pool + round.low_pool;
 
-        let payout = stake.amount * total_pool / side_pool;
-        token_client(&env).transfer(&env.current_contract_address(), &player, &payout);
+        shrink_stake(&env, round_id, &mut round, &from, sent, amount);
+        save_stake(&env, round_id, &to, &received);
+        save_round(&env, round_id, &round);
+        events::publish(&env, "stake", (from, to), (round_id, amount));
     }
 
-    /// Refund original stake if admin never resolved within grace period.
-    pub fn refund(env: Env, player: Address, round_id: u32) {
-        player.require_auth();
+    // ---------------------------------------------------
+    // Resolution
+    // ---------------------------------------------------
+
+    /// Resolve as the admin or, when a committee is configured, cast
+    /// `resolver`'s vote; the round settles on the `threshold`‑th matching
+    /// count. A member cannot vote twice or change their vote.
+    ///
+    /// `observed_count` is the invocation count read at finality; the round
+    /// is decided on its delta over `baseline_count`. With a feed set
+    /// configured the feeds' median is used instead, so resolving only
+    /// confirms it (and fails with `OracleDisagreement` while they diverge).
+    pub fn resolve_round(env: Env, resolver: Address, round_id: u32, observed_count: u32) {
+        Self::resolve_round_with_evidence(env, resolver, round_id, observed_count, None)
+    }
+
+    /// `resolve_round` that also records a 32‑byte `evidence` hash (e.g. of
+    /// the indexer snapshot the count was read from). With a committee the
+    /// deciding vote's evidence is kept.
+    pub fn resolve_round_with_evidence(
+        env: Env,
+        resolver: Address,
+        round_id: u32,
+        observed_count: u32,
+        evidence: Option<BytesN<32>>,
+    ) {
+        require_live(&env);
+        let committee: Option<Committee> = env.storage().instance().get(&DataKey::Committee);
+        match &committee {
+            Some(c) if !c.members.contains(&resolver) => {
+                panic_with_error!(env, Error::Unauthorized)
+            }
+            Some(_) => resolver.require_auth(),
+            None => require_role(&env, Role::Resolver, &resolver),
+        }
+
+        let mut round = load_round_advanced(&env, round_id);
+        require_resolvable(&env, &round);
+        require_count_market(&env, &round);
+        let observed_count = match feed_count(&env, round.rules.metric) {
+            Some(reading) => reading.unwrap_or_else(|err| panic_with_error!(env, err)),
+            None => observed_count,
+        };
+
+        if let Some(c) = committee {
+            let votes_key = DataKey::Votes(round_id);
+            let mut votes: Map<Address, u32> = env
+                .storage()
+                .persistent()
+                .get(&votes_key)
+                .unwrap_or_else(|| Map::new(&env));
+            match votes.get(resolver.clone()) {
+                Some(prev) if prev == observed_count => {
+                    panic_with_error!(env, Error::DuplicateVote)
+                }
+                Some(_) => panic_with_error!(env, Error::ConflictingVote),
+                None => votes.set(resolver.clone(), observed_count),
+            }
+
+            let matching = votes
+                .values()
+                .iter()
+                .filter(|&v| v == observed_count)
+                .count();
+            if (matching as u32) < c.threshold {
+                env.storage().persistent().set(&votes_key, &votes);
+                return;
+            }
+            env.storage().persistent().remove(&votes_key);
+        }
+
+        round.resolved_by = Some(resolver);
+        if let Some(hash) = evidence {
+            round.evidence = hash;
+        }
+        finish_resolution(&env, round_id, &mut round, observed_count);
+    }
 
-        let round: Round = env
+    /// Settle from a count signed off‑chain by the configured attestor;
+    /// anyone may relay it. The signature covers the XDR of
+    /// `(contract address, round_id, observed_count)`, so it cannot be
+    /// replayed on another round or deployment.
+    pub fn resolve_attested(env: Env, round_id: u32, observed_count: u32, signature: BytesN<64>) {
+        require_live(&env);
+        let attestor: BytesN<32> = env
             .storage()
-            .persistent()
-            .get(&DataKey::Round(round_id))
-            .unwrap_or_else(|| panic_with_error!(env, Error::RoundNotFound));
+            .instance()
+            .get(&DataKey::Attestor)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AttestorNotSet));
+
+        let mut round = load_round_advanced(&env, round_id);
+        require_resolvable(&env, &round);
+
+        let message = (env.current_contract_address(), round_id, observed_count).to_xdr(&env);
+        env.crypto().ed25519_verify(&attestor, &message, &signature);
+
+        // relayed by anyone: the attestation itself is the provenance
+        round.evidence = env.crypto().sha256(&signature.into()).into();
+        finish_resolution(&env, round_id, &mut round, observed_count);
+    }
+
+    /// Correct a fat‑fingered count within `AMEND_WINDOW_LEDGERS` of
+    /// resolution. No claims can have been paid yet, so the outcome is simply
+    /// recomputed; afterwards the resolution is immutable.
+    pub fn amend_resolution(env: Env, resolver: Address, round_id: u32, corrected_count: u32) {
+        require_live(&env);
+        require_role(&env, Role::Resolver, &resolver);
 
-        if round.resolved {
+        let mut round = load_round_advanced(&env, round_id);
+        match round.state {
+            RoundState::Resolved => {}
+            RoundState::Created | RoundState::Open | RoundState::Locked => {
+                panic_with_error!(env, Error::NotResolved)
+            }
+            // window over, or a cancelled / expired round with no count
+            _ => panic_with_error!(env, Error::AmendWindowClosed),
+        }
+
+        settle(&env, &mut round, corrected_count);
+        save_round(&env, round_id, &round);
+        events::publish(
+            &env,
+            "round_resolved",
+            (round_id,),
+            (round.outcome, round.actual_count, total_pool(&round)),
+        );
+    }
+
+    /// Void a round before resolution (wrong parameters, oracle failure…).
+    /// Every bettor can `claim` their exact stake back straight away; a
+    /// creator's bond on the round is forfeited to the admin.
+    pub fn cancel_round(env: Env, resolver: Address, round_id: u32) {
+        require_live(&env);
+        require_role(&env, Role::Resolver, &resolver);
+
+        let mut round = load_round_advanced(&env, round_id);
+        if !matches!(round.state, RoundState::Open | RoundState::Locked) {
             panic_with_error!(env, Error::AlreadyResolved);
         }
 
-        if env.ledger().sequence() <= round.finality_ledger + GRACE_LEDGERS {
-            panic_with_error!(env, Error::RefundNotAvailable);
+        round.outcome = Outcome::Voided;
+        round.resolved_by = Some(resolver.clone());
+        round.resolution_ledger = env.ledger().sequence();
+        transition(&env, round_id, &mut round, RoundState::Voided);
+        save_round(&env, round_id, &round);
+        creators::slash(&env, round_id);
+        events::publish(&env, "cancelled", (round_id,), resolver);
+    }
+
+    /// Void a round whose betting closed with one side of the book empty
+    /// (for bucket and scalar rounds: with at most one bucket or guess
+    /// backed): there is
+    /// nothing to win, so rather than waiting on a 1:1 resolution or the
+    /// grace period, anyone may hand the stakes straight back. Sealed
+    /// rounds only count once their reveal window has closed.
+    pub fn void_one_sided(env: Env, round_id: u32) {
+        require_live(&env);
+
+        let mut round = load_round_advanced(&env, round_id);
+        match round.state {
+            RoundState::Locked => {}
+            RoundState::Created | RoundState::Open => panic_with_error!(env, Error::TooEarly),
+            _ => panic_with_error!(env, Error::AlreadyResolved),
+        }
+        // the vault or maker takes the other side of every trade
+        if has_maker(&env, round_id) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        // sealed pools only show once the reveal window is over
+        if sealed::is_sealed(&env, round_id) && !round.finality.reached(&env) {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        let backed_sides = [round.high_pool, round.low_pool]
+            .into_iter()
+            .chain(round.bucket_pools.iter())
+            .chain(round.guesses.values())
+            .filter(|&p| p > 0)
+            .count();
+        if backed_sides > 1 {
+            panic_with_error!(env, Error::NotOneSided);
+        }
+
+        round.outcome = Outcome::Voided;
+        round.resolution_ledger = env.ledger().sequence();
+        transition(&env, round_id, &mut round, RoundState::Voided);
+        save_round(&env, round_id, &round);
+    }
+
+    /// Permissionless fallback once the admin has missed the round's
+    /// public resolve delay past finality: reads the count from the
+    /// oracle and pays `caller` the keeper fee out of the pot. Closes when
+    /// the refund grace period opens.
+    pub fn public_resolve(env: Env, caller: Address, round_id: u32) {
+        require_live(&env);
+        caller.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        require_resolvable(&env, &round);
+        if !round
+            .finality
+            .plus(round.rules.public_resolve_ledgers)
+            .passed(&env)
+        {
+            panic_with_error!(env, Error::TooEarly);
+        }
+
+        let observed_count = oracle_count(&env, round.rules.metric)
+            .unwrap_or_else(|| panic_with_error!(env, Error::OracleNotSet));
+
+        round.bounty = mul_div(
+            &env,
+            total_pool(&round),
+            i128::from(round.rules.keeper_fee_bps),
+            10_000,
+        );
+        round.resolved_by = Some(caller.clone());
+        finish_resolution(&env, round_id, &mut round, observed_count);
+
+        if round.bounty > 0 {
+            pay(&env, &round_token(&env, &round), &caller, round.bounty);
+        }
+    }
+
+    // ---------------------------------------------------
+    // Claim & refund
+    // ---------------------------------------------------
+
+    /// Collect winnings, or the stake back from a push, voided or expired
+    /// round.
+    pub fn claim(env: Env, player: Address, round_id: u32) {
+        Self::claim_to(env, player.clone(), round_id, player);
+    }
+
+    /// `claim`, paying `recipient` (a cold wallet, an integrating
+    /// contract) instead of the player.
+    pub fn claim_to(env: Env, player: Address, round_id: u32, recipient: Address) {
+        require_live(&env);
+        player.require_auth();
+        extend_instance(&env);
+
+        let (round, payout) = settle_stake(&env, &player, round_id);
+        if payout > 0 {
+            pay(&env, &round_token(&env, &round), &recipient, payout);
+        }
+    }
+
+    /// `claim` on each of `round_ids` (at most `MAX_CLAIM_ROUNDS`), all
+    /// or none of them, paying the total once per token.
+    pub fn claim_all(env: Env, player: Address, round_ids: Vec<u32>) {
+        require_live(&env);
+        if round_ids.is_empty() || round_ids.len() > MAX_CLAIM_ROUNDS {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        player.require_auth();
+
+        let mut owed: Map<Address, i128> = Map::new(&env);
+        for round_id in round_ids.iter() {
+            let (round, payout) = settle_stake(&env, &player, round_id);
+            let so_far = owed.get(round.token.clone()).unwrap_or(0);
+            owed.set(round.token, checked_add(&env, so_far, payout));
+        }
+        for (token, payout) in owed.iter() {
+            if payout > 0 {
+                pay(&env, &token::Client::new(&env, &token), &player, payout);
+            }
+        }
+    }
+
+    /// Pay each of `batch` (at most `MAX_DISTRIBUTE_BATCH` players) what
+    /// `claim` would, straight to them, so passive winners are not left
+    /// waiting. Anyone may call it; players without a stake, or with
+    /// frozen stakes, are skipped. Returns the total paid.
+    pub fn distribute(env: Env, round_id: u32, batch: Vec<Address>) -> i128 {
+        require_live(&env);
+        if batch.is_empty() || batch.len() > MAX_DISTRIBUTE_BATCH {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        let mut paid = 0;
+        for player in batch.iter() {
+            if load_stake(&env, round_id, &player).is_none() || compliance::is_frozen(&env, &player)
+            {
+                continue;
+            }
+            let (round, payout) = settle_stake(&env, &player, round_id);
+            if payout > 0 {
+                pay(&env, &round_token(&env, &round), &player, payout);
+                paid = checked_add(&env, paid, payout);
+            }
+        }
+        paid
+    }
+
+    /// `claim`, betting `rebet_bps` of the payout (10 000 for all of it)
+    /// on `side` of `next_round_id` — staked in the same token — without
+    /// it leaving the contract. The rest is paid out. Returns the amount
+    /// rebet.
+    pub fn claim_and_rebet(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        next_round_id: u32,
+        side: Side,
+        rebet_bps: u32,
+    ) -> i128 {
+        require_live(&env);
+        if rebet_bps == 0 || rebet_bps > 10_000 {
+            panic_with_error!(env, Error::InvalidFee);
+        }
+        player.require_auth();
+
+        let (round, payout) = settle_stake(&env, &player, round_id);
+        if load_round(&env, next_round_id).token != round.token {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        let rebet = mul_div(&env, payout, i128::from(rebet_bps), 10_000);
+        require_min_bet(&env, rebet);
+        place_bet(&env, None, &player, next_round_id, side, rebet);
+        if payout > rebet {
+            pay(&env, &round_token(&env, &round), &player, payout - rebet);
+        }
+        rebet
+    }
+
+    /// Delete a round that is done with: archived, its claim window over,
+    /// its protocol fee collected, no stake entry left (see
+    /// `clear_stakes`), and nothing beside it still paying out or reading
+    /// it — a jackpot, creator bond, maker, outcome shares, reclaimable
+    /// sealed bets, insurance policies, series or conditional rounds
+    /// (`WrongMarket`). Anyone may call it and receives the round's dust,
+    /// if `sweep_dust` has not taken it. Short‑lived rounds' stakes have
+    /// expired by then. Evented as `("pruned", round_id) → (caller,
+    /// bounty)`. Returns the bounty.
+    pub fn prune_round(env: Env, caller: Address, round_id: u32) -> i128 {
+        require_live(&env);
+        caller.require_auth();
+        let round = load_round(&env, round_id);
+        if round.state != RoundState::Archived {
+            panic_with_error!(env, Error::ClaimsNotOpen);
+        }
+        match claim_deadline(&round) {
+            Some(deadline) if env.ledger().sequence() > deadline => {}
+            _ => panic_with_error!(env, Error::TooEarly),
+        }
+        if !round.fee_collected && round.protocol_fee > 0 {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        if has_maker(&env, round_id)
+            || jackpot::is_pending(&env, round_id, &round)
+            || creators::is_bonded(&env, round_id)
+            || sets::is_outstanding(&env, round_id, &round)
+            || sealed::is_reclaimable(&env, round_id, &round)
+            || liens(&env, round_id) > 0
+            || (round.positions > 0 && stakes_until(&env, round_id).is_none())
+        {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+
+        let store = env.storage().persistent();
+        for key in [
+            DataKey::Round(round_id),
+            DataKey::ShortLived(round_id),
+            DataKey::Bettors(round_id),
+            DataKey::Allowlist(round_id),
+            DataKey::Votes(round_id),
+            DataKey::Proposal(round_id),
+            DataKey::Precondition(round_id),
+            DataKey::MaxImbalance(round_id),
+            DataKey::Sealed(round_id),
+            DataKey::SealedEscrow(round_id),
+            DataKey::ShareSupply(round_id, Side::Higher),
+            DataKey::ShareSupply(round_id, Side::Lower),
+            DataKey::Jackpot(round_id),
+            DataKey::Duel(round_id),
+            DataKey::Referrals(round_id),
+        ] {
+            store.remove(&key);
+        }
+        let dust = total_pool(&round) - round.bounty - round.protocol_fee - round.paid_out;
+        let bounty = dust.max(0);
+        if bounty > 0 {
+            pay(&env, &round_token(&env, &round), &caller, bounty);
+        }
+        events::publish(&env, "pruned", (round_id,), (caller, bounty));
+        bounty
+    }
+
+    /// Delete the stake entries of `players` (at most
+    /// `MAX_DISTRIBUTE_BATCH`) that a round's claim window closed on, once
+    /// `sweep_unclaimed` has archived it, so it can be pruned. Anyone may
+    /// call it; players without a stake there are skipped. Returns how
+    /// many were deleted.
+    pub fn clear_stakes(env: Env, round_id: u32, players: Vec<Address>) -> u32 {
+        require_live(&env);
+        if players.is_empty() || players.len() > MAX_DISTRIBUTE_BATCH {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        let mut round = load_round(&env, round_id);
+        if round.state != RoundState::Archived {
+            panic_with_error!(env, Error::ClaimsNotOpen);
+        }
+        let mut cleared = 0;
+        for player in players.iter() {
+            if load_stake(&env, round_id, &player).is_some() {
+                remove_stake(&env, round_id, &player);
+                round.positions -= 1;
+                cleared += 1;
+            }
+        }
+        save_round(&env, round_id, &round);
+        cleared
+    }
+
+    /// Send a round's protocol fee to the admin once its claims are open.
+    pub fn collect_fee(env: Env, admin: Address, round_id: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+
+        let mut round = load_round_advanced(&env, round_id);
+        if round.state == RoundState::Resolved {
+            panic_with_error!(env, Error::ClaimsNotOpen);
+        }
+        if !matches!(round.state, RoundState::Settled | RoundState::Archived) {
+            panic_with_error!(env, Error::NotResolved);
+        }
+        if round.fee_collected || round.protocol_fee == 0 {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+
+        round.fee_collected = true;
+        save_round(&env, round_id, &round);
+        let fee = round.protocol_fee - creators::creator_share(&env, round_id, &round);
+        let fee = fee - referrals::accrue(&env, round_id, &round, fee);
+        pay(&env, &round_token(&env, &round), &admin, fee);
+    }
+
+    /// Once every stake in a round has been claimed, send the rounding
+    /// left over from splitting its pot to the admin. Anyone may call it.
+    /// Returns the amount.
+    pub fn sweep_dust(env: Env, round_id: u32) -> i128 {
+        require_live(&env);
+        let mut round = load_round(&env, round_id);
+        if round.state != RoundState::Archived {
+            panic_with_error!(env, Error::ClaimsNotOpen);
+        }
+        let dust = total_pool(&round) - round.bounty - round.protocol_fee - round.paid_out;
+        if dust <= 0 {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        round.paid_out = checked_add(&env, round.paid_out, dust);
+        save_round(&env, round_id, &round);
+        pay(&env, &round_token(&env, &round), &get_admin(&env), dust);
+        dust
+    }
+
+    /// Once a round's claim window has run out, archive it and take what
+    /// its stakes left unclaimed: a round in the deployment's token rolls
+    /// it into the next count round's jackpot, any other sends it to the
+    /// admin. Anyone may call it. Returns the amount.
+    pub fn sweep_unclaimed(env: Env, round_id: u32) -> i128 {
+        require_live(&env);
+        let mut round = load_round_advanced(&env, round_id);
+        require_settled(&env, &round);
+        match claim_deadline(&round) {
+            Some(deadline) if env.ledger().sequence() > deadline => {}
+            _ => panic_with_error!(env, Error::TooEarly),
+        }
+
+        let unclaimed = total_pool(&round) - round.bounty - round.protocol_fee - round.paid_out;
+        round.paid_out = checked_add(&env, round.paid_out, unclaimed);
+        // the stakes left stand until `clear_stakes`
+        transition(&env, round_id, &mut round, RoundState::Archived);
+        save_round(&env, round_id, &round);
+        if unclaimed > 0 {
+            if in_default_token(&env, &round) {
+                let rollover: i128 = env
+                    .storage()
+                    .instance()
+                    .get(&DataKey::Rollover)
+                    .unwrap_or(0);
+                env.storage()
+                    .instance()
+                    .set(&DataKey::Rollover, &checked_add(&env, rollover, unclaimed));
+            } else {
+                pay(
+                    &env,
+                    &round_token(&env, &round),
+                    &get_admin(&env),
+                    unclaimed,
+                );
+            }
         }
+        unclaimed
+    }
+
+    /// What `claim` would pay `player` right now, claim fee deducted: 0
+    /// before claims open, after a loss or once claimed.
+    pub fn get_claimable(env: Env, player: Address, round_id: u32) -> i128 {
+        let mut round = load_round(&env, round_id);
+        advance(&env, round_id, &mut round, false);
+        match load_stake(&env, round_id, &player) {
+            Some(stake) if round.state == RoundState::Settled => {
+                let payout = gross_payout(&env, &round, &stake);
+                payout - claim_fee(&env, &round, payout)
+            }
+            _ => 0,
+        }
+    }
 
-        let stake_key = DataKey::Stake(round_id, player.clone());
-        let stake: Stake = env
+    /// Pay the claim fees accrued in `token` to the fee collector.
+    /// Returns the amount.
+    pub fn withdraw_fees(env: Env, collector: Address, token: Address) -> i128 {
+        require_live(&env);
+        let expected: Address = env
             .storage()
+            .instance()
+            .get(&DataKey::FeeCollector)
+            .unwrap_or_else(|| get_admin(&env));
+        if collector != expected {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        collector.require_auth();
+        let key = DataKey::AccruedFees(token.clone());
+        let held: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if held == 0 {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        env.storage().persistent().remove(&key);
+        pay(&env, &token::Client::new(&env, &token), &collector, held);
+        held
+    }
+
+    /// Claim fees accrued in `token` and not yet withdrawn.
+    pub fn get_accrued_fees(env: Env, token: Address) -> i128 {
+        env.storage()
             .persistent()
-            .get(&stake_key)
-            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+            .get(&DataKey::AccruedFees(token))
+            .unwrap_or(0)
+    }
+
+    /// Refund original stake once the round has expired unresolved; `claim`
+    /// does the same.
+    pub fn refund(env: Env, player: Address, round_id: u32) {
+        require_live(&env);
+        player.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+
+        if matches!(
+            round.state,
+            RoundState::Created | RoundState::Open | RoundState::Locked
+        ) {
+            panic_with_error!(env, Error::RefundNotAvailable);
+        }
+        if round.outcome != Outcome::Expired {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
 
         // remove stake first
-        env.storage().persistent().remove(&stake_key);
+        let stake = take_stake(&env, round_id, &mut round, &player);
+
+        // transfer original stake back, with its share of any forfeits
+        let payout = refund_share(&env, &round, &stake, total_pool(&round));
+        round.paid_out = checked_add(&env, round.paid_out, payout);
+        save_round(&env, round_id, &round);
+        seasons::record_payout(&env, round_id, &round, &player, &stake, payout);
+        stats::record_result(&env, &round, &player, &stake, payout);
+        if payout > 0 {
+            pay(&env, &round_token(&env, &round), &player, payout);
+        }
+        events::publish(&env, "refunded", (round_id, player), payout);
+    }
 
-        // transfer original stake back
-        token_client(&env).transfer(&env.current_contract_address(), &player, &stake.amount);
+    // ---------------------------------------------------
+    // Sunset
+    // ---------------------------------------------------
+
+    /// Start winding the deployment down. After `SUNSET_DELAY_LEDGERS` no new
+    /// rounds can start; running rounds settle as usual.
+    pub fn schedule_sunset(env: Env, admin: Address) -> u32 {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if env.storage().instance().has(&DataKey::Sunset) {
+            panic_with_error!(env, Error::SunsetActive);
+        }
+
+        let at = env.ledger().sequence() + SUNSET_DELAY_LEDGERS;
+        env.storage().instance().set(&DataKey::Sunset, &at);
+        events::publish(&env, "sunset", (), at);
+        at
+    }
+
+    /// One‑time hand‑over of the remaining balance to `successor` once the
+    /// sunset is in effect, every round is settled and the last one has had
+    /// `SUNSET_CLAIM_LEDGERS` for claims. Balances in the allowed tokens go
+    /// too (the event reports the deployment token's). Afterwards only
+    /// views work.
+    pub fn sunset_transfer(env: Env, admin: Address, successor: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+
+        let now = env.ledger().sequence();
+        let at: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::Sunset)
+            .unwrap_or_else(|| panic_with_error!(env, Error::TooEarly));
+        let last_settled: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::LastSettled)
+            .unwrap_or(0);
+        if now < at || open_rounds(&env) > 0 || now <= last_settled + SUNSET_CLAIM_LEDGERS {
+            panic_with_error!(env, Error::TooEarly);
+        }
+
+        env.storage().instance().set(&DataKey::Retired, &true);
+        let this = env.current_contract_address();
+        for token in allowed_tokens(&env).iter() {
+            let token = token::Client::new(&env, &token);
+            let balance = token.balance(&this);
+            if balance > 0 {
+                token.transfer(&this, &successor, &balance);
+            }
+            env.storage()
+                .instance()
+                .remove(&DataKey::Liabilities(token.address));
+        }
+        let token = token_client(&env);
+        let remaining = token.balance(&this);
+        if remaining > 0 {
+            token.transfer(&this, &successor, &remaining);
+        }
+        env.storage()
+            .instance()
+            .remove(&DataKey::Liabilities(token.address));
+        events::publish(&env, "retired", (successor,), remaining);
     }
 
-    /// Address that was set as admin in the constructor.
+    // ---------------------------------------------------
+    // Views
+    // ---------------------------------------------------
+
+    /// Address that was set as admin in the constructor, or the contract's
+    /// own once the admin has renounced.
     pub fn get_admin(env: Env) -> Address {
         get_admin(&env)
     }
 
-    /// Full `Round` data, or panics with `RoundNotFound` (#3).
-    pub fn get_round(env: Env, round_id: u32) -> Round {
+    /// What the contract holds in `token` on others' behalf: stakes and
+    /// pots not yet paid out, deposits, the vault and insurance reserve,
+    /// bonds, fees and rewards owed.
+    pub fn get_liabilities(env: Env, token: Address) -> i128 {
         env.storage()
-            .persistent()
-            .get(&DataKey::Round(round_id))
-            .unwrap_or_else(|| panic_with_error!(env, Error::RoundNotFound))
+            .instance()
+            .get(&DataKey::Liabilities(token))
+            .unwrap_or(0)
+    }
+
+    /// The contract's `token` balance beyond its liabilities — tokens sent
+    /// to it directly. Negative would mean it cannot pay what it owes.
+    pub fn get_surplus(env: Env, token: Address) -> i128 {
+        let balance = token::Client::new(&env, &token).balance(&env.current_contract_address());
+        balance - Self::get_liabilities(env, token)
+    }
+
+    /// What `player` has staked across unresolved rounds, as counted
+    /// against the exposure cap.
+    pub fn get_exposure(env: Env, player: Address) -> i128 {
+        open_exposure(&env, &player).values().iter().sum()
+    }
+
+    /// The wager token, its decimals and whether it is native XLM.
+    pub fn get_token_info(env: Env) -> TokenInfo {
+        let token = token_client(&env);
+        // `Asset::Native` as XDR
+        let native = env
+            .deployer()
+            .with_stellar_asset(Bytes::from_array(&env, &[0; 4]))
+            .deployed_address();
+        TokenInfo {
+            decimals: token.decimals(),
+            native: token.address == native,
+            token: token.address,
+        }
+    }
+
+    /// Ids of the rounds taking bets right now, oldest first.
+    pub fn get_open_rounds(env: Env) -> Vec<u32> {
+        let mut open = Vec::new(&env);
+        for (round_id, deadline) in live_rounds(&env).iter() {
+            if !deadline.passed(&env) {
+                open.push_back(round_id);
+            }
+        }
+        open
+    }
+
+    /// Full `Round` data, or panics with `RoundNotFound` (#3). Moves that
+    /// are due by ledger (locking, expiry, claims opening) are reported even
+    /// before anyone touches the round.
+    pub fn get_round(env: Env, round_id: u32) -> Round {
+        let mut round = load_round(&env, round_id);
+        advance(&env, round_id, &mut round, false);
+        round
+    }
+
+    /// Lifecycle state of a round; an expired round is `Voided` (then
+    /// `Settled`) with `Outcome::Expired`.
+    pub fn get_status(env: Env, round_id: u32) -> RoundState {
+        Self::get_round(env, round_id).state
+    }
+
+    /// Behavioural rules the round was started with.
+    pub fn get_rules(env: Env, round_id: u32) -> Rules {
+        load_round(&env, round_id).rules
+    }
+
+    /// Rounds not yet resolved or voided.
+    pub fn get_open_round_count(env: Env) -> u32 {
+        open_rounds(&env)
+    }
+
+    /// Ledger a short‑lived round keeps its stakes to, if it is one.
+    pub fn get_stakes_until(env: Env, round_id: u32) -> Option<u32> {
+        stakes_until(&env, round_id)
     }
 
     /// Caller’s stake for a round, or `None` if they never bet.
     pub fn get_stake(env: Env, player: Address, round_id: u32) -> Option<Stake> {
-        env.storage()
-            .persistent()
-            .get(&DataKey::Stake(round_id, player))
+        load_stake(&env, round_id, &player)
     }
 }
 
@@ -0,0 +1,311 @@
+//! LMSR market maker: an alternative to the parimutuel pool where a round
+//! always quotes a price. Bettors buy outcome shares that pay one token
+//! unit each if their side wins; buying `Δ` shares of a side costs
+//! `C(q + Δ) − C(q)` under Hanson's cost function
+//! `C(q) = b · ln(e^(q_higher/b) + e^(q_lower/b))`, so the implied
+//! probability moves continuously with every trade. The admin subsidises
+//! the maker's worst case, `b · ln 2`, when opening the round and takes
+//! back whatever is left once it settles.
+//!
+//! Arithmetic is in 18‑decimal fixed point. `C` is evaluated as
+//! `max(q) + b · ln(1 + e^(−|q_higher − q_lower| / b))`, which keeps both
+//! `exp` and `ln` on small, fast‑converging domains.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::state::load_round_advanced;
+use crate::{
+    check_risk, checked_add, compliance, create_round, default_rules, extend_written, load_round,
+    mul_div, pay, pull, require_admin, require_live, require_min_bet, require_paying, token_client,
+    DataKey, Deadline, Error, KalePrediction, KalePredictionArgs, KalePredictionClient, Market,
+    Round, RoundState, Side,
+};
+
+/// Fixed‑point one.
+pub(crate) const SCALE: i128 = 1_000_000_000_000_000_000;
+
+/// ln 2 in fixed point.
+const LN_2: i128 = 693_147_180_559_945_309;
+
+/// Beyond this exponent `e^(−x)` is below fixed‑point resolution.
+const EXP_CUTOFF: i128 = 42;
+
+/// `e^(−x)` for fixed‑point `x ≥ 0`: `x = k·ln 2 + r` with `r < ln 2`, a
+/// Taylor series for `e^(−r)`, then `k` halvings.
+pub(crate) fn exp_neg(x: i128) -> i128 {
+    if x >= EXP_CUTOFF * SCALE {
+        return 0;
+    }
+    let k = x / LN_2;
+    let r = x - k * LN_2;
+    let mut term = SCALE;
+    let mut sum = SCALE;
+    let mut n = 1;
+    while term != 0 {
+        term = -term * r / SCALE / n;
+        sum += term;
+        n += 1;
+    }
+    sum >> k
+}
+
+/// `ln(1 + y)` for fixed‑point `0 ≤ y ≤ 1`, via
+/// `ln z = 2·atanh((z − 1) / (z + 1))`.
+pub(crate) fn ln_1p(y: i128) -> i128 {
+    let t = y * SCALE / (2 * SCALE + y); // ≤ 1/3
+    let t2 = t * t / SCALE;
+    let mut term = t;
+    let mut sum = 0;
+    let mut n = 1;
+    while term != 0 {
+        sum += term / n;
+        term = term * t2 / SCALE;
+        n += 2;
+    }
+    2 * sum
+}
+
+/// `C(q)` in fixed‑point token units.
+pub(crate) fn cost(e: &Env, b: i128, higher: i128, lower: i128) -> i128 {
+    let (top, gap) = (higher.max(lower), checked_add(e, higher, -lower).abs());
+    let tail = if gap / b >= EXP_CUTOFF {
+        0
+    } else {
+        ln_1p(exp_neg(mul_div(e, gap, SCALE, b)))
+    };
+    checked_add(e, mul_div(e, top, SCALE, 1), mul_div(e, b, tail, 1))
+}
+
+/// Round's market maker state.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Amm {
+    pub b: i128,         // liquidity: higher is deeper and slower to move
+    pub higher: i128,    // shares sold per side
+    pub lower: i128,     // idem
+    pub collected: i128, // paid in by buyers
+    pub subsidy: i128,   // paid in by the admin, `b · ln 2` rounded up
+    pub closed: bool,    // remainder returned to the admin
+}
+
+/// A player's shares in one round.
+#[contracttype]
+#[derive(Clone, Copy, Default)]
+pub struct Holding {
+    pub higher: i128,
+    pub lower: i128,
+    pub paid: i128, // refunded if the round pushes or is voided
+}
+
+impl Amm {
+    /// Tokens (rounded up) to buy `shares` of `side`.
+    fn quote(&self, e: &Env, side: Side, shares: i128) -> i128 {
+        let (higher, lower) = match side {
+            Side::Higher => (checked_add(e, self.higher, shares), self.lower),
+            _ => (self.higher, checked_add(e, self.lower, shares)),
+        };
+        let delta = cost(e, self.b, higher, lower) - cost(e, self.b, self.higher, self.lower);
+        checked_add(e, delta, SCALE - 1) / SCALE
+    }
+
+    /// What the maker owes holders in total once `round` has settled.
+    fn owed(&self, round: &Round) -> i128 {
+        match round.outcome.winner() {
+            Some(Side::Higher) => self.higher,
+            Some(Side::Lower) => self.lower,
+            _ => self.collected,
+        }
+    }
+}
+
+fn load_amm(e: &Env, round_id: u32) -> Amm {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Amm(round_id))
+        .unwrap_or_else(|| panic_with_error!(e, Error::WrongMarket))
+}
+
+fn save_amm(e: &Env, round_id: u32, amm: &Amm) {
+    let key = DataKey::Amm(round_id);
+    e.storage().persistent().set(&key, amm);
+    extend_written(e, &key);
+}
+
+fn require_binary(e: &Env, side: Side) {
+    if !matches!(side, Side::Higher | Side::Lower) {
+        panic_with_error!(e, Error::InvalidSide);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Start a count round traded against an LMSR maker with liquidity
+    /// `b`, taking the maker's `b · ln 2` subsidy from `admin`.
+    pub fn start_lmsr_round(
+        env: Env,
+        admin: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+        b: i128,
+    ) -> u32 {
+        if b <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        let rules = default_rules(&env);
+        let round_id = create_round(
+            &env,
+            &admin,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        );
+
+        let subsidy = checked_add(&env, mul_div(&env, b, LN_2, 1), SCALE - 1) / SCALE;
+        pull(&env, &admin, subsidy);
+        let amm = Amm {
+            b,
+            higher: 0,
+            lower: 0,
+            collected: 0,
+            subsidy,
+            closed: false,
+        };
+        save_amm(&env, round_id, &amm);
+        round_id
+    }
+
+    /// Buy `shares` of `side` at the maker's price, paying at most
+    /// `max_cost` (else `SlippageExceeded`). A cost below the minimum bet
+    /// fails with `ZeroAmount`. Returns the cost.
+    pub fn buy_shares(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        side: Side,
+        shares: i128,
+        max_cost: i128,
+    ) -> i128 {
+        require_live(&env);
+        if shares <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        require_binary(&env, side);
+        player.require_auth();
+
+        let round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let mut amm = load_amm(&env, round_id);
+        let price = amm.quote(&env, side, shares);
+        if price > max_cost {
+            panic_with_error!(env, Error::SlippageExceeded);
+        }
+        require_min_bet(&env, price);
+        check_risk(&env, &player, round_id, price);
+
+        pull(&env, &player, price);
+        let key = DataKey::Holding(round_id, player);
+        let mut holding: Holding = env.storage().persistent().get(&key).unwrap_or_default();
+        match side {
+            Side::Higher => {
+                amm.higher = checked_add(&env, amm.higher, shares);
+                holding.higher = checked_add(&env, holding.higher, shares);
+            }
+            _ => {
+                amm.lower = checked_add(&env, amm.lower, shares);
+                holding.lower = checked_add(&env, holding.lower, shares);
+            }
+        }
+        amm.collected = checked_add(&env, amm.collected, price);
+        holding.paid = checked_add(&env, holding.paid, price);
+        save_amm(&env, round_id, &amm);
+        env.storage().persistent().set(&key, &holding);
+        extend_written(&env, &key);
+        price
+    }
+
+    /// Redeem a settled round's shares: one token unit per winning share,
+    /// or everything paid back if it pushed or was voided.
+    pub fn redeem_shares(env: Env, player: Address, round_id: u32) {
+        require_live(&env);
+        player.require_auth();
+        compliance::require_unfrozen(&env, &player);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        load_amm(&env, round_id);
+        let key = DataKey::Holding(round_id, player.clone());
+        let holding: Holding = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&key);
+
+        let payout = match round.outcome.winner() {
+            Some(Side::Higher) => holding.higher,
+            Some(Side::Lower) => holding.lower,
+            _ => holding.paid,
+        };
+        if payout > 0 {
+            pay(&env, &token_client(&env), &player, payout);
+        }
+    }
+
+    /// Return to the admin what the maker holds beyond what it owes
+    /// holders, once the round has settled.
+    pub fn close_amm(env: Env, admin: Address, round_id: u32) -> i128 {
+        require_live(&env);
+        require_admin(&env, &admin);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let mut amm = load_amm(&env, round_id);
+        if amm.closed {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        amm.closed = true;
+        save_amm(&env, round_id, &amm);
+
+        let remainder = checked_add(&env, amm.subsidy, amm.collected) - amm.owed(&round);
+        if remainder > 0 {
+            pay(&env, &token_client(&env), &admin, remainder);
+        }
+        remainder
+    }
+
+    /// Current cost of `shares` of `side`.
+    pub fn quote_shares(env: Env, round_id: u32, side: Side, shares: i128) -> i128 {
+        require_binary(&env, side);
+        load_round(&env, round_id);
+        load_amm(&env, round_id).quote(&env, side, shares)
+    }
+
+    /// Maker's implied probability of `Higher`, in basis points.
+    pub fn lmsr_price(env: Env, round_id: u32) -> u32 {
+        load_round(&env, round_id);
+        let amm = load_amm(&env, round_id);
+        let gap = amm.higher - amm.lower;
+        let tail = if gap.abs() / amm.b >= EXP_CUTOFF {
+            0
+        } else {
+            exp_neg(gap.abs() * SCALE / amm.b)
+        };
+        let p = if gap >= 0 {
+            SCALE * SCALE / (SCALE + tail)
+        } else {
+            tail * SCALE / (SCALE + tail)
+        };
+        (p * 10_000 / SCALE) as u32
+    }
+
+    /// Round's market maker state.
+    pub fn get_amm(env: Env, round_id: u32) -> Amm {
+        load_round(&env, round_id);
+        load_amm(&env, round_id)
+    }
+}
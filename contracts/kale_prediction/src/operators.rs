@@ -0,0 +1,149 @@
+//! Delegated betting. A player deposits tokens with the contract and names
+//! an operator — a bot, say — that may bet that balance for them, up to
+//! `round_limit` in any one round. The stakes are the player's; the
+//! operator only ever moves deposited funds into pools. Deposits are in
+//! the deployment's token, so operators only bet rounds staked in it.
+//! Like unclaimed winnings, deposits left at a sunset hand‑over go with it.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::events;
+use crate::{
+    checked_add, compliance, in_default_token, load_round, pay, place_bet, pull, require_live,
+    require_min_bet, token_client, DataKey, Error, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, Side,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Operator {
+    pub operator: Address,
+    pub round_limit: i128, // most it may bet for the player in one round
+}
+
+fn deposit_of(e: &Env, player: &Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Deposit(player.clone()))
+        .unwrap_or(0)
+}
+
+fn set_deposit(e: &Env, player: &Address, amount: i128) {
+    let key = DataKey::Deposit(player.clone());
+    if amount == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &amount);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Add `amount` to `player`'s deposit for operator bets.
+    pub fn deposit(env: Env, player: Address, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        player.require_auth();
+        pull(&env, &player, amount);
+        set_deposit(
+            &env,
+            &player,
+            checked_add(&env, deposit_of(&env, &player), amount),
+        );
+    }
+
+    /// Take `amount` of `player`'s deposit back.
+    pub fn withdraw(env: Env, player: Address, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        player.require_auth();
+        compliance::require_unfrozen(&env, &player);
+        let held = deposit_of(&env, &player);
+        if amount > held {
+            panic_with_error!(env, Error::InsufficientShares);
+        }
+        set_deposit(&env, &player, held - amount);
+        pay(&env, &token_client(&env), &player, amount);
+    }
+
+    /// Let `operator` bet `player`'s deposit, up to `round_limit` a round,
+    /// replacing any earlier operator. Evented as
+    /// `("operator", player) → operator`.
+    pub fn set_operator(env: Env, player: Address, operator: Address, round_limit: i128) {
+        require_live(&env);
+        if round_limit <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        if operator == player {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        player.require_auth();
+        env.storage().persistent().set(
+            &DataKey::Operator(player.clone()),
+            &Operator {
+                operator: operator.clone(),
+                round_limit,
+            },
+        );
+        events::publish(&env, "operator", (player,), operator);
+    }
+
+    /// Withdraw `player`'s operator's authority.
+    pub fn revoke_operator(env: Env, player: Address) {
+        require_live(&env);
+        player.require_auth();
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Operator(player));
+    }
+
+    /// Bet `amount` of `player`'s deposit on their behalf, as their
+    /// operator. Past the round limit or the deposit it fails with
+    /// `InsufficientShares`.
+    pub fn operator_bet(
+        env: Env,
+        operator: Address,
+        player: Address,
+        round_id: u32,
+        side: Side,
+        amount: i128,
+    ) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        operator.require_auth();
+        let delegation: Operator = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Operator(player.clone()))
+            .filter(|d: &Operator| d.operator == operator)
+            .unwrap_or_else(|| panic_with_error!(env, Error::Unauthorized));
+
+        let spent_key = DataKey::OperatorSpent(round_id, player.clone());
+        let spent: i128 = env.storage().persistent().get(&spent_key).unwrap_or(0);
+        let held = deposit_of(&env, &player);
+        let spent = checked_add(&env, spent, amount);
+        if spent > delegation.round_limit || amount > held {
+            panic_with_error!(env, Error::InsufficientShares);
+        }
+        env.storage().persistent().set(&spent_key, &spent);
+        if !in_default_token(&env, &load_round(&env, round_id)) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        set_deposit(&env, &player, held - amount);
+        place_bet(&env, None, &player, round_id, side, amount);
+    }
+
+    /// `player`'s deposit available to their operator.
+    pub fn get_deposit(env: Env, player: Address) -> i128 {
+        deposit_of(&env, &player)
+    }
+
+    /// Who may bet `player`'s deposit, if anyone.
+    pub fn get_operator(env: Env, player: Address) -> Option<Operator> {
+        env.storage().persistent().get(&DataKey::Operator(player))
+    }
+}
@@ -0,0 +1,183 @@
+//! Optimistic resolution: anyone may propose a count by posting a bond.
+//! Unchallenged, it settles the round after `liveness_ledgers` and the bond
+//! is returned; challenged (with an equal bond), the admin arbitrates and
+//! whoever was right takes both bonds.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::events;
+use crate::state::load_round_advanced;
+use crate::{
+    finish_resolution, pay, pull, require_admin, require_count_market, require_live,
+    require_resolvable, token_client, DataKey, Error, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, RoundState,
+};
+
+/// Bond (in the wager token) and challenge window for proposals.
+#[contracttype]
+#[derive(Clone)]
+pub struct OptimisticConfig {
+    pub bond: i128,
+    pub liveness_ledgers: u32,
+}
+
+/// A pending proposal for one round.
+#[contracttype]
+#[derive(Clone)]
+pub struct Proposal {
+    pub proposer: Address,
+    pub observed_count: u32,
+    pub bond: i128,
+    pub ledger: u32,                 // challenge window starts here
+    pub liveness_ledgers: u32,       // idem, its length
+    pub challenger: Option<Address>, // set once disputed
+}
+
+fn load_proposal(e: &Env, round_id: u32) -> Proposal {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Proposal(round_id))
+        .unwrap_or_else(|| panic_with_error!(e, Error::NoProposal))
+}
+
+/// Settle the round on `observed_count` unless it already settled another
+/// way (admin, attestation, expiry) in the meantime.
+fn resolve_if_open(e: &Env, round_id: u32, observed_count: u32, resolved_by: Address) {
+    let mut round = load_round_advanced(e, round_id);
+    if round.state == RoundState::Locked {
+        round.resolved_by = Some(resolved_by);
+        finish_resolution(e, round_id, &mut round, observed_count);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Enable optimistic resolution with `config`, or disable it with
+    /// `None`. Pending proposals keep the terms they were made under.
+    pub fn set_optimistic(env: Env, admin: Address, config: Option<OptimisticConfig>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        match config {
+            Some(cfg) => {
+                if cfg.bond <= 0 {
+                    panic_with_error!(env, Error::ZeroAmount);
+                }
+                env.storage().instance().set(&DataKey::Optimistic, &cfg);
+            }
+            None => env.storage().instance().remove(&DataKey::Optimistic),
+        }
+    }
+
+    /// Propose `observed_count` for a round past finality, escrowing the
+    /// bond. The challenge window must close before the round's hard
+    /// deadline.
+    pub fn propose(env: Env, proposer: Address, round_id: u32, observed_count: u32) {
+        require_live(&env);
+        proposer.require_auth();
+        let cfg: OptimisticConfig = env
+            .storage()
+            .instance()
+            .get(&DataKey::Optimistic)
+            .unwrap_or_else(|| panic_with_error!(env, Error::OptimisticDisabled));
+
+        let round = load_round_advanced(&env, round_id);
+        require_resolvable(&env, &round);
+        require_count_market(&env, &round);
+        let hard_deadline = round.finality.plus(round.rules.grace_ledgers);
+        if hard_deadline.passed_in(&env, cfg.liveness_ledgers) {
+            panic_with_error!(env, Error::ResolutionExpired);
+        }
+        let key = DataKey::Proposal(round_id);
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, Error::ProposalExists);
+        }
+
+        pull(&env, &proposer, cfg.bond);
+        env.storage().persistent().set(
+            &key,
+            &Proposal {
+                proposer: proposer.clone(),
+                observed_count,
+                bond: cfg.bond,
+                ledger: env.ledger().sequence(),
+                liveness_ledgers: cfg.liveness_ledgers,
+                challenger: None,
+            },
+        );
+        events::publish(&env, "propose", (round_id,), (proposer, observed_count));
+    }
+
+    /// Dispute a proposal within its challenge window by matching its bond.
+    pub fn challenge(env: Env, challenger: Address, round_id: u32) {
+        require_live(&env);
+        challenger.require_auth();
+
+        let mut proposal = load_proposal(&env, round_id);
+        if proposal.challenger.is_some()
+            || env.ledger().sequence() > proposal.ledger + proposal.liveness_ledgers
+        {
+            panic_with_error!(env, Error::ChallengeClosed);
+        }
+
+        pull(&env, &challenger, proposal.bond);
+        proposal.challenger = Some(challenger.clone());
+        env.storage()
+            .persistent()
+            .set(&DataKey::Proposal(round_id), &proposal);
+        events::publish(&env, "challenge", (round_id,), challenger);
+    }
+
+    /// Finalise an unchallenged proposal once its window has passed and
+    /// return the bond. Anyone may call this.
+    pub fn settle_proposal(env: Env, round_id: u32) {
+        require_live(&env);
+
+        let proposal = load_proposal(&env, round_id);
+        if proposal.challenger.is_some() {
+            panic_with_error!(env, Error::ProposalDisputed);
+        }
+        if env.ledger().sequence() <= proposal.ledger + proposal.liveness_ledgers {
+            panic_with_error!(env, Error::TooEarly);
+        }
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Proposal(round_id));
+        resolve_if_open(
+            &env,
+            round_id,
+            proposal.observed_count,
+            proposal.proposer.clone(),
+        );
+        pay(&env, &token_client(&env), &proposal.proposer, proposal.bond);
+    }
+
+    /// Adjudicate a disputed proposal: the round settles on `observed_count`
+    /// and the proposer keeps both bonds if it matches their count, the
+    /// challenger otherwise.
+    pub fn arbitrate(env: Env, admin: Address, round_id: u32, observed_count: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+
+        let proposal = load_proposal(&env, round_id);
+        let Some(challenger) = proposal.challenger else {
+            panic_with_error!(env, Error::NoProposal);
+        };
+
+        env.storage()
+            .persistent()
+            .remove(&DataKey::Proposal(round_id));
+        resolve_if_open(&env, round_id, observed_count, admin);
+        let winner = if observed_count == proposal.observed_count {
+            proposal.proposer
+        } else {
+            challenger
+        };
+        pay(&env, &token_client(&env), &winner, proposal.bond * 2);
+    }
+
+    /// Pending proposal for a round, if any.
+    pub fn get_proposal(env: Env, round_id: u32) -> Option<Proposal> {
+        env.storage().persistent().get(&DataKey::Proposal(round_id))
+    }
+}
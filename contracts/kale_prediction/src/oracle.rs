@@ -0,0 +1,71 @@
+//! Cross‑contract interfaces the market reads external data from.
+//!
+//! Only the **read surface** the market needs is declared here; the actual
+//! source can be the Kale contract itself or a thin adapter in front of it.
+
+use soroban_sdk::{contractclient, contracttype, Address, Env, Symbol};
+
+/// On‑chain source of Kale statistics (see `Metric`).
+#[contractclient(name = "CountOracleClient")]
+pub trait CountOracle {
+    /// Number of Kale‑contract invocations observed so far.
+    fn invocation_count(env: Env) -> u32;
+
+    /// Whole KALE harvested so far.
+    fn harvest_volume(env: Env) -> u32;
+
+    /// Whole KALE currently staked in the farm.
+    fn total_staked(env: Env) -> u32;
+}
+
+/// Off‑chain‑informed risk scorer consulted for large bets.
+#[contractclient(name = "RiskOracleClient")]
+pub trait RiskOracle {
+    /// Risk score for `player`; higher is riskier.
+    fn score(env: Env, player: Address) -> u32;
+}
+
+/// Registry of approved (e.g. KYC'd) accounts a private round may defer to.
+#[contractclient(name = "AllowlistClient")]
+pub trait Allowlist {
+    /// Whether `account` may take part.
+    fn is_allowed(env: Env, account: Address) -> bool;
+}
+
+/// Ledger span of one Kale farm epoch, both ends inclusive.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct EpochBounds {
+    pub start_ledger: u32,
+    pub end_ledger: u32,
+}
+
+/// Kale farm's epoch schedule, so rounds can follow farm time.
+#[contractclient(name = "FarmEpochsClient")]
+pub trait FarmEpochs {
+    /// Ledger span of epoch `index`, including epochs yet to start.
+    fn epoch_bounds(env: Env, index: u32) -> EpochBounds;
+}
+
+/// SEP‑40 asset identifier.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Stellar(Address),
+    Other(Symbol),
+}
+
+/// SEP‑40 price record; `price` is scaled by the oracle's `decimals()`.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PriceData {
+    pub price: i128,
+    pub timestamp: u64,
+}
+
+/// SEP‑40 price feed (e.g. Reflector).
+#[contractclient(name = "PriceOracleClient")]
+pub trait PriceOracle {
+    /// Most recent price of `asset`, if the oracle tracks it.
+    fn lastprice(env: Env, asset: Asset) -> Option<PriceData>;
+}
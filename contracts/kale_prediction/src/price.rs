@@ -0,0 +1,78 @@
+//! Price rounds: over/under on an asset price read from a SEP‑40 oracle
+//! (e.g. Reflector) instead of a Kale invocation count.
+
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env};
+
+use crate::oracle::PriceOracleClient;
+use crate::state::load_round_advanced;
+use crate::{
+    against_line, create_round, decide, default_rules, record_resolution, require_live,
+    require_resolvable, Deadline, Error, KalePrediction, KalePredictionArgs, KalePredictionClient,
+    Market, PriceFeed, Round,
+};
+
+/// Oldest oracle price (seconds) a price round will settle on.
+const MAX_PRICE_AGE_SECS: u64 = 600;
+
+/// The feed's latest price, unless the oracle fails or it is older than
+/// `MAX_PRICE_AGE_SECS`.
+pub(crate) fn fresh_price(e: &Env, feed: &PriceFeed) -> Option<i128> {
+    let Ok(Ok(Some(data))) = PriceOracleClient::new(e, &feed.oracle).try_lastprice(&feed.asset)
+    else {
+        return None;
+    };
+    (data.timestamp + MAX_PRICE_AGE_SECS >= e.ledger().timestamp()).then_some(data.price)
+}
+
+/// Decide a price round on `price` against its line.
+pub(crate) fn settle_price(
+    e: &Env,
+    round_id: u32,
+    round: &mut Round,
+    feed: &PriceFeed,
+    price: i128,
+) {
+    round.settled_price = price;
+    decide(e, round, against_line(price, feed.line, round.rules.spread));
+    record_resolution(e, round_id, round);
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Start a round on whether `feed.asset` ends above or below
+    /// `feed.line` at finality, with the default rules.
+    pub fn start_price_round(
+        env: Env,
+        admin: Address,
+        feed: PriceFeed,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        let rules = default_rules(&env);
+        create_round(
+            &env,
+            &admin,
+            Market::Price(feed),
+            0,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        )
+    }
+
+    /// Settle a price round from its oracle. Anyone may call this from
+    /// finality on; the price must be at most `MAX_PRICE_AGE_SECS` old.
+    pub fn resolve_price(env: Env, round_id: u32) {
+        require_live(&env);
+
+        let mut round = load_round_advanced(&env, round_id);
+        require_resolvable(&env, &round);
+        let Market::Price(feed) = round.market.clone() else {
+            panic_with_error!(env, Error::WrongMarket);
+        };
+
+        let price = fresh_price(&env, &feed)
+            .unwrap_or_else(|| panic_with_error!(env, Error::PriceUnavailable));
+        settle_price(&env, round_id, &mut round, &feed, price);
+    }
+}
@@ -0,0 +1,127 @@
+//! Referrals. A bet placed with `bet_with_referrer` credits its volume to
+//! the referrer in that round. When the round's protocol fee is collected,
+//! `referral_bps` of the admin's part of it, pro rata to the volume each
+//! referrer brought in against the whole pot, accrues to the referrers;
+//! they withdraw it with `claim_referral_rewards`.
+
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env, Map};
+
+use crate::events;
+use crate::roles::{require_role, Role};
+use crate::timelock::{self, Fee};
+use crate::{
+    checked_add, in_default_token, mul_div, pay, require_live, store_fee, token_client, total_pool,
+    DataKey, Error, KalePrediction, KalePredictionArgs, KalePredictionClient, Round, Side,
+};
+
+/// Most referrers credited in one round; volume from any beyond them is
+/// taken but earns nothing.
+pub const MAX_REFERRERS: u32 = 20;
+
+/// Upper bound for the referrers' share of the fee (basis points).
+pub(crate) const MAX_REFERRAL_BPS: u32 = 5_000;
+
+/// Credit the referrers of `round` with their share of `fee`, the
+/// admin's part of its protocol fee. Returns what they were credited.
+pub(crate) fn accrue(e: &Env, round_id: u32, round: &Round, fee: i128) -> i128 {
+    let key = DataKey::Referrals(round_id);
+    // rewards are paid in the deployment's token
+    if !in_default_token(e, round) {
+        e.storage().persistent().remove(&key);
+        return 0;
+    }
+    let Some(volumes) = e
+        .storage()
+        .persistent()
+        .get::<DataKey, Map<Address, i128>>(&key)
+    else {
+        return 0;
+    };
+    e.storage().persistent().remove(&key);
+    let bps: i128 = e
+        .storage()
+        .instance()
+        .get::<DataKey, u32>(&DataKey::ReferralBps)
+        .unwrap_or(0)
+        .into();
+    let total = total_pool(round);
+
+    let mut credited = 0;
+    for (referrer, volume) in volumes.iter() {
+        let reward = mul_div(e, mul_div(e, fee, volume, total), bps, 10_000);
+        if reward > 0 {
+            let rewards_key = DataKey::ReferralRewards(referrer);
+            let owed: i128 = e.storage().persistent().get(&rewards_key).unwrap_or(0);
+            e.storage()
+                .persistent()
+                .set(&rewards_key, &checked_add(e, owed, reward));
+            credited = checked_add(e, credited, reward);
+        }
+    }
+    credited
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Pay referrers `bps` (at most `MAX_REFERRAL_BPS`) of the protocol
+    /// fee on the volume they bring in. Zero by default.
+    pub fn set_referral_bps(env: Env, manager: Address, bps: u32) {
+        require_live(&env);
+        require_role(&env, Role::FeeManager, &manager);
+        timelock::require_unlocked(&env);
+        store_fee(&env, Fee::Referral, bps);
+    }
+
+    /// `bet`, crediting the volume to `referrer`. Evented as
+    /// `("referral", referrer, player) → (round_id, amount)`.
+    pub fn bet_with_referrer(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        side: Side,
+        amount: i128,
+        referrer: Address,
+    ) {
+        if referrer == player {
+            panic_with_error!(env, Error::Unauthorized);
+        }
+        Self::bet(env.clone(), player.clone(), round_id, side, amount);
+
+        let key = DataKey::Referrals(round_id);
+        let mut volumes: Map<Address, i128> = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| Map::new(&env));
+        match volumes.get(referrer.clone()) {
+            Some(volume) => volumes.set(referrer.clone(), volume + amount),
+            None if volumes.len() < MAX_REFERRERS => volumes.set(referrer.clone(), amount),
+            None => {}
+        }
+        env.storage().persistent().set(&key, &volumes);
+        events::publish(&env, "referral", (referrer, player), (round_id, amount));
+    }
+
+    /// Withdraw everything credited to `referrer`. Returns the amount.
+    pub fn claim_referral_rewards(env: Env, referrer: Address) -> i128 {
+        require_live(&env);
+        referrer.require_auth();
+        let key = DataKey::ReferralRewards(referrer.clone());
+        let owed: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        if owed == 0 {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        env.storage().persistent().remove(&key);
+        pay(&env, &token_client(&env), &referrer, owed);
+        events::publish(&env, "referred", (referrer,), owed);
+        owed
+    }
+
+    /// Rewards credited to `referrer` and not yet withdrawn.
+    pub fn get_referral_rewards(env: Env, referrer: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ReferralRewards(referrer))
+            .unwrap_or(0)
+    }
+}
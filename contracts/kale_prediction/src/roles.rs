@@ -0,0 +1,146 @@
+//! Operational roles, so day‑to‑day keys need not hold full control. The
+//! admin may do everything and grants or revokes the rest: a `Resolver`
+//! settles, amends and cancels rounds (unless a committee resolves), a
+//! `FeeManager` tunes the fee rates, and a `Pauser` halts the intake of
+//! new funds — claims, refunds and withdrawals keep working while paused.
+//! Once oracle resolution is configured the admin may renounce for good,
+//! after which neither an admin nor any role holder can act.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::events;
+use crate::timelock;
+use crate::{
+    get_admin, load_config, require_admin, require_live, Committee, DataKey, Error, KalePrediction,
+    KalePredictionArgs, KalePredictionClient,
+};
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Resolver = 0,
+    Pauser = 1,
+    FeeManager = 2,
+}
+
+fn holds(e: &Env, role: Role, account: &Address) -> bool {
+    e.storage()
+        .instance()
+        .has(&DataKey::Role(role, account.clone()))
+}
+
+/// Whether the admin has renounced, leaving the contract as its own admin.
+fn renounced(e: &Env) -> bool {
+    get_admin(e) == e.current_contract_address()
+}
+
+/// `caller` must be the admin or hold `role`, and have signed.
+pub(crate) fn require_role(e: &Env, role: Role, caller: &Address) {
+    if renounced(e) || (*caller != get_admin(e) && !holds(e, role, caller)) {
+        panic_with_error!(e, Error::Unauthorized);
+    }
+    caller.require_auth();
+}
+
+/// Give `role` to `account` (`granted`) or take it away, evented as
+/// `("role", role, account) → granted`.
+pub(crate) fn assign(e: &Env, role: Role, account: Address, granted: bool) {
+    let key = DataKey::Role(role, account.clone());
+    if granted {
+        e.storage().instance().set(&key, &true);
+    } else {
+        e.storage().instance().remove(&key);
+    }
+    events::publish(e, "role", (role, account), granted);
+}
+
+/// Unless the config lets insiders bet, the admin, resolvers and committee
+/// members are refused with `ConflictOfInterest`.
+pub(crate) fn require_outsider(e: &Env, player: &Address) {
+    if load_config(e).insiders_may_bet {
+        return;
+    }
+    let on_committee = e
+        .storage()
+        .instance()
+        .get::<DataKey, Committee>(&DataKey::Committee)
+        .is_some_and(|c| c.members.contains(player));
+    if *player == get_admin(e) || holds(e, Role::Resolver, player) || on_committee {
+        panic_with_error!(e, Error::ConflictOfInterest);
+    }
+}
+
+/// New funds are refused with `Paused` while the contract is paused.
+pub(crate) fn require_unpaused(e: &Env) {
+    if e.storage().instance().has(&DataKey::Paused) {
+        panic_with_error!(e, Error::Paused);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Give `account` `role`. Evented as `("role", role, account) → true`.
+    /// Behind a timelock, resolvers are granted by queuing a change.
+    pub fn grant_role(env: Env, admin: Address, role: Role, account: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if role == Role::Resolver {
+            timelock::require_unlocked(&env);
+        }
+        assign(&env, role, account, true);
+    }
+
+    /// Take `role` from `account`. Evented as `("role", role, account) →
+    /// false`.
+    pub fn revoke_role(env: Env, admin: Address, role: Role, account: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if role == Role::Resolver {
+            timelock::require_unlocked(&env);
+        }
+        assign(&env, role, account, false);
+    }
+
+    /// Whether `account` holds `role` (the admin holds them all). Nobody
+    /// does after a renounce.
+    pub fn has_role(env: Env, role: Role, account: Address) -> bool {
+        !renounced(&env) && (account == get_admin(&env) || holds(&env, role, &account))
+    }
+
+    /// Give up the admin for good, making the contract its own admin: no
+    /// key can start rounds, resolve or change settings afterwards, and the
+    /// admin's cuts stay in the contract. Only allowed once an oracle or
+    /// feed set can resolve rounds (`OracleNotSet` otherwise). Evented as
+    /// `("renounce",) → admin`.
+    pub fn renounce_admin(env: Env, admin: Address) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let store = env.storage().instance();
+        if !store.has(&DataKey::Oracle) && !store.has(&DataKey::Feeds) {
+            panic_with_error!(env, Error::OracleNotSet);
+        }
+        store.set(&DataKey::Admin, &env.current_contract_address());
+        events::publish(&env, "renounce", (), admin);
+    }
+
+    /// Stop taking new bets and deposits.
+    pub fn pause(env: Env, pauser: Address) {
+        require_live(&env);
+        require_role(&env, Role::Pauser, &pauser);
+        env.storage().instance().set(&DataKey::Paused, &true);
+        events::publish(&env, "paused", (), true);
+    }
+
+    /// Take bets and deposits again.
+    pub fn unpause(env: Env, pauser: Address) {
+        require_live(&env);
+        require_role(&env, Role::Pauser, &pauser);
+        env.storage().instance().remove(&DataKey::Paused);
+        events::publish(&env, "paused", (), false);
+    }
+
+    /// Whether new funds are being refused.
+    pub fn is_paused(env: Env) -> bool {
+        env.storage().instance().has(&DataKey::Paused)
+    }
+}
@@ -0,0 +1,73 @@
+//! Scalar rounds: every bettor stakes on an exact invocation count with
+//! `Side::Guess`, and the pot is shared by weight, where a stake's weight
+//! is its amount over `1 + |guess - actual_count|`. Close guesses earn
+//! most, but every guess gets something back.
+
+use soroban_sdk::{contractimpl, Address, Env};
+
+use crate::{
+    create_round, default_rules, mul_div, Deadline, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, Market, Outcome, Round, Side, Stake,
+};
+
+/// Most distinct guesses a round takes, which bounds the settlement loop.
+pub const MAX_GUESSES: u32 = 64;
+
+/// Fixed‑point scale of weights, so that far guesses still weigh above 0.
+const WEIGHT_SCALE: i128 = 1_000_000;
+
+fn weight(amount: i128, guess: u32, actual_count: u32) -> i128 {
+    amount * WEIGHT_SCALE / (1 + i128::from(guess.abs_diff(actual_count)))
+}
+
+/// Score every guess against `actual_count`. Scalar rounds charge no
+/// protocol fee.
+pub(crate) fn score(round: &mut Round) {
+    round.guess_weight = round
+        .guesses
+        .iter()
+        .map(|(guess, amount)| weight(amount, guess, round.actual_count))
+        .sum();
+    round.outcome = if round.guess_weight > 0 {
+        Outcome::Scored
+    } else {
+        Outcome::Push
+    };
+    round.protocol_fee = 0;
+}
+
+/// `stake`'s share of `payable_pool` in a scored round.
+pub(crate) fn share(e: &Env, round: &Round, stake: &Stake, payable_pool: i128) -> i128 {
+    let Side::Guess(guess) = stake.side else {
+        return 0;
+    };
+    mul_div(
+        e,
+        weight(stake.amount, guess, round.actual_count),
+        payable_pool,
+        round.guess_weight,
+    )
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Start a scalar round on the invocations from now to finality, with
+    /// the default rules.
+    pub fn start_scalar_round(
+        env: Env,
+        admin: Address,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        let rules = default_rules(&env);
+        create_round(
+            &env,
+            &admin,
+            Market::Scalar,
+            0,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        )
+    }
+}
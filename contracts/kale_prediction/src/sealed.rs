@@ -0,0 +1,207 @@
+//! Sealed rounds: commit‑reveal over/under betting, so small markets cannot
+//! herd on visible pools. While betting is open a player escrows a stake
+//! behind `sha256(xdr((player, round_id, side, salt)))`, so a copied
+//! commitment is no use to anyone else; only the pot's total shows. After
+//! the deadline, and before finality, they reveal the side and salt and
+//! the stake joins that side's pool. A stake never revealed is forfeited to
+//! the pot, owned by nobody — unless the round is voided (cancelled,
+//! expired, one‑sided), when its owner takes it back with
+//! `reclaim_sealed_bet`.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, xdr::ToXdr, Address, BytesN, Env};
+
+use crate::state::load_round_advanced;
+use crate::{
+    add_stake, check_risk, checked_add, compliance, create_round, default_rules, events, pay,
+    pull_token, require_live, require_min_bet, round_token, save_round, DataKey, Deadline, Error,
+    KalePrediction, KalePredictionArgs, KalePredictionClient, Market, Outcome, Round, RoundState,
+    Side,
+};
+
+/// A committed, not yet revealed stake.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SealedBet {
+    pub commitment: BytesN<32>,
+    pub amount: i128,
+}
+
+/// Whether bets on the round are sealed (and so not taken by `bet`).
+pub(crate) fn is_sealed(e: &Env, round_id: u32) -> bool {
+    e.storage().persistent().has(&DataKey::Sealed(round_id))
+}
+
+/// Stakes committed to the round and not yet revealed (or reclaimed).
+pub(crate) fn escrowed(e: &Env, round_id: u32) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::SealedEscrow(round_id))
+        .unwrap_or(0)
+}
+
+fn set_escrowed(e: &Env, round_id: u32, amount: i128) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::SealedEscrow(round_id), &amount);
+}
+
+/// Whether a voided round still escrows sealed stakes for
+/// `reclaim_sealed_bet`. Elsewhere unrevealed stakes were forfeited.
+pub(crate) fn is_reclaimable(e: &Env, round_id: u32, round: &Round) -> bool {
+    matches!(round.outcome, Outcome::Voided | Outcome::Expired) && escrowed(e, round_id) > 0
+}
+
+/// Take a voided round's unrevealed stakes out of its pot, where they
+/// would otherwise be shared out with the refunds. In memory only.
+pub(crate) fn release(e: &Env, round_id: u32, round: &mut Round) {
+    round.forfeited = checked_add(e, round.forfeited, -escrowed(e, round_id));
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// `start_round`, with bets sealed until the deadline.
+    pub fn start_sealed_round(
+        env: Env,
+        admin: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+    ) -> u32 {
+        let rules = default_rules(&env);
+        let round_id = create_round(
+            &env,
+            &admin,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        );
+        env.storage()
+            .persistent()
+            .set(&DataKey::Sealed(round_id), &true);
+        round_id
+    }
+
+    /// Escrow `amount` behind `commitment`, the sha256 of the XDR of
+    /// `(player, round_id, side, salt)`. One sealed bet per player and
+    /// round; a second fails with `DuplicateVote`.
+    pub fn commit_bet(
+        env: Env,
+        player: Address,
+        round_id: u32,
+        commitment: BytesN<32>,
+        amount: i128,
+    ) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        if !is_sealed(&env, round_id) {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        let key = DataKey::SealedBet(round_id, player.clone());
+        if env.storage().persistent().has(&key) {
+            panic_with_error!(env, Error::DuplicateVote);
+        }
+        check_risk(&env, &player, round_id, amount);
+
+        pull_token(&env, &round_token(&env, &round), &player, amount);
+        env.storage()
+            .persistent()
+            .set(&key, &SealedBet { commitment, amount });
+        // in the pot, but on no side until revealed
+        round.forfeited = checked_add(&env, round.forfeited, amount);
+        save_round(&env, round_id, &round);
+        set_escrowed(
+            &env,
+            round_id,
+            checked_add(&env, escrowed(&env, round_id), amount),
+        );
+    }
+
+    /// Open a sealed bet between the deadline and finality, moving its
+    /// stake onto `side`. A side or salt not matching the commitment fails
+    /// with `InvalidSide`.
+    pub fn reveal_bet(env: Env, player: Address, round_id: u32, side: Side, salt: BytesN<32>) {
+        require_live(&env);
+        player.require_auth();
+
+        let mut round = load_round_advanced(&env, round_id);
+        match round.state {
+            RoundState::Open => panic_with_error!(env, Error::TooEarly),
+            RoundState::Locked if !round.finality.reached(&env) => {}
+            _ => panic_with_error!(env, Error::BettingClosed),
+        }
+        let key = DataKey::SealedBet(round_id, player.clone());
+        let sealed: SealedBet = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::InsufficientShares));
+        let opened: BytesN<32> = env
+            .crypto()
+            .sha256(&(player.clone(), round_id, side, salt).to_xdr(&env))
+            .into();
+        if opened != sealed.commitment || !matches!(side, Side::Higher | Side::Lower) {
+            panic_with_error!(env, Error::InvalidSide);
+        }
+
+        env.storage().persistent().remove(&key);
+        round.forfeited = checked_add(&env, round.forfeited, -sealed.amount);
+        set_escrowed(
+            &env,
+            round_id,
+            checked_add(&env, escrowed(&env, round_id), -sealed.amount),
+        );
+        add_stake(&env, round_id, &mut round, &player, side, sealed.amount);
+        save_round(&env, round_id, &round);
+    }
+
+    /// Take back an unrevealed sealed stake once its round has been voided,
+    /// whether cancelled, expired or left one‑sided. Evented as
+    /// `refunded`. Returns the amount.
+    pub fn reclaim_sealed_bet(env: Env, player: Address, round_id: u32) -> i128 {
+        require_live(&env);
+        player.require_auth();
+
+        let round = load_round_advanced(&env, round_id);
+        if !matches!(round.outcome, Outcome::Voided | Outcome::Expired)
+            || !matches!(
+                round.state,
+                RoundState::Voided | RoundState::Settled | RoundState::Archived
+            )
+        {
+            panic_with_error!(env, Error::RefundNotAvailable);
+        }
+        if compliance::is_frozen(&env, &player) {
+            panic_with_error!(env, Error::RiskRejected);
+        }
+        let key = DataKey::SealedBet(round_id, player.clone());
+        let sealed: SealedBet = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&key);
+        set_escrowed(
+            &env,
+            round_id,
+            checked_add(&env, escrowed(&env, round_id), -sealed.amount),
+        );
+        pay(&env, &round_token(&env, &round), &player, sealed.amount);
+        events::publish(&env, "refunded", (round_id, player), sealed.amount);
+        sealed.amount
+    }
+
+    /// A player's unrevealed sealed bet, if any.
+    pub fn get_sealed_bet(env: Env, round_id: u32, player: Address) -> Option<SealedBet> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SealedBet(round_id, player))
+    }
+}
@@ -0,0 +1,262 @@
+//! Seasons: every round opened while a season runs counts towards it.
+//! Players' bets, claims and refunds on those rounds add up to a season
+//! score (rounds played, rounds called right, net winnings). Once the
+//! season ends players enter the leaderboard with their score during
+//! `SEASON_ENTRY_LEDGERS`, after which `settle_season` splits the sponsor
+//! pool among the top K by net winnings, weighted by rank.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env, Vec};
+
+use crate::events;
+use crate::{
+    checked_add, mul_div, pay, pull, require_admin, require_live, token_client, DataKey, Error,
+    KalePrediction, KalePredictionArgs, KalePredictionClient, Outcome, Round, Stake,
+};
+
+/// Largest leaderboard a season may pay.
+pub const MAX_TOP_K: u32 = 10;
+
+/// Ledgers after a season ends during which players enter the leaderboard
+/// (~one day).
+pub const SEASON_ENTRY_LEDGERS: u32 = 17_280;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Season {
+    pub pool: i128, // sponsor funding, paid out at settlement
+    pub top_k: u32,
+    pub end_ledger: u32,     // rounds opened before this ledger join
+    pub board: Vec<Address>, // best net winnings first, at most `top_k`
+    pub settled: bool,
+}
+
+/// A player's record over one season.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct SeasonScore {
+    pub played: u32,  // rounds with a stake (voided ones excluded)
+    pub correct: u32, // rounds they called right (or guessed at a profit)
+    pub net: i128,    // paid out less staked
+}
+
+fn load_season(e: &Env, season_id: u32) -> Season {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Season(season_id))
+        .unwrap_or_else(|| panic_with_error!(e, Error::RoundNotFound))
+}
+
+fn save_season(e: &Env, season_id: u32, season: &Season) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::Season(season_id), season);
+}
+
+fn load_score(e: &Env, season_id: u32, player: &Address) -> SeasonScore {
+    e.storage()
+        .persistent()
+        .get(&DataKey::SeasonScore(season_id, player.clone()))
+        .unwrap_or_default()
+}
+
+/// Apply `update` to `player`'s score if `round_id` belongs to a season.
+fn score(e: &Env, round_id: u32, player: &Address, update: impl FnOnce(&mut SeasonScore)) {
+    let Some(season_id) = e
+        .storage()
+        .persistent()
+        .get::<DataKey, u32>(&DataKey::SeasonOf(round_id))
+    else {
+        return;
+    };
+    let mut score = load_score(e, season_id, player);
+    update(&mut score);
+    e.storage()
+        .persistent()
+        .set(&DataKey::SeasonScore(season_id, player.clone()), &score);
+}
+
+/// Enrol a newly opened round in the running season, if any.
+pub(crate) fn enroll(e: &Env, round_id: u32) {
+    let Some(season_id) = e
+        .storage()
+        .instance()
+        .get::<DataKey, u32>(&DataKey::CurrentSeason)
+    else {
+        return;
+    };
+    if e.ledger().sequence() < load_season(e, season_id).end_ledger {
+        e.storage()
+            .persistent()
+            .set(&DataKey::SeasonOf(round_id), &season_id);
+    }
+}
+
+/// Count a bet of `amount`; `opened` when it starts the player's position.
+pub(crate) fn record_bet(e: &Env, round_id: u32, player: &Address, amount: i128, opened: bool) {
+    score(e, round_id, player, |s| {
+        s.played += opened as u32;
+        s.net -= amount;
+    });
+}
+
+/// Count a claim or refund paying `payout` on `stake`.
+pub(crate) fn record_payout(
+    e: &Env,
+    round_id: u32,
+    round: &Round,
+    player: &Address,
+    stake: &Stake,
+    payout: i128,
+) {
+    score(e, round_id, player, |s| {
+        s.net += payout;
+        match round.outcome {
+            // a transferred-in stake was never counted as played
+            Outcome::Voided | Outcome::Expired => s.played = s.played.saturating_sub(1),
+            Outcome::Scored | Outcome::Laddered if payout > stake.amount => s.correct += 1,
+            _ if round.outcome.winner() == Some(stake.side) => s.correct += 1,
+            _ => {}
+        }
+    });
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Start a season taking in every round opened before `end_ledger`,
+    /// paying its top `top_k` players. Supersedes any season still running.
+    pub fn start_season(env: Env, admin: Address, top_k: u32, end_ledger: u32) -> u32 {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if top_k == 0 || top_k > MAX_TOP_K {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        if end_ledger <= env.ledger().sequence() {
+            panic_with_error!(env, Error::TooEarly);
+        }
+
+        let season_id = env
+            .storage()
+            .instance()
+            .get::<DataKey, u32>(&DataKey::CurrentSeason)
+            .map_or(1, |id| id + 1);
+        env.storage()
+            .instance()
+            .set(&DataKey::CurrentSeason, &season_id);
+        save_season(
+            &env,
+            season_id,
+            &Season {
+                pool: 0,
+                top_k,
+                end_ledger,
+                board: Vec::new(&env),
+                settled: false,
+            },
+        );
+        season_id
+    }
+
+    /// Add `amount` to a season's prize pool. Anyone may sponsor.
+    pub fn fund_season(env: Env, sponsor: Address, season_id: u32, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        sponsor.require_auth();
+
+        let mut season = load_season(&env, season_id);
+        if season.settled {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+        pull(&env, &sponsor, amount);
+        season.pool = checked_add(&env, season.pool, amount);
+        save_season(&env, season_id, &season);
+    }
+
+    /// Put `player` on the leaderboard with their current score, or move
+    /// them to where it now ranks. Open to anyone during the entry window,
+    /// so stale entries can be refreshed too; players without net winnings
+    /// drop off.
+    pub fn enter_season(env: Env, season_id: u32, player: Address) {
+        require_live(&env);
+
+        let mut season = load_season(&env, season_id);
+        let now = env.ledger().sequence();
+        if now < season.end_ledger {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        if season.settled || now >= season.end_ledger + SEASON_ENTRY_LEDGERS {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+
+        if let Some(at) = season.board.first_index_of(&player) {
+            season.board.remove(at);
+        }
+        let entry = load_score(&env, season_id, &player);
+        if entry.net > 0 {
+            // ties keep whoever entered first
+            let at = season
+                .board
+                .iter()
+                .position(|other| load_score(&env, season_id, &other).net < entry.net)
+                .unwrap_or(season.board.len() as usize);
+            if (at as u32) < season.top_k {
+                season.board.insert(at as u32, player);
+                if season.board.len() > season.top_k {
+                    season.board.pop_back();
+                }
+            }
+        }
+        save_season(&env, season_id, &season);
+    }
+
+    /// Pay out a season once its entry window has closed: rank `i` of `n`
+    /// takes `n - i` parts of the pool. With nobody on the board the pool
+    /// goes back to the admin. Anyone may call this.
+    pub fn settle_season(env: Env, season_id: u32) {
+        require_live(&env);
+
+        let mut season = load_season(&env, season_id);
+        if season.settled {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        if env.ledger().sequence() < season.end_ledger + SEASON_ENTRY_LEDGERS {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        season.settled = true;
+        save_season(&env, season_id, &season);
+
+        let token = token_client(&env);
+        let n = season.board.len() as i128;
+        if n == 0 {
+            let admin: Address = env.storage().instance().get(&DataKey::Admin).unwrap();
+            if season.pool > 0 {
+                pay(&env, &token, &admin, season.pool);
+            }
+            return;
+        }
+        let parts = n * (n + 1) / 2;
+        let mut paid = 0;
+        for (rank, player) in season.board.iter().enumerate().rev() {
+            // the top rank, paid last, also takes the rounding dust
+            let prize = if rank == 0 {
+                season.pool - paid
+            } else {
+                mul_div(&env, season.pool, n - rank as i128, parts)
+            };
+            paid = checked_add(&env, paid, prize);
+            if prize > 0 {
+                pay(&env, &token, &player, prize);
+            }
+        }
+        events::publish(&env, "season", (season_id,), season.pool);
+    }
+
+    pub fn get_season(env: Env, season_id: u32) -> Season {
+        load_season(&env, season_id)
+    }
+
+    pub fn get_season_score(env: Env, season_id: u32, player: Address) -> SeasonScore {
+        load_score(&env, season_id, &player)
+    }
+}
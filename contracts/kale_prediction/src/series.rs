@@ -0,0 +1,245 @@
+//! Best‑of‑N series: a parimutuel side book on whether `side` wins at
+//! least `min_wins` of a run of over/under rounds. Players back the series
+//! (`backs = true`) or oppose it while every round still takes bets; once
+//! all of them pay out, the side that called it splits both pools. If any
+//! round was voided or expired, or nobody called it, every stake comes
+//! back.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env, Vec};
+
+use crate::state::load_round_advanced;
+use crate::{
+    check_risk, checked_add, compliance, hold_round, in_default_token, mul_div, pay, pull,
+    release_round, require_admin, require_live, require_min_bet, require_paying, token_client,
+    DataKey, Error, KalePrediction, KalePredictionArgs, KalePredictionClient, Market, Outcome,
+    RoundState, Side,
+};
+
+/// Most rounds a series may span.
+pub const MAX_SERIES_ROUNDS: u32 = 10;
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Series {
+    pub rounds: Vec<u32>,
+    pub side: Side, // `Higher` or `Lower`
+    pub min_wins: u32,
+    pub backing: i128,        // staked on the series happening
+    pub opposing: i128,       // staked against it
+    pub decided: bool,        // every round paid out and `result` is final
+    pub result: Option<bool>, // whether it happened; `None` if void
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SeriesBet {
+    pub backs: bool,
+    pub amount: i128,
+}
+
+fn load_series(e: &Env, series_id: u32) -> Series {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Series(series_id))
+        .unwrap_or_else(|| panic_with_error!(e, Error::RoundNotFound))
+}
+
+/// Whether the series happened, or `None` if it is void. Every round must
+/// be paying out.
+fn called(e: &Env, series: &Series) -> Option<bool> {
+    let mut wins = 0;
+    let mut void = false;
+    for round_id in series.rounds.iter() {
+        let round = load_round_advanced(e, round_id);
+        require_paying(e, &round);
+        match round.outcome {
+            Outcome::Voided | Outcome::Expired => void = true,
+            outcome => wins += u32::from(outcome.winner() == Some(series.side)),
+        }
+    }
+    (!void).then_some(wins >= series.min_wins)
+}
+
+/// Fix the series' result, lifting its hold on rounds it has bets riding
+/// on. Every round must be paying out.
+fn decide(e: &Env, series_id: u32, series: &mut Series) {
+    series.result = called(e, series);
+    series.decided = true;
+    if checked_add(e, series.backing, series.opposing) > 0 {
+        for round_id in series.rounds.iter() {
+            release_round(e, round_id);
+        }
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::Series(series_id), series);
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Open a series on `side` winning at least `min_wins` of `rounds`,
+    /// which must all be over/under rounds still taking bets.
+    pub fn start_series(
+        env: Env,
+        admin: Address,
+        rounds: Vec<u32>,
+        side: Side,
+        min_wins: u32,
+    ) -> u32 {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if !matches!(side, Side::Higher | Side::Lower) {
+            panic_with_error!(env, Error::InvalidSide);
+        }
+        if rounds.is_empty()
+            || rounds.len() > MAX_SERIES_ROUNDS
+            || min_wins == 0
+            || min_wins > rounds.len()
+        {
+            panic_with_error!(env, Error::InvalidRules);
+        }
+        for round_id in rounds.iter() {
+            let round = load_round_advanced(&env, round_id);
+            if round.state != RoundState::Open {
+                panic_with_error!(env, Error::BettingClosed);
+            }
+            if !matches!(round.market, Market::Invocations | Market::Price(_))
+                || !in_default_token(&env, &round)
+            {
+                panic_with_error!(env, Error::WrongMarket);
+            }
+        }
+
+        let series_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextSeriesId)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextSeriesId, &(series_id + 1));
+        env.storage().persistent().set(
+            &DataKey::Series(series_id),
+            &Series {
+                rounds,
+                side,
+                min_wins,
+                backing: 0,
+                opposing: 0,
+                decided: false,
+                result: None,
+            },
+        );
+        series_id
+    }
+
+    /// Back (or oppose) a series while all of its rounds take bets. Top‑ups
+    /// must stay on the same side.
+    pub fn bet_series(env: Env, player: Address, series_id: u32, backs: bool, amount: i128) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+
+        let mut series = load_series(&env, series_id);
+        for round_id in series.rounds.iter() {
+            if load_round_advanced(&env, round_id).state != RoundState::Open {
+                panic_with_error!(env, Error::BettingClosed);
+            }
+        }
+        let bet_key = DataKey::SeriesBet(series_id, player.clone());
+        let held = match env
+            .storage()
+            .persistent()
+            .get::<DataKey, SeriesBet>(&bet_key)
+        {
+            Some(b) if b.backs != backs => panic_with_error!(env, Error::InvalidSide),
+            Some(b) => b.amount,
+            None => 0,
+        };
+        check_risk(&env, &player, series.rounds.get_unchecked(0), amount);
+
+        pull(&env, &player, amount);
+        // rounds stay until the series they carry bets for is decided
+        let pooled = checked_add(&env, series.backing, series.opposing);
+        if pooled == 0 {
+            for round_id in series.rounds.iter() {
+                hold_round(&env, round_id);
+            }
+        }
+        env.storage().persistent().set(
+            &bet_key,
+            &SeriesBet {
+                backs,
+                amount: checked_add(&env, held, amount),
+            },
+        );
+        if backs {
+            series.backing = checked_add(&env, series.backing, amount);
+        } else {
+            series.opposing = checked_add(&env, series.opposing, amount);
+        }
+        env.storage()
+            .persistent()
+            .set(&DataKey::Series(series_id), &series);
+    }
+
+    /// Collect a series bet once every round pays out: the right call
+    /// shares both pools; a void series, or one nobody called, refunds.
+    pub fn claim_series(env: Env, player: Address, series_id: u32) {
+        require_live(&env);
+        player.require_auth();
+        compliance::require_unfrozen(&env, &player);
+
+        let mut series = load_series(&env, series_id);
+        if !series.decided {
+            decide(&env, series_id, &mut series);
+        }
+        let result = series.result;
+        let bet_key = DataKey::SeriesBet(series_id, player.clone());
+        let bet: SeriesBet = env
+            .storage()
+            .persistent()
+            .get(&bet_key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&bet_key);
+
+        let winning_pool = match result {
+            Some(true) => series.backing,
+            Some(false) => series.opposing,
+            None => 0,
+        };
+        let payout = match result {
+            Some(hit) if winning_pool > 0 && bet.backs != hit => return,
+            Some(_) if winning_pool > 0 => mul_div(
+                &env,
+                bet.amount,
+                checked_add(&env, series.backing, series.opposing),
+                winning_pool,
+            ),
+            _ => bet.amount,
+        };
+        pay(&env, &token_client(&env), &player, payout);
+    }
+
+    /// Decide a series once every round pays out, so those rounds can be
+    /// pruned. Claims do this too. Anyone may call it.
+    pub fn settle_series(env: Env, series_id: u32) {
+        require_live(&env);
+        let mut series = load_series(&env, series_id);
+        if series.decided {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        decide(&env, series_id, &mut series);
+    }
+
+    pub fn get_series(env: Env, series_id: u32) -> Series {
+        load_series(&env, series_id)
+    }
+
+    /// A player's bet on a series, if they hold one.
+    pub fn get_series_bet(env: Env, series_id: u32, player: Address) -> Option<SeriesBet> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::SeriesBet(series_id, player))
+    }
+}
@@ -0,0 +1,173 @@
+//! Outcome shares. Collateral splits 1:1 into a HIGH and a LOW share of a
+//! round (a complete set), which move between addresses like token
+//! balances, so positions can be traded outside the contract. A set
+//! merges back into collateral at any time; once claims open each winning
+//! share redeems for one token unit, and on a push or void every share for
+//! half of one.
+
+use soroban_sdk::{contractimpl, panic_with_error, Address, Env};
+
+use crate::events;
+use crate::state::load_round_advanced;
+use crate::{
+    checked_add, compliance, extend_written, in_default_token, load_round, pay, pull, require_live,
+    require_paying, token_client, DataKey, Error, KalePrediction, KalePredictionArgs,
+    KalePredictionClient, Market, Round, RoundState, Side,
+};
+
+fn balance(e: &Env, round_id: u32, side: Side, owner: &Address) -> i128 {
+    e.storage()
+        .persistent()
+        .get(&DataKey::ShareBalance(round_id, side, owner.clone()))
+        .unwrap_or(0)
+}
+
+/// Add `delta` (possibly negative) to a balance; it may not go below zero.
+fn credit(e: &Env, round_id: u32, side: Side, owner: &Address, delta: i128) {
+    let updated = checked_add(e, balance(e, round_id, side, owner), delta);
+    if updated < 0 {
+        panic_with_error!(e, Error::InsufficientShares);
+    }
+    let key = DataKey::ShareBalance(round_id, side, owner.clone());
+    if updated == 0 {
+        e.storage().persistent().remove(&key);
+    } else {
+        e.storage().persistent().set(&key, &updated);
+    }
+}
+
+/// Add `delta` (possibly negative) to a round's `side` shares in issue.
+fn issue(e: &Env, round_id: u32, side: Side, delta: i128) {
+    let key = DataKey::ShareSupply(round_id, side);
+    let supply: i128 = e.storage().persistent().get(&key).unwrap_or(0);
+    match checked_add(e, supply, delta) {
+        0 => e.storage().persistent().remove(&key),
+        supply => {
+            e.storage().persistent().set(&key, &supply);
+            extend_written(e, &key);
+        }
+    }
+}
+
+/// Whether shares that `redeem_set` would still pay out are in issue.
+pub(crate) fn is_outstanding(e: &Env, round_id: u32, round: &Round) -> bool {
+    let issued = |side| {
+        e.storage()
+            .persistent()
+            .get::<DataKey, i128>(&DataKey::ShareSupply(round_id, side))
+            .is_some()
+    };
+    match round.outcome.winner() {
+        Some(side) => issued(side),
+        None => issued(Side::Higher) || issued(Side::Lower),
+    }
+}
+
+fn require_binary(e: &Env, side: Side) {
+    if !matches!(side, Side::Higher | Side::Lower) {
+        panic_with_error!(e, Error::InvalidSide);
+    }
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Lock `amount` of collateral for `amount` HIGH and `amount` LOW
+    /// shares of an over/under round that has not been decided yet.
+    pub fn split_set(env: Env, owner: Address, round_id: u32, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        owner.require_auth();
+
+        let round = load_round_advanced(&env, round_id);
+        if !matches!(round.market, Market::Invocations | Market::Price(_))
+            || !in_default_token(&env, &round)
+        {
+            panic_with_error!(env, Error::WrongMarket);
+        }
+        if !matches!(round.state, RoundState::Open | RoundState::Locked) {
+            panic_with_error!(env, Error::AlreadyResolved);
+        }
+
+        pull(&env, &owner, amount);
+        credit(&env, round_id, Side::Higher, &owner, amount);
+        credit(&env, round_id, Side::Lower, &owner, amount);
+        issue(&env, round_id, Side::Higher, amount);
+        issue(&env, round_id, Side::Lower, amount);
+    }
+
+    /// Burn `amount` HIGH and `amount` LOW shares for the collateral back.
+    pub fn merge_set(env: Env, owner: Address, round_id: u32, amount: i128) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        owner.require_auth();
+        compliance::require_unfrozen(&env, &owner);
+
+        credit(&env, round_id, Side::Higher, &owner, -amount);
+        credit(&env, round_id, Side::Lower, &owner, -amount);
+        issue(&env, round_id, Side::Higher, -amount);
+        issue(&env, round_id, Side::Lower, -amount);
+        pay(&env, &token_client(&env), &owner, amount);
+    }
+
+    /// Move `amount` of `from`'s `side` shares of a round to `to`, evented
+    /// as `("shares", from, to) → (round_id, side, amount)`.
+    pub fn transfer_shares(
+        env: Env,
+        from: Address,
+        to: Address,
+        round_id: u32,
+        side: Side,
+        amount: i128,
+    ) {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        require_binary(&env, side);
+        from.require_auth();
+        compliance::require_unfrozen(&env, &from);
+
+        credit(&env, round_id, side, &from, -amount);
+        credit(&env, round_id, side, &to, amount);
+        events::publish(&env, "shares", (from, to), (round_id, side, amount));
+    }
+
+    /// Redeem every share `owner` holds in a round whose claims are open.
+    pub fn redeem_set(env: Env, owner: Address, round_id: u32) -> i128 {
+        require_live(&env);
+        owner.require_auth();
+        compliance::require_unfrozen(&env, &owner);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let higher = balance(&env, round_id, Side::Higher, &owner);
+        let lower = balance(&env, round_id, Side::Lower, &owner);
+        if higher == 0 && lower == 0 {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        credit(&env, round_id, Side::Higher, &owner, -higher);
+        credit(&env, round_id, Side::Lower, &owner, -lower);
+        issue(&env, round_id, Side::Higher, -higher);
+        issue(&env, round_id, Side::Lower, -lower);
+
+        let payout = match round.outcome.winner() {
+            Some(Side::Higher) => higher,
+            Some(Side::Lower) => lower,
+            _ => checked_add(&env, higher, lower) / 2,
+        };
+        if payout > 0 {
+            pay(&env, &token_client(&env), &owner, payout);
+        }
+        payout
+    }
+
+    /// `owner`'s `side` shares of a round.
+    pub fn share_balance(env: Env, owner: Address, round_id: u32, side: Side) -> i128 {
+        load_round(&env, round_id);
+        balance(&env, round_id, side, &owner)
+    }
+}
@@ -0,0 +1,114 @@
+//! Round lifecycle. Every state change goes through `transition`, which
+//! rejects illegal moves; the moves that are due by ledger alone (betting
+//! closing, expiry, claims opening) are applied lazily by `advance` the
+//! next time the round is loaded.
+//!
+//! ```text
+//! Created → Open → Locked → Resolved → Settled → Archived
+//!            ╰───────┴──→ Voided ─────────╯
+//! ```
+
+use soroban_sdk::{contracttype, panic_with_error, Env};
+
+use crate::conditions;
+use crate::epochs::chain_next;
+use crate::events;
+use crate::sealed;
+use crate::{close_round, index_live, load_round, save_round, Deadline, Error, Outcome, Round};
+
+#[contracttype]
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum RoundState {
+    Created = 0,  // stored, not yet taking bets
+    Open = 1,     // accepting bets until `deadline_ledger`
+    Locked = 2,   // betting closed, awaiting resolution
+    Resolved = 3, // settled on a count; amend window running
+    Voided = 4,   // cancelled or expired; stakes are returned
+    Settled = 5,  // payouts open
+    Archived = 6, // every position paid out
+}
+
+/// Validate and apply a move in memory only. A voided round hands its
+/// unrevealed sealed stakes back to their owners rather than the pot.
+fn step(e: &Env, round_id: u32, round: &mut Round, to: RoundState) {
+    use RoundState::*;
+    let legal = matches!(
+        (round.state, to),
+        (Created, Open)
+            | (Open, Locked)
+            | (Open | Locked, Voided)
+            | (Locked, Resolved)
+            | (Resolved | Voided, Settled)
+            | (Settled, Archived)
+    );
+    if !legal {
+        panic_with_error!(e, Error::InvalidTransition);
+    }
+    round.state = to;
+    if to == Voided {
+        sealed::release(e, round_id, round);
+    }
+}
+
+/// Move `round` to `to`. Legal moves are evented as
+/// `("state", round_id) → (from, to)`; opening and closing for bets keeps
+/// the live index, leaving the unresolved set also does the open‑round
+/// book‑keeping and starts the next round of an epoch chain, and settling
+/// lets go of the round it was conditional on. The caller saves the round.
+pub(crate) fn transition(e: &Env, round_id: u32, round: &mut Round, to: RoundState) {
+    let from = round.state;
+    step(e, round_id, round, to);
+    if from == RoundState::Open || to == RoundState::Open {
+        index_live(e, round_id, round);
+    }
+    if matches!(to, RoundState::Resolved | RoundState::Voided) {
+        close_round(e);
+        chain_next(e, round_id, round);
+    }
+    if to == RoundState::Settled {
+        conditions::release(e, round_id);
+    }
+    events::publish(e, "state", (round_id,), (from, to));
+}
+
+/// Apply every move that is due by ledger alone; with `record` unset they
+/// are only projected (for views). Returns whether the state changed.
+pub(crate) fn advance(e: &Env, round_id: u32, round: &mut Round, record: bool) -> bool {
+    let now = e.ledger().sequence();
+    let hard_deadline = round.finality.plus(round.rules.grace_ledgers);
+    let from = round.state;
+    loop {
+        let to = match round.state {
+            RoundState::Open if round.deadline.passed(e) => RoundState::Locked,
+            RoundState::Locked if hard_deadline.passed(e) => {
+                round.outcome = Outcome::Expired;
+                round.resolution_ledger = match hard_deadline {
+                    Deadline::Ledger(l) => l,
+                    Deadline::Timestamp(_) => now,
+                };
+                RoundState::Voided
+            }
+            RoundState::Resolved
+                if now > round.resolution_ledger + round.rules.amend_window_ledgers =>
+            {
+                RoundState::Settled
+            }
+            RoundState::Voided => RoundState::Settled,
+            _ => return round.state != from,
+        };
+        if record {
+            transition(e, round_id, round, to);
+        } else {
+            step(e, round_id, round, to);
+        }
+    }
+}
+
+/// `load_round` with due moves applied and persisted.
+pub(crate) fn load_round_advanced(e: &Env, round_id: u32) -> Round {
+    let mut round = load_round(e, round_id);
+    if advance(e, round_id, &mut round, true) {
+        save_round(e, round_id, &round);
+    }
+    round
+}
@@ -0,0 +1,206 @@
+//! Round statistics for frontends: each side's pool, how many players
+//! back it, the odds its pool implies and what it would pay per unit if
+//! it won now, all worked out the way the contract itself pays. Players'
+//! lifetime records in the deployment's token are kept here too.
+
+use soroban_sdk::{contractimpl, contracttype, Address, Env, Map, Vec};
+
+use crate::state::load_round_advanced;
+use crate::{
+    in_default_token, load_config, mul_div, pool, protocol_fee, total_pool, DataKey,
+    KalePrediction, KalePredictionArgs, KalePredictionClient, Market, Outcome, Round, Side, Stake,
+};
+
+/// One side of a round.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SideStats {
+    pub side: Side,
+    pub pool: i128,
+    pub bettors: u32,        // players who backed it, still counted once paid out
+    pub implied_bps: u32,    // the side's share of the pot it competes for
+    pub multiplier_bps: u32, // payout per unit staked if it won now, after fees
+}
+
+/// A round at a glance. Scalar rounds list no sides.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoundStats {
+    pub total_pool: i128,
+    pub positions: u32,
+    pub sides: Vec<SideStats>,
+}
+
+/// A player's record over rounds in the deployment's token. A round counts
+/// once their stake in it is claimed or refunded; a losing stake, once it
+/// is claimed for nothing.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PlayerStats {
+    pub wagered: i128, // every bet placed, stakes later taken back included
+    pub wins: u32,
+    pub losses: u32,
+    pub net: i128, // paid out less staked, over the rounds counted
+}
+
+fn load_player(e: &Env, player: &Address) -> PlayerStats {
+    e.storage()
+        .persistent()
+        .get(&DataKey::PlayerStats(player.clone()))
+        .unwrap_or_default()
+}
+
+fn save_player(e: &Env, player: &Address, stats: &PlayerStats) {
+    e.storage()
+        .persistent()
+        .set(&DataKey::PlayerStats(player.clone()), stats);
+}
+
+/// Add a bet of `amount` to `player`'s volume.
+pub(crate) fn record_wager(e: &Env, round: &Round, player: &Address, amount: i128) {
+    if !in_default_token(e, round) {
+        return;
+    }
+    let mut stats = load_player(e, player);
+    stats.wagered += amount;
+    save_player(e, player, &stats);
+}
+
+/// Count `stake`'s result, `payout` net of fees. Stakes coming back from a
+/// push or a void are neither won nor lost.
+pub(crate) fn record_result(e: &Env, round: &Round, player: &Address, stake: &Stake, payout: i128) {
+    if !in_default_token(e, round) {
+        return;
+    }
+    let mut stats = load_player(e, player);
+    let decided = !matches!(
+        round.outcome,
+        Outcome::Push | Outcome::Voided | Outcome::Expired
+    );
+    if decided && payout > stake.total(e) {
+        stats.wins += 1;
+    } else if decided {
+        stats.losses += 1;
+    }
+    stats.net += payout - stake.total(e);
+    save_player(e, player, &stats);
+}
+
+fn bettors(e: &Env, round_id: u32) -> Map<Side, u32> {
+    e.storage()
+        .persistent()
+        .get(&DataKey::Bettors(round_id))
+        .unwrap_or_else(|| Map::new(e))
+}
+
+/// Sides `stake` has money on.
+fn held(stake: Option<&Stake>) -> [Option<Side>; 2] {
+    match stake {
+        Some(s) => [
+            (s.amount > 0).then_some(s.side),
+            s.side.opposite().filter(|_| s.hedge > 0),
+        ],
+        None => [None, None],
+    }
+}
+
+/// Recount a round's bettors per side as a stake goes from `before` to
+/// `after`.
+pub(crate) fn count_bettors(e: &Env, round_id: u32, before: Option<&Stake>, after: Option<&Stake>) {
+    let (was, is) = (held(before), held(after));
+    if was == is {
+        return;
+    }
+    let mut counts = bettors(e, round_id);
+    for side in was.iter().flatten().filter(|s| !is.contains(&Some(**s))) {
+        counts.set(*side, counts.get(*side).unwrap_or(0) - 1);
+    }
+    for side in is.iter().flatten().filter(|s| !was.contains(&Some(**s))) {
+        counts.set(*side, counts.get(*side).unwrap_or(0) + 1);
+    }
+    e.storage()
+        .persistent()
+        .set(&DataKey::Bettors(round_id), &counts);
+}
+
+/// The round's sides, each with the outcome it wins on (`None` for ladder
+/// rungs, which are decided on their own).
+fn sides(e: &Env, round: &Round) -> Vec<(Side, Option<Outcome>)> {
+    let mut sides = Vec::new(e);
+    match &round.market {
+        Market::Invocations | Market::Price(_) => {
+            sides.push_back((Side::Higher, Some(Outcome::Higher)));
+            sides.push_back((Side::Lower, Some(Outcome::Lower)));
+        }
+        Market::Buckets(_) => {
+            for i in 0..round.bucket_pools.len() {
+                sides.push_back((Side::Bucket(i), Some(Outcome::Bucket(i))));
+            }
+        }
+        Market::Ladder(lines) => {
+            for i in 0..lines.len() {
+                sides.push_back((Side::Over(i), None));
+                sides.push_back((Side::Under(i), None));
+            }
+        }
+        Market::Scalar => {}
+    }
+    sides
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Pools, bettors, implied odds and payout multipliers per side, as
+    /// things stand. Ladder rungs are priced against their own rung's pot.
+    pub fn get_round_stats(env: Env, round_id: u32) -> RoundStats {
+        let round = load_round_advanced(&env, round_id);
+        let counts = bettors(&env, round_id);
+        let claim_bps = i128::from(load_config(&env).fee_bps);
+        let total = total_pool(&round);
+
+        let mut sides = Vec::new(&env);
+        for (side, wins_on) in self::sides(&env, &round).iter() {
+            let staked = pool(&round, side);
+            let (pot, fee) = match (side, wins_on) {
+                (Side::Over(i) | Side::Under(i), _) => (
+                    pool(&round, Side::Over(i)) + pool(&round, Side::Under(i)),
+                    0,
+                ),
+                (_, Some(outcome)) => {
+                    let mut won = round.clone();
+                    won.outcome = outcome;
+                    (total, protocol_fee(&env, &won))
+                }
+                _ => (total, 0),
+            };
+            let implied = if pot > 0 {
+                mul_div(&env, staked, 10_000, pot)
+            } else {
+                0
+            };
+            let multiplier = if staked > 0 {
+                let gross = mul_div(&env, pot - fee, 10_000, staked);
+                gross - mul_div(&env, gross, claim_bps, 10_000)
+            } else {
+                0
+            };
+            sides.push_back(SideStats {
+                side,
+                pool: staked,
+                bettors: counts.get(side).unwrap_or(0),
+                implied_bps: implied as u32,
+                multiplier_bps: multiplier.min(u32::MAX.into()) as u32,
+            });
+        }
+        RoundStats {
+            total_pool: total,
+            positions: round.positions,
+            sides,
+        }
+    }
+
+    /// `player`'s lifetime record.
+    pub fn get_player_stats(env: Env, player: Address) -> PlayerStats {
+        load_player(&env, &player)
+    }
+}
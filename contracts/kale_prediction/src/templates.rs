@@ -0,0 +1,63 @@
+//! Round templates: named presets of windows and `Rules`, so the admin (or
+//! a keeper acting as admin) can start a count round from just a name and
+//! a line, and recurring markets cannot drift in their parameters.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env, Symbol};
+
+use crate::{
+    create_round, require_admin, require_live, DataKey, Deadline, Error, KalePrediction,
+    KalePredictionArgs, KalePredictionClient, Market, Rules,
+};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Template {
+    pub betting_ledgers: u32,  // from start to the betting deadline
+    pub finality_ledgers: u32, // from the deadline to finality
+    pub rules: Rules,
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Register (or replace) template `name`, or delete it with `None`.
+    /// Its rules are checked when a round is started from it.
+    pub fn set_template(env: Env, admin: Address, name: Symbol, template: Option<Template>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let key = DataKey::Template(name);
+        match template {
+            Some(t) if t.betting_ledgers == 0 => panic_with_error!(env, Error::InvalidRules),
+            Some(t) => env.storage().persistent().set(&key, &t),
+            None => env.storage().persistent().remove(&key),
+        }
+    }
+
+    /// Start a count round on `predicted_count` from template `name`, its
+    /// windows counted from now.
+    pub fn start_from_template(
+        env: Env,
+        admin: Address,
+        name: Symbol,
+        predicted_count: u32,
+    ) -> u32 {
+        let template: Template = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Template(name))
+            .unwrap_or_else(|| panic_with_error!(env, Error::InvalidRules));
+        let deadline = env.ledger().sequence() + template.betting_ledgers;
+        create_round(
+            &env,
+            &admin,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline),
+            Deadline::Ledger(deadline + template.finality_ledgers),
+            template.rules,
+        )
+    }
+
+    pub fn get_template(env: Env, name: Symbol) -> Option<Template> {
+        env.storage().persistent().get(&DataKey::Template(name))
+    }
+}
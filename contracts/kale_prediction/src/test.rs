@@ -8,30 +8,271 @@ extern crate std;
 use std::panic::{catch_unwind, AssertUnwindSafe};
 use std::println;
 
+use ed25519_dalek::{Signer, SigningKey};
+
 use soroban_sdk::{
+    contract, contractimpl, contracttype, symbol_short,
     testutils::Ledger,
-    testutils::{Address as _, EnvTestConfig},
+    testutils::{
+        storage::Instance as _, storage::Persistent as _, storage::Temporary as _, Address as _,
+        EnvTestConfig, Events,
+    },
     token::{self, StellarAssetClient},
-    Address, Env,
+    vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, Symbol, TryIntoVal,
+};
+
+use crate::compliance::RoundAllowlist;
+use crate::conditions::Precondition;
+use crate::creators::CreatorTerms;
+use crate::duels::DuelTerms;
+use crate::epochs::{EpochSchedule, LineSource};
+use crate::events::SCHEMA_VERSION;
+use crate::governance::{Ballot, GovernanceConfig};
+use crate::insurance::{InsuranceTerms, Policy, Reserve};
+use crate::lmsr::{cost, exp_neg, ln_1p, SCALE};
+use crate::optimistic::OptimisticConfig;
+use crate::oracle::{Asset, EpochBounds, PriceData};
+use crate::roles::Role;
+use crate::scalar::MAX_GUESSES;
+use crate::sealed::SealedBet;
+use crate::seasons::SEASON_ENTRY_LEDGERS;
+use crate::templates::Template;
+use crate::timelock::{Change, Fee};
+use crate::vault::FixedOdds;
+use crate::{
+    gross_payout, BetCaps, Committee, Config, DataKey, Deadline, Error, FeeCurve, FeedSet,
+    KalePrediction, KalePredictionClient, Metric, Outcome, PriceFeed, RiskConfig, RoundState,
+    Rules, Side, Stake, TiePolicy, TokenInfo, AMEND_WINDOW_LEDGERS, ENTRY_TTL_LEDGERS,
+    GRACE_LEDGERS, PUBLIC_RESOLVE_LEDGERS, SECS_PER_LEDGER, SUNSET_CLAIM_LEDGERS,
+    SUNSET_DELAY_LEDGERS,
 };
 
-use crate::{KalePrediction, KalePredictionClient, Side, GRACE_LEDGERS};
+// ---------------------------------------------------------------------
+// Mock count oracle
+// ---------------------------------------------------------------------
+
+/// Stand‑in for the Kale invocation‑count source; tests set the count.
+#[contract]
+pub struct MockOracle;
+
+#[contractimpl]
+impl MockOracle {
+    pub fn set_count(env: Env, count: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("count"), &count);
+    }
+
+    pub fn invocation_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("count"))
+            .unwrap_or(0)
+    }
+
+    pub fn set_stats(env: Env, harvested: u32, staked: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("harvest"), &harvested);
+        env.storage()
+            .instance()
+            .set(&symbol_short!("staked"), &staked);
+    }
+
+    pub fn harvest_volume(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("harvest"))
+            .unwrap_or(0)
+    }
+
+    pub fn total_staked(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("staked"))
+            .unwrap_or(0)
+    }
+}
+
+/// Stand‑in allowlist registry approving one configured account.
+#[contract]
+pub struct MockAllowlist;
+
+#[contractimpl]
+impl MockAllowlist {
+    pub fn approve(env: Env, account: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("approved"), &account);
+    }
+
+    pub fn is_allowed(env: Env, account: Address) -> bool {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&symbol_short!("approved"))
+            == Some(account)
+    }
+}
+
+/// Stand‑in risk scorer; every player gets the same configured score.
+#[contract]
+pub struct MockRisk;
+
+#[contractimpl]
+impl MockRisk {
+    pub fn set_score(env: Env, score: u32) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("score"), &score);
+    }
+
+    pub fn score(env: Env, _player: Address) -> u32 {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("score"))
+            .unwrap_or(0)
+    }
+}
+
+/// Stand‑in farm schedule: fixed epochs of `FARM_EPOCH_LEDGERS` from ledger 0.
+#[contract]
+pub struct MockFarm;
+
+const FARM_EPOCH_LEDGERS: u32 = 60;
+
+#[contractimpl]
+impl MockFarm {
+    pub fn epoch_bounds(_env: Env, index: u32) -> EpochBounds {
+        EpochBounds {
+            start_ledger: index * FARM_EPOCH_LEDGERS,
+            end_ledger: (index + 1) * FARM_EPOCH_LEDGERS - 1,
+        }
+    }
+}
+
+/// Stand‑in SEP‑40 feed quoting one price for every asset.
+#[contract]
+pub struct MockPrice;
+
+#[contractimpl]
+impl MockPrice {
+    pub fn set_price(env: Env, price: i128, timestamp: u64) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("price"), &PriceData { price, timestamp });
+    }
+
+    pub fn lastprice(env: Env, _asset: Asset) -> Option<PriceData> {
+        env.storage().instance().get(&symbol_short!("price"))
+    }
+}
+
+/// Opens a price round on KALE with a line of 1.0 (7 decimals) and
+/// returns it with its feed.
+fn price_round(env: &Env, kp: &KalePredictionClient, admin: &Address) -> (u32, Address) {
+    let oracle = env.register(MockPrice, ());
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_price_round(
+        admin,
+        &PriceFeed {
+            oracle: oracle.clone(),
+            asset: Asset::Other(symbol_short!("KALE")),
+            line: 10_000_000,
+        },
+        &(cur + 5),
+        &(cur + 10),
+    );
+    (round_id, oracle)
+}
+
+/// Registers a `MockRisk` scoring everyone `score` and installs the hook
+/// for bets of 100+ (flag at 50, reject at 80).
+fn setup_risk(env: &Env, kp: &KalePredictionClient, admin: &Address, score: u32) -> Address {
+    let risk = env.register(MockRisk, ());
+    MockRiskClient::new(env, &risk).set_score(&score);
+    kp.set_risk_config(
+        admin,
+        &Some(RiskConfig {
+            oracle: risk.clone(),
+            min_amount: 100,
+            flag_score: 50,
+            reject_score: 80,
+        }),
+    );
+    risk
+}
+
+/// Registers a `MockOracle` reporting `count` and wires it into `kp`.
+fn setup_oracle(env: &Env, kp: &KalePredictionClient, admin: &Address, count: u32) -> Address {
+    let oracle = env.register(MockOracle, ());
+    MockOracleClient::new(env, &oracle).set_count(&count);
+    kp.set_oracle(admin, &oracle);
+    oracle
+}
+
+/// Registers one `MockOracle` per count and installs them as a feed set
+/// tolerating a spread of 10.
+fn setup_feeds(
+    env: &Env,
+    kp: &KalePredictionClient,
+    admin: &Address,
+    counts: &[u32],
+) -> std::vec::Vec<Address> {
+    let oracles: std::vec::Vec<Address> = counts
+        .iter()
+        .map(|count| {
+            let oracle = env.register(MockOracle, ());
+            MockOracleClient::new(env, &oracle).set_count(count);
+            oracle
+        })
+        .collect();
+    let mut list = soroban_sdk::Vec::new(env);
+    for oracle in &oracles {
+        list.push_back(oracle.clone());
+    }
+    kp.set_feeds(
+        admin,
+        &Some(FeedSet {
+            oracles: list,
+            max_spread: 10,
+        }),
+    );
+    oracles
+}
 
 // ---------------------------------------------------------------------
 // Test‑bed bootstrap
 // ---------------------------------------------------------------------
 
-/// Builds a fresh environment with:
-/// * an on‑the‑fly SEP‑41 token contract (mint authority held by `token_admin`)
-/// * a deployed and initialised Kale‑Prediction contract using that token.
-fn setup() -> (
+type TestBed = (
     Env,
     StellarAssetClient<'static>, // mint‑only helper
     token::Client<'static>,      // generic token client for balance checks
     KalePredictionClient<'static>,
     Address, // admin
-) {
-    let mut env = Env::default();
+);
+
+/// Builds a fresh environment with:
+/// * an on‑the‑fly SEP‑41 token contract (mint authority held by `token_admin`)
+/// * a deployed and initialised Kale‑Prediction contract using that token.
+fn setup() -> TestBed {
+    deploy(Env::default())
+}
+
+/// `setup` for tests that jump weeks ahead: every entry, the token's
+/// included, lives through the jump.
+fn setup_long_lived() -> TestBed {
+    let env = Env::default();
+    env.ledger().with_mut(|li| {
+        li.min_persistent_entry_ttl = 1_000_000;
+        li.max_entry_ttl = 2_000_000;
+    });
+    deploy(env)
+}
+
+fn deploy(mut env: Env) -> TestBed {
     env.mock_all_auths();
     env.set_config(EnvTestConfig {
         capture_snapshot_at_drop: false,
@@ -54,6 +295,106 @@ fn setup() -> (
     (env, mint_client, token_client, kp_client, admin)
 }
 
+// The Stellar Asset Contract's balance entry, as it stores it.
+#[contracttype]
+enum SacKey {
+    Balance(Address),
+}
+
+#[contracttype]
+struct SacBalance {
+    amount: i128,
+    authorized: bool,
+    clawback: bool,
+}
+
+/// Credits `amount` of native XLM to `to` by writing its balance into the
+/// native Stellar Asset Contract (which has no admin to mint with).
+fn fund_native(env: &Env, xlm: &Address, to: &Address, amount: i128) {
+    env.as_contract(xlm, || {
+        env.storage().persistent().set(
+            &SacKey::Balance(to.clone()),
+            &SacBalance {
+                amount,
+                authorized: true,
+                clawback: false,
+            },
+        );
+    });
+}
+
+/// Installs a 2‑of‑3 resolver committee and returns its members.
+fn setup_committee(env: &Env, kp: &KalePredictionClient, admin: &Address) -> [Address; 3] {
+    let members = [
+        Address::generate(env),
+        Address::generate(env),
+        Address::generate(env),
+    ];
+    kp.set_committee(
+        admin,
+        &Some(Committee {
+            members: vec![
+                env,
+                members[0].clone(),
+                members[1].clone(),
+                members[2].clone(),
+            ],
+            threshold: 2,
+        }),
+    );
+    members
+}
+
+/// Installs a fixed ed25519 attestor key and returns it for signing.
+fn setup_attestor(env: &Env, kp: &KalePredictionClient, admin: &Address) -> SigningKey {
+    let key = SigningKey::from_bytes(&[7; 32]);
+    kp.set_attestor(
+        admin,
+        &Some(BytesN::from_array(env, &key.verifying_key().to_bytes())),
+    );
+    key
+}
+
+/// Attestor's signature over `(contract, round_id, count)` as XDR.
+fn attest(
+    env: &Env,
+    kp: &KalePredictionClient,
+    key: &SigningKey,
+    round_id: u32,
+    count: u32,
+) -> BytesN<64> {
+    let message = (kp.address.clone(), round_id, count).to_xdr(env);
+    let mut buf = std::vec![0u8; message.len() as usize];
+    message.copy_into_slice(&mut buf);
+    BytesN::from_array(env, &key.sign(&buf).to_bytes())
+}
+
+/// Enables optimistic resolution with a 500 bond and 30‑ledger liveness.
+fn setup_optimistic(kp: &KalePredictionClient, admin: &Address) {
+    kp.set_optimistic(
+        admin,
+        &Some(OptimisticConfig {
+            bond: 500,
+            liveness_ledgers: 30,
+        }),
+    );
+}
+
+/// Opens a round that is past finality, ready for proposals.
+fn finalised_round(env: &Env, kp: &KalePredictionClient, admin: &Address) -> u32 {
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(admin, &100u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 2);
+    round_id
+}
+
+/// Advance past the post‑resolution amend window so claims are open.
+fn open_claims(env: &Env) {
+    let seq = env.ledger().sequence();
+    env.ledger()
+        .set_sequence_number(seq + AMEND_WINDOW_LEDGERS + 1);
+}
+
 // ---------------------------------------------------------------------
 // Tests
 // ---------------------------------------------------------------------
@@ -84,6 +425,7 @@ fn happy_path_claims() {
 
     // actual count higher than predicted ⇒ Higher wins
     kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
 
     let bal_a_before = tok.balance(&alice);
     let bal_b_before = tok.balance(&bob);
@@ -157,6 +499,7 @@ fn proportional_split_two_winners() {
     // move past finality and resolve with actual LOWER than predicted
     env.ledger().set_sequence_number(finality + 1);
     kp.resolve_round(&admin, &round_id, &100u32); // actual < predicted ⇒ Lower wins
+    open_claims(&env);
 
     // balances before claims are 0 because stakes are locked
     assert_eq!(tok.balance(&alice), 0);
@@ -183,128 +526,4403 @@ fn proportional_split_two_winners() {
     println!("✅ proportional_split_two_winners passed");
 }
 
-// ---------------------------------------------------------------------
-// Error‑coverage tests (one per Error::* variant)
-// ---------------------------------------------------------------------
+/// Exact hit on the line ⇒ push: both sides get their stake back.
+#[test]
+fn push_returns_all_stakes() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
 
-/// Non‑admin attempts to start and resolve ➜ `Unauthorized` (#1).
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+
+    env.ledger().set_sequence_number(finality + 1);
+    kp.resolve_round(&admin, &round_id, &100u32);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Push);
+    open_claims(&env);
+
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 100);
+    assert_eq!(tok.balance(&bob), 300);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// Admin cancellation lets every bettor reclaim their stake at once.
 #[test]
-#[should_panic(expected = "Error(Contract, #1)")]
-fn unauthorized_admin_calls() {
-    let (env, _mint, _tok, kp, _admin) = setup();
-    let eve = Address::generate(&env);
+fn cancelled_round_refunds_via_claim() {
+    let (env, mint, tok, kp, admin) = setup();
     let cur = env.ledger().sequence();
-    let deadline = cur + 2;
-    let finality = cur + 4;
-    // eve tries to start
-    kp.start_round(&eve, &1u32, &deadline, &finality);
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &70);
+    mint.mint(&bob, &30);
+    kp.bet(&alice, &round_id, &Side::Higher, &70);
+    kp.bet(&bob, &round_id, &Side::Lower, &30);
+
+    // no need to wait for finality or the grace period
+    kp.cancel_round(&admin, &round_id);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Voided);
+
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 70);
+    assert_eq!(tok.balance(&bob), 30);
 }
 
-/// Claiming an unknown round ➜ `RoundNotFound` (#3).
+/// A cancelled round's claim window runs from the cancellation, so its
+/// refunds cannot be swept straight away.
 #[test]
-#[should_panic(expected = "Error(Contract, #3)")]
-fn round_not_found_claim() {
-    let (env, _mint, _tok, kp, _admin) = setup();
-    let frank = Address::generate(&env); // Higher loser, stake 400
-    kp.claim(&frank, &999u32);
+fn cancelled_round_claims_cannot_be_swept_early() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10)))
+    };
+    // still taking bets well past a claim window counted from ledger 0
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 500), &(cur + 600), &rules);
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &70);
+    kp.bet(&alice, &round_id, &Side::Higher, &70);
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 200);
+    kp.cancel_round(&admin, &round_id);
+    assert_eq!(kp.try_sweep_unclaimed(&round_id), Err(Ok(Error::TooEarly)));
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 70);
 }
 
-/// Betting after the deadline must fail ➜ `BettingClosed` (#4).
+/// A round nobody resolves walks Open → Locked → Voided (expired) →
+/// Settled, and `claim` hands stakes back without the caller needing
+/// `refund`; the last payout archives it.
 #[test]
-#[should_panic(expected = "Error(Contract, #4)")]
-fn bet_after_deadline_panics() {
+fn expired_round_refunds_via_claim() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+    assert_eq!(kp.get_status(&round_id), RoundState::Open);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Pending);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &70);
+    mint.mint(&bob, &30);
+    kp.bet(&alice, &round_id, &Side::Higher, &70);
+    kp.bet(&bob, &round_id, &Side::Lower, &30);
+
+    env.ledger().set_sequence_number(finality + GRACE_LEDGERS);
+    assert_eq!(kp.get_status(&round_id), RoundState::Locked);
+
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.state, RoundState::Settled);
+    assert_eq!(round.outcome, Outcome::Expired);
+
+    kp.claim(&alice, &round_id);
+    kp.refund(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 70);
+    assert_eq!(tok.balance(&bob), 30);
+    assert_eq!(kp.get_status(&round_id), RoundState::Archived);
+}
+
+/// Each lifecycle move is evented as `("state", round_id) → (from, to)`.
+#[test]
+fn transitions_are_evented() {
     let (env, mint, _tok, kp, admin) = setup();
+    let state_moves = || -> std::vec::Vec<(RoundState, RoundState)> {
+        env.events()
+            .all()
+            .iter()
+            .filter(|(c, topics, _)| {
+                *c == kp.address
+                    && *topics == (symbol_short!("state"), SCHEMA_VERSION, 0u32).into_val(&env)
+            })
+            .map(|(_, _, data)| data.try_into_val(&env).unwrap())
+            .collect()
+    };
 
     let cur = env.ledger().sequence();
-    let deadline = cur + 1;
-    let finality = cur + 10;
-    let round_id = kp.start_round(&admin, &10u32, &deadline, &finality);
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 1), &(cur + 2));
+    let mut moves = state_moves();
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &10);
+    kp.bet(&alice, &round_id, &Side::Higher, &10);
 
-    let dave = Address::generate(&env);
-    mint.mint(&dave, &10);
+    env.ledger().set_sequence_number(cur + 2);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    moves.extend(state_moves());
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    moves.extend(state_moves());
 
-    env.ledger().set_sequence_number(deadline + 1); // after deadline
-    kp.bet(&dave, &round_id, &Side::Higher, &10); // should panic
+    use RoundState::*;
+    assert_eq!(
+        moves,
+        std::vec![
+            (Created, Open),
+            (Open, Locked),
+            (Locked, Resolved),
+            (Resolved, Settled),
+            (Settled, Archived),
+        ]
+    );
 }
 
-/// Admin resolves twice ➜ `AlreadyResolved` (#5).
+/// The oracle's count at `start_round` is the baseline; resolution is
+/// decided on the invocations since then.
 #[test]
-#[should_panic(expected = "Error(Contract, #5)")]
-fn resolve_twice_panics() {
+fn resolution_uses_delta_over_baseline() {
     let (env, _mint, _tok, kp, admin) = setup();
+    let oracle = setup_oracle(&env, &kp, &admin, 5_000);
     let cur = env.ledger().sequence();
-    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 1), &(cur + 2));
+    assert_eq!(kp.get_round(&round_id).baseline_count, 5_000);
+
+    // an absolute reading far above the line is still only 60 new calls
+    MockOracleClient::new(&env, &oracle).set_count(&5_060);
     env.ledger().set_sequence_number(cur + 3);
-    kp.resolve_round(&admin, &round, &2u32);
-    kp.resolve_round(&admin, &round, &3u32); // second time
+    kp.resolve_round(&admin, &round_id, &5_060u32);
+
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.actual_count, 60);
+    assert_eq!(round.outcome, Outcome::Lower);
 }
 
-/// Resolving before finality ➜ `TooEarly` (#6).
+/// Provenance: the resolver and their evidence hash are kept on the round.
 #[test]
-#[should_panic(expected = "Error(Contract, #6)")]
-fn resolve_too_early_panics() {
+fn resolution_records_provenance() {
     let (env, _mint, _tok, kp, admin) = setup();
     let cur = env.ledger().sequence();
-    let round = kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10));
-    env.ledger().set_sequence_number(cur + 6); // before finality
-    kp.resolve_round(&admin, &round, &0u32);
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+
+    let evidence = BytesN::from_array(&env, &[0xab; 32]);
+    kp.resolve_round_with_evidence(&admin, &round_id, &150u32, &Some(evidence.clone()));
+
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.resolved_by, Some(admin));
+    assert_eq!(round.evidence, evidence);
+    assert_eq!(round.resolution_ledger, cur + 3);
 }
 
-/// Claim before resolution ➜ `NotResolved` (#7).
+/// A mistyped count can be corrected before claims open; the payouts
+/// follow the amended outcome.
 #[test]
-#[should_panic(expected = "Error(Contract, #7)")]
-fn claim_not_resolved_panics() {
-    let (env, mint, _tok, kp, admin) = setup();
+fn amend_resolution_within_window() {
+    let (env, mint, tok, kp, admin) = setup();
     let cur = env.ledger().sequence();
-    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 3));
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+
     let alice = Address::generate(&env);
-    mint.mint(&alice, &1);
-    kp.bet(&alice, &round, &Side::Higher, &1);
-    env.ledger().set_sequence_number(cur + 2);
-    kp.claim(&alice, &round);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+
+    env.ledger().set_sequence_number(finality + 1);
+    kp.resolve_round(&admin, &round_id, &1_500u32); // meant 15
+
+    // claims are held back while the resolution may still change
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.claim(&alice, &round_id))).is_err());
+
+    env.ledger()
+        .set_sequence_number(finality + 1 + AMEND_WINDOW_LEDGERS);
+    kp.amend_resolution(&admin, &round_id, &15u32);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.outcome, Outcome::Lower);
+    assert_eq!(round.actual_count, 15);
+
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 0);
+    assert_eq!(tok.balance(&bob), 400);
 }
 
-/// Double claim ➜ `AlreadyClaimed` (#8).
+/// With a 2‑of‑3 committee the round only settles on the second matching
+/// count; a dissenting vote does not count towards it.
 #[test]
-#[should_panic(expected = "Error(Contract, #8)")]
-fn double_claim_panics() {
-    let (env, mint, _tok, kp, admin) = setup();
+fn committee_resolves_on_matching_votes() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let [r1, r2, r3] = setup_committee(&env, &kp, &admin);
     let cur = env.ledger().sequence();
-    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
-    let alice = Address::generate(&env);
-    mint.mint(&alice, &1);
-    kp.bet(&alice, &round, &Side::Higher, &1);
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 1), &(cur + 2));
     env.ledger().set_sequence_number(cur + 3);
-    kp.resolve_round(&admin, &round, &2u32);
-    kp.claim(&alice, &round);
-    kp.claim(&alice, &round); // second claim
+
+    // the admin alone no longer resolves
+    assert!(catch_unwind(AssertUnwindSafe(
+        || kp.resolve_round(&admin, &round_id, &150u32)
+    ))
+    .is_err());
+
+    kp.resolve_round(&r1, &round_id, &150u32);
+    kp.resolve_round(&r2, &round_id, &90u32);
+    assert_eq!(kp.get_status(&round_id), RoundState::Locked);
+
+    kp.resolve_round(&r3, &round_id, &150u32);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.state, RoundState::Resolved);
+    assert_eq!(round.outcome, Outcome::Higher);
+    assert_eq!(round.actual_count, 150);
 }
 
-/// Refund attempt before grace period ➜ `RefundNotAvailable` (#9).
+/// Sunset: no new rounds once the timelock passes, running rounds settle,
+/// and after the claim period whatever is left (here an unclaimed win)
+/// goes to the successor, leaving the contract read‑only.
 #[test]
-#[should_panic(expected = "Error(Contract, #9)")]
-fn refund_before_grace_panics() {
-    let (env, mint, _tok, kp, admin) = setup();
+fn sunset_drains_to_successor() {
+    let (env, mint, tok, kp, admin) = setup_long_lived();
     let cur = env.ledger().sequence();
-    let deadline = cur + 2;
-    let finality = cur + 4;
-    let round = kp.start_round(&admin, &1u32, &deadline, &finality);
-    let frank = Address::generate(&env);
-    mint.mint(&frank, &10);
-    kp.bet(&frank, &round, &Side::Higher, &10);
+    // settles after the sunset takes effect
+    let finality = cur + SUNSET_DELAY_LEDGERS + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
 
-    // move to just after finality but before grace period ends
-    env.ledger().set_sequence_number(finality + 1);
-    kp.refund(&frank, &round); // should panic with #9
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let successor = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+
+    let at = kp.schedule_sunset(&admin);
+    assert_eq!(at, cur + SUNSET_DELAY_LEDGERS);
+
+    env.ledger().set_sequence_number(at);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.start_round(&admin, &1u32, &(at + 1), &(at + 2))
+    }))
+    .is_err());
+    // the open round still blocks the hand‑over
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.sunset_transfer(&admin, &successor))).is_err());
+
+    env.ledger().set_sequence_number(finality);
+    kp.resolve_round(&admin, &round_id, &50u32);
+
+    // claim period still running
+    env.ledger()
+        .set_sequence_number(finality + SUNSET_CLAIM_LEDGERS);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.sunset_transfer(&admin, &successor))).is_err());
+
+    env.ledger()
+        .set_sequence_number(finality + SUNSET_CLAIM_LEDGERS + 1);
+    kp.sunset_transfer(&admin, &successor);
+    assert_eq!(tok.balance(&successor), 400);
+    assert_eq!(tok.balance(&kp.address), 0);
+    // views keep working
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Lower);
 }
 
-/// Bet amount zero ➜ `ZeroAmount` (#10).
+/// Anyone can settle from the oracle once the admin is late, and earns
+/// the bounty out of the pot.
 #[test]
-#[should_panic(expected = "Error(Contract, #10)")]
-fn zero_amount_panics() {
-    let (env, _mint, _tok, kp, admin) = setup();
+fn public_resolve_pays_bounty() {
+    let (env, mint, tok, kp, admin) = setup();
+    let oracle = setup_oracle(&env, &kp, &admin, 1_000);
     let cur = env.ledger().sequence();
-    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+
     let alice = Address::generate(&env);
-    kp.bet(&alice, &round, &Side::Higher, &0);
+    let bob = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint.mint(&alice, &4_000);
+    mint.mint(&bob, &6_000);
+    kp.bet(&alice, &round_id, &Side::Higher, &4_000);
+    kp.bet(&bob, &round_id, &Side::Lower, &6_000);
+    MockOracleClient::new(&env, &oracle).set_count(&1_150);
+
+    // admin window still running
+    env.ledger()
+        .set_sequence_number(finality + PUBLIC_RESOLVE_LEDGERS);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.public_resolve(&keeper, &round_id))).is_err());
+
+    env.ledger()
+        .set_sequence_number(finality + PUBLIC_RESOLVE_LEDGERS + 1);
+    kp.public_resolve(&keeper, &round_id);
+
+    // 0.5 % of 10 000
+    assert_eq!(tok.balance(&keeper), 50);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.bounty, 50);
+    assert_eq!(round.resolved_by, Some(keeper.clone()));
+    open_claims(&env);
+
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 9_950);
+    assert_eq!(tok.balance(&bob), 0);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// Large bets are scored and evented; small ones skip the hook.
+#[test]
+fn risk_hook_flags_large_bets() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let risk = setup_risk(&env, &kp, &admin, 60);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &10u32, &(cur + 5), &(cur + 10));
+
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &200);
+
+    kp.bet(&alice, &round_id, &Side::Higher, &50);
+    // a rejecting score no longer matters below `min_amount`
+    MockRiskClient::new(&env, &risk).set_score(&90);
+    kp.bet(&alice, &round_id, &Side::Higher, &50);
+    MockRiskClient::new(&env, &risk).set_score(&60);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+
+    let risk_events: std::vec::Vec<(u32, i128, u32, bool)> = env
+        .events()
+        .all()
+        .iter()
+        .filter(|(c, topics, _)| {
+            *c == kp.address
+                && *topics == (symbol_short!("risk"), SCHEMA_VERSION, alice.clone()).into_val(&env)
+        })
+        .map(|(_, _, data)| data.try_into_val(&env).unwrap())
+        .collect();
+    assert_eq!(risk_events, std::vec![(round_id, 100, 60, true)]);
+}
+
+/// A tuned keeper fee comes out of every stake pro rata when the public
+/// resolution turns out to be a push.
+#[test]
+fn keeper_fee_on_push_is_shared() {
+    let (env, mint, tok, kp, admin) = setup();
+    let oracle = setup_oracle(&env, &kp, &admin, 0);
+    kp.set_keeper_fee_bps(&admin, &200);
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let keeper = Address::generate(&env);
+    mint.mint(&alice, &4_000);
+    mint.mint(&bob, &6_000);
+    kp.bet(&alice, &round_id, &Side::Higher, &4_000);
+    kp.bet(&bob, &round_id, &Side::Lower, &6_000);
+    MockOracleClient::new(&env, &oracle).set_count(&100);
+
+    env.ledger()
+        .set_sequence_number(finality + PUBLIC_RESOLVE_LEDGERS + 1);
+    kp.public_resolve(&keeper, &round_id);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Push);
+    assert_eq!(tok.balance(&keeper), 200); // 2 % of 10 000
+
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 3_920);
+    assert_eq!(tok.balance(&bob), 5_880);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// Anyone can relay the attestor's signed count; a signature for another
+/// count or round does not verify.
+#[test]
+fn attested_count_resolves_round() {
+    let (env, mint, tok, kp, admin) = setup();
+    let key = setup_attestor(&env, &kp, &admin);
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+
+    env.ledger().set_sequence_number(finality);
+    let signature = attest(&env, &kp, &key, round_id, 150);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.resolve_attested(&round_id, &90u32, &signature)
+    }))
+    .is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.resolve_attested(&(round_id + 1), &150u32, &signature)
+    }))
+    .is_err());
+
+    kp.resolve_attested(&round_id, &150u32, &signature);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.outcome, Outcome::Higher);
+    assert_eq!(round.actual_count, 150);
+    assert_eq!(round.resolved_by, None);
+    assert_eq!(
+        round.evidence,
+        env.crypto().sha256(&signature.into()).to_bytes()
+    );
+
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 400);
+}
+
+/// The same 3:1 book pays a higher protocol fee when the favourite wins
+/// than when the underdog does; the admin collects it once claims open.
+#[test]
+fn fee_curve_charges_favourites_more() {
+    let (env, mint, tok, kp, admin) = setup();
+    let rules = Rules {
+        grace_ledgers: GRACE_LEDGERS,
+        public_resolve_ledgers: PUBLIC_RESOLVE_LEDGERS,
+        amend_window_ledgers: AMEND_WINDOW_LEDGERS,
+        claim_window_ledgers: 0,
+        keeper_fee_bps: 0,
+        tie_policy: TiePolicy::Push,
+        spread: 0,
+        fee_curve: FeeCurve {
+            min_bps: 100,
+            max_bps: 500,
+        },
+        metric: Metric::Invocations,
+    };
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let rounds = [
+        kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &finality, &rules),
+        kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &finality, &rules),
+    ];
+
+    let fav = Address::generate(&env);
+    let dog = Address::generate(&env);
+    mint.mint(&fav, &60_000);
+    mint.mint(&dog, &20_000);
+    for round_id in rounds {
+        kp.bet(&fav, &round_id, &Side::Higher, &30_000);
+        kp.bet(&dog, &round_id, &Side::Lower, &10_000);
+    }
+
+    env.ledger().set_sequence_number(finality);
+    kp.resolve_round(&admin, &rounds[0], &150u32); // favourite wins
+    kp.resolve_round(&admin, &rounds[1], &50u32); // underdog wins
+                                                  // 75 % share ➜ 400 bps of 10 000; 25 % share ➜ 200 bps of 30 000
+    assert_eq!(kp.get_round(&rounds[0]).protocol_fee, 400);
+    assert_eq!(kp.get_round(&rounds[1]).protocol_fee, 600);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.collect_fee(&admin, &rounds[0]))).is_err());
+
+    open_claims(&env);
+    kp.claim(&fav, &rounds[0]);
+    kp.claim(&dog, &rounds[1]);
+    assert_eq!(tok.balance(&fav), 39_600);
+    assert_eq!(tok.balance(&dog), 39_400);
+
+    kp.collect_fee(&admin, &rounds[0]);
+    kp.collect_fee(&admin, &rounds[1]);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.collect_fee(&admin, &rounds[1]))).is_err());
+    assert_eq!(tok.balance(&admin), 1_000);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// An unchallenged proposal settles the round after its liveness window
+/// and the proposer gets the bond back.
+#[test]
+fn unchallenged_proposal_settles() {
+    let (env, mint, tok, kp, admin) = setup();
+    setup_optimistic(&kp, &admin);
+    let round_id = finalised_round(&env, &kp, &admin);
+    let proposer = Address::generate(&env);
+    mint.mint(&proposer, &500);
+
+    kp.propose(&proposer, &round_id, &150u32);
+    assert_eq!(tok.balance(&proposer), 0);
+    assert_eq!(kp.get_proposal(&round_id).unwrap().observed_count, 150);
+
+    let at = env.ledger().sequence();
+    env.ledger().set_sequence_number(at + 30);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.settle_proposal(&round_id))).is_err());
+    env.ledger().set_sequence_number(at + 31);
+    kp.settle_proposal(&round_id);
+
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.outcome, Outcome::Higher);
+    assert_eq!(round.actual_count, 150);
+    assert_eq!(tok.balance(&proposer), 500);
+    assert!(kp.get_proposal(&round_id).is_none());
+}
+
+/// A challenged proposal waits for the admin; the side that was right
+/// takes both bonds and the round settles on the arbitrated count.
+#[test]
+fn disputed_proposal_is_arbitrated() {
+    let (env, mint, tok, kp, admin) = setup();
+    setup_optimistic(&kp, &admin);
+    let round_id = finalised_round(&env, &kp, &admin);
+    let proposer = Address::generate(&env);
+    let challenger = Address::generate(&env);
+    mint.mint(&proposer, &500);
+    mint.mint(&challenger, &500);
+
+    kp.propose(&proposer, &round_id, &150u32);
+    kp.challenge(&challenger, &round_id);
+    assert_eq!(tok.balance(&kp.address), 1_000);
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 31);
+    kp.arbitrate(&admin, &round_id, &80u32);
+
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.outcome, Outcome::Lower);
+    assert_eq!(tok.balance(&challenger), 1_000);
+    assert_eq!(tok.balance(&proposer), 0);
+}
+
+/// A price round settles from a fresh oracle price against its line;
+/// a stale quote is refused.
+#[test]
+fn price_round_settles_on_feed() {
+    let (env, mint, tok, kp, admin) = setup();
+    let (round_id, oracle) = price_round(&env, &kp, &admin);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Lower, &100);
+    kp.bet(&bob, &round_id, &Side::Higher, &300);
+
+    env.ledger().with_mut(|li| {
+        li.sequence_number += 10;
+        li.timestamp = 10_000;
+    });
+    let feed = MockPriceClient::new(&env, &oracle);
+    feed.set_price(&9_500_000, &9_000);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.resolve_price(&round_id))).is_err());
+
+    feed.set_price(&9_500_000, &9_950);
+    kp.resolve_price(&round_id);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.outcome, Outcome::Lower);
+    assert_eq!(round.settled_price, 9_500_000);
+
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 400);
+}
+
+/// Epoch rounds take their windows from the farm schedule and refuse
+/// epochs that are already under way.
+#[test]
+fn epoch_round_follows_farm_schedule() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    kp.set_farm(&admin, &env.register(MockFarm, ()));
+    env.ledger().set_sequence_number(130); // inside epoch 2
+
+    let round_id = kp.start_epoch_round(&admin, &500u32, &3u32);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.deadline, Deadline::Ledger(179));
+    assert_eq!(round.finality, Deadline::Ledger(239));
+    assert_eq!(round.predicted_count, 500);
+
+    assert!(catch_unwind(AssertUnwindSafe(
+        || kp.start_epoch_round(&admin, &500u32, &2u32)
+    ))
+    .is_err());
+}
+
+/// Custom rules are stored on the round: ties go to the over side and
+/// claims open after the shorter amend window. Default rounds snapshot the
+/// keeper fee configured when they start.
+#[test]
+fn custom_rules_apply_per_round() {
+    let (env, mint, tok, kp, admin) = setup();
+    let rules = Rules {
+        grace_ledgers: 30,
+        public_resolve_ledgers: 10,
+        amend_window_ledgers: 5,
+        claim_window_ledgers: 0,
+        keeper_fee_bps: 0,
+        tie_policy: TiePolicy::Higher,
+        spread: 0,
+        fee_curve: FeeCurve {
+            min_bps: 0,
+            max_bps: 0,
+        },
+        metric: Metric::Invocations,
+    };
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 1), &(cur + 2), &rules);
+    assert_eq!(kp.get_rules(&round_id), rules);
+
+    kp.set_keeper_fee_bps(&admin, &300);
+    let plain = kp.start_round(&admin, &100u32, &(cur + 1), &(cur + 2));
+    kp.set_keeper_fee_bps(&admin, &400);
+    let plain_rules = kp.get_rules(&plain);
+    assert_eq!(plain_rules.keeper_fee_bps, 300);
+    assert_eq!(plain_rules.grace_ledgers, GRACE_LEDGERS);
+    assert_eq!(plain_rules.tie_policy, TiePolicy::Push);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round_id, &100u32);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Higher);
+
+    env.ledger().set_sequence_number(cur + 3 + 6);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 400);
+}
+
+/// Wall‑clock rounds lock, finalise and expire on the ledger timestamp,
+/// however many ledgers go by.
+#[test]
+fn timestamp_round_follows_the_clock() {
+    let (env, mint, _tok, kp, admin) = setup_long_lived();
+    let t0 = env.ledger().timestamp();
+    let round_id = kp.start_round_at(
+        &admin,
+        &100u32,
+        &Deadline::Timestamp(t0 + 3_600),
+        &Deadline::Timestamp(t0 + 7_200),
+        &None,
+    );
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 10_000);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+
+    env.ledger().set_timestamp(t0 + 3_601);
+    assert_eq!(kp.get_status(&round_id), RoundState::Locked);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.resolve_round(&admin, &round_id, &150u32)
+    }))
+    .is_err());
+
+    let grace = u64::from(GRACE_LEDGERS) * SECS_PER_LEDGER;
+    env.ledger().set_timestamp(t0 + 7_200 + grace);
+    assert_eq!(kp.get_status(&round_id), RoundState::Locked);
+    env.ledger().set_timestamp(t0 + 7_200 + grace + 1);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Expired);
+    kp.refund(&alice, &round_id);
+}
+
+/// With a feed set the round settles on the median reading, whatever
+/// count the admin passes; an outlier within the spread is outvoted.
+#[test]
+fn feeds_resolve_on_median() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let feeds = setup_feeds(&env, &kp, &admin, &[1_000, 1_004, 998]);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(kp.get_round(&round_id).baseline_count, 1_000);
+
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+
+    for (feed, count) in feeds.iter().zip([1_150, 1_156, 1_146]) {
+        MockOracleClient::new(&env, feed).set_count(&count);
+    }
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &0u32);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.actual_count, 150);
+    assert_eq!(round.outcome, Outcome::Higher);
+}
+
+/// A book left one‑sided at the deadline is voided on demand and its
+/// bettors are paid back at once.
+#[test]
+fn one_sided_round_voids_early() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Higher, &300);
+
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.void_one_sided(&round_id))).is_err());
+    env.ledger().set_sequence_number(cur + 6);
+    kp.void_one_sided(&round_id);
+    assert_eq!(kp.get_status(&round_id), RoundState::Settled);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Voided);
+
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 100);
+    assert_eq!(tok.balance(&bob), 300);
+}
+
+/// `tick` locks, resolves from the oracles and opens claims as each step
+/// falls due, and is a no‑op in between.
+#[test]
+fn tick_runs_due_transitions() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let oracle = setup_oracle(&env, &kp, &admin, 1_000);
+    let cur = env.ledger().sequence();
+    let count_round = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (price_round_id, feed) = price_round(&env, &kp, &admin);
+    assert_eq!(kp.tick(), 0);
+
+    env.ledger().set_sequence_number(cur + 6);
+    assert_eq!(kp.tick(), 2);
+    assert_eq!(kp.tick(), 0);
+    assert_eq!(kp.get_status(&count_round), RoundState::Locked);
+
+    // price rounds resolve from finality, count rounds wait for the admin
+    env.ledger().set_sequence_number(cur + 10);
+    MockPriceClient::new(&env, &feed).set_price(&12_000_000, &env.ledger().timestamp());
+    MockOracleClient::new(&env, &oracle).set_count(&1_150);
+    assert_eq!(kp.tick(), 1);
+    assert_eq!(kp.get_round(&price_round_id).outcome, Outcome::Higher);
+    assert_eq!(kp.get_status(&count_round), RoundState::Locked);
+
+    env.ledger()
+        .set_sequence_number(cur + 10 + PUBLIC_RESOLVE_LEDGERS + 1);
+    assert_eq!(kp.tick(), 2); // count round resolves, price round settles
+    let round = kp.get_round(&count_round);
+    assert_eq!(round.outcome, Outcome::Higher);
+    assert_eq!(round.bounty, 0);
+
+    open_claims(&env);
+    assert_eq!(kp.tick(), 1);
+    assert_eq!(kp.get_status(&count_round), RoundState::Settled);
+    assert_eq!(kp.tick(), 0);
+}
+
+/// A long‑dated round at the front does not keep `tick` from the rounds
+/// opened after it.
+#[test]
+fn tick_reaches_rounds_behind_a_long_dated_one() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    kp.start_round(&admin, &100u32, &(cur + 5_000), &(cur + 6_000));
+    let short = [0; 10].map(|_| kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10)));
+
+    env.ledger().set_sequence_number(cur + 6);
+    assert_eq!(kp.tick(), 7);
+    assert_eq!(kp.tick(), 3);
+    for round_id in short {
+        assert_eq!(kp.get_status(&round_id), RoundState::Locked);
+    }
+    assert_eq!(kp.tick(), 0);
+}
+
+/// Bucket rounds pay the bucket the count fell in, pro rata; an unbacked
+/// winning bucket returns every stake.
+#[test]
+fn bucket_round_pays_winning_bucket() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let edges = vec![&env, 100u32, 200, 300];
+    let round_id = kp.start_bucket_round(&admin, &edges, &(cur + 5), &(cur + 10));
+    let pushed = kp.start_bucket_round(&admin, &edges, &(cur + 5), &(cur + 10));
+
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    for (who, amount) in [(&alice, 100), (&bob, 300), (&carol, 600)] {
+        mint.mint(who, &(amount * 2));
+    }
+    kp.bet(&alice, &round_id, &Side::Bucket(1), &100);
+    kp.bet(&bob, &round_id, &Side::Bucket(1), &300);
+    kp.bet(&carol, &round_id, &Side::Bucket(3), &600);
+    kp.bet(&alice, &pushed, &Side::Bucket(0), &100);
+    kp.bet(&carol, &pushed, &Side::Bucket(3), &600);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet(&bob, &round_id, &Side::Bucket(4), &100)
+    }))
+    .is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    kp.resolve_round(&admin, &pushed, &250u32);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Bucket(1));
+    assert_eq!(kp.get_round(&pushed).outcome, Outcome::Push);
+
+    open_claims(&env);
+    for who in [&alice, &bob, &carol] {
+        kp.claim(who, &round_id);
+    }
+    kp.claim(&alice, &pushed);
+    kp.claim(&carol, &pushed);
+    assert_eq!(tok.balance(&alice), 350);
+    assert_eq!(tok.balance(&bob), 1_050);
+    assert_eq!(tok.balance(&carol), 600);
+}
+
+/// Scalar rounds share the pot by closeness: an exact guess takes most of
+/// it, a distant one a sliver.
+#[test]
+fn scalar_round_pays_by_distance() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_scalar_round(&admin, &(cur + 5), &(cur + 10));
+
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    for (who, guess) in [(&alice, 150), (&bob, 160), (&carol, 300)] {
+        mint.mint(who, &100);
+        kp.bet(who, &round_id, &Side::Guess(guess), &100);
+    }
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet(&alice, &round_id, &Side::Guess(151), &1)
+    }))
+    .is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Scored);
+
+    open_claims(&env);
+    for who in [&alice, &bob, &carol] {
+        kp.claim(who, &round_id);
+    }
+    assert_eq!(tok.balance(&alice), 273);
+    assert_eq!(tok.balance(&bob), 24);
+    assert_eq!(tok.balance(&carol), 1);
+}
+
+/// An unwon jackpot rolls into the next count round, and the next exact
+/// hit takes all of it.
+#[test]
+fn jackpot_rolls_over_until_hit() {
+    let (env, mint, tok, kp, admin) = setup();
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    for who in [&alice, &bob, &carol] {
+        mint.mint(who, &100);
+    }
+
+    let cur = env.ledger().sequence();
+    let missed = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.bet_jackpot(&alice, &missed, &150u32, &40);
+    kp.bet_jackpot(&bob, &missed, &120u32, &60);
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &missed, &151u32);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.roll_jackpot(&missed))).is_err());
+    open_claims(&env);
+    kp.claim_jackpot(&alice, &missed);
+    kp.roll_jackpot(&missed);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.roll_jackpot(&missed))).is_err());
+
+    let cur = env.ledger().sequence();
+    let hit = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(kp.get_jackpot(&hit).carried, 100);
+    kp.bet_jackpot(&carol, &hit, &42u32, &25);
+    kp.bet_jackpot(&alice, &hit, &42u32, &25);
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &hit, &42u32);
+    open_claims(&env);
+    kp.claim_jackpot(&carol, &hit);
+    kp.claim_jackpot(&alice, &hit);
+
+    assert_eq!(tok.balance(&alice), 110);
+    assert_eq!(tok.balance(&bob), 40);
+    assert_eq!(tok.balance(&carol), 150);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// With a spread, only results clear of the band around the line win;
+/// inside it every stake comes back.
+#[test]
+fn spread_band_pushes() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let mut rules = kp.get_rules(&kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10)));
+    rules.spread = 20;
+    let inside = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    let clear = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &200);
+    for round_id in [inside, clear] {
+        kp.bet(&alice, &round_id, &Side::Higher, &100);
+        kp.bet(&bob, &round_id, &Side::Lower, &100);
+    }
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &inside, &120u32);
+    kp.resolve_round(&admin, &clear, &121u32);
+    assert_eq!(kp.get_round(&inside).outcome, Outcome::Push);
+    assert_eq!(kp.get_round(&clear).outcome, Outcome::Higher);
+
+    open_claims(&env);
+    for round_id in [inside, clear] {
+        kp.claim(&alice, &round_id);
+        kp.claim(&bob, &round_id);
+    }
+    assert_eq!(tok.balance(&alice), 300);
+    assert_eq!(tok.balance(&bob), 100);
+}
+
+/// Fixed‑odds bets are paid their locked odds from the vault, which keeps
+/// the losers' stakes; exposure caps both bets and withdrawals.
+#[test]
+fn fixed_odds_round_pays_from_vault() {
+    let (env, mint, tok, kp, admin) = setup();
+    let lp = Address::generate(&env);
+    mint.mint(&lp, &1_000);
+    assert_eq!(kp.deposit_vault(&lp, &1_000), 1_000);
+
+    let cur = env.ledger().sequence();
+    let odds = FixedOdds {
+        higher_bps: 20_000,
+        lower_bps: 15_000,
+    };
+    let round_id = kp.start_fixed_round(&admin, &100u32, &(cur + 5), &(cur + 10), &odds);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &2_000);
+    mint.mint(&bob, &400);
+    kp.bet_fixed(&alice, &round_id, &Side::Higher, &500);
+    kp.set_odds(
+        &admin,
+        &round_id,
+        &FixedOdds {
+            higher_bps: 30_000,
+            lower_bps: 15_000,
+        },
+    );
+    kp.bet_fixed(&alice, &round_id, &Side::Higher, &200);
+    kp.bet_fixed(&bob, &round_id, &Side::Lower, &400);
+    // owed 1 600 if Higher against 1 100 staked
+    assert_eq!(kp.get_vault().exposure, 500);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet_fixed(&alice, &round_id, &Side::Higher, &600)
+    }))
+    .is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.withdraw_vault(&lp, &600))).is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet(&bob, &round_id, &Side::Higher, &100)
+    }))
+    .is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim_fixed(&alice, &round_id);
+    kp.claim_fixed(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 2_000 - 700 + 1_600);
+    assert_eq!(tok.balance(&bob), 0);
+
+    let vault = kp.get_vault();
+    assert_eq!((vault.assets, vault.exposure), (500, 0));
+    assert_eq!(kp.withdraw_vault(&lp, &1_000), 500);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// Deposits that would mint no shares, or feed a wiped‑out vault's
+/// worthless shares, are refused.
+#[test]
+fn vault_refuses_deposits_it_cannot_price() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let lp = Address::generate(&env);
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&lp, &101);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &200);
+    kp.deposit_vault(&lp, &100);
+    let odds = FixedOdds {
+        higher_bps: 20_000,
+        lower_bps: 20_000,
+    };
+
+    let cur = env.ledger().sequence();
+    let won = kp.start_fixed_round(&admin, &100u32, &(cur + 5), &(cur + 10), &odds);
+    kp.bet_fixed(&alice, &won, &Side::Higher, &100);
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &won, &50u32);
+    open_claims(&env);
+    kp.close_book(&won);
+    // 200 of assets behind 100 shares: a deposit of 1 is worth half a share
+    assert!(kp.try_deposit_vault(&lp, &1).is_err());
+
+    let cur = env.ledger().sequence();
+    let lost = kp.start_fixed_round(&admin, &100u32, &(cur + 5), &(cur + 10), &odds);
+    kp.bet_fixed(&bob, &lost, &Side::Higher, &200);
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &lost, &150u32);
+    open_claims(&env);
+    kp.close_book(&lost);
+    assert_eq!(kp.get_vault().assets, 0);
+    assert!(kp.try_deposit_vault(&lp, &1).is_err());
+    assert_eq!(kp.get_vault_shares(&lp), 100);
+}
+
+/// Fixed‑point `exp` and `ln` agree with known values to ~1e‑15.
+#[test]
+fn lmsr_math_is_accurate() {
+    let close = |got: i128, want: i128| (got - want).abs() <= 1_000;
+    assert!(close(exp_neg(0), SCALE));
+    assert!(close(exp_neg(SCALE), 367_879_441_171_442_321));
+    assert!(close(exp_neg(10 * SCALE), 45_399_929_762_484));
+    assert_eq!(exp_neg(50 * SCALE), 0);
+    assert!(close(ln_1p(0), 0));
+    assert!(close(ln_1p(SCALE), 693_147_180_559_945_309));
+    assert!(close(ln_1p(SCALE / 2), 405_465_108_108_164_381));
+    // C(0, 0) = b · ln 2, and C grows like max(q) far from balance
+    let env = Env::default();
+    assert!(close(cost(&env, 1, 0, 0), 693_147_180_559_945_309));
+    assert_eq!(cost(&env, 1, 100, 0), 100 * SCALE);
+}
+
+/// LMSR rounds quote moving prices, pay a unit per winning share and hand
+/// the maker's remainder back to the admin.
+#[test]
+fn lmsr_round_trades_and_settles() {
+    let (env, mint, tok, kp, admin) = setup();
+    mint.mint(&admin, &69_315);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_lmsr_round(&admin, &100u32, &(cur + 5), &(cur + 10), &100_000);
+    assert_eq!(kp.get_amm(&round_id).subsidy, 69_315);
+    assert_eq!(kp.lmsr_price(&round_id), 5_000);
+
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100_000);
+    mint.mint(&bob, &100_000);
+    assert_eq!(kp.quote_shares(&round_id, &Side::Higher, &50_000), 28_093);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.buy_shares(&alice, &round_id, &Side::Higher, &50_000, &28_000)
+    }))
+    .is_err());
+    kp.buy_shares(&alice, &round_id, &Side::Higher, &50_000, &28_093);
+    assert_eq!(kp.lmsr_price(&round_id), 6_224);
+    assert_eq!(
+        kp.buy_shares(&bob, &round_id, &Side::Lower, &20_000, &10_000),
+        8_028
+    );
+    assert_eq!(kp.lmsr_price(&round_id), 5_744);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet(&bob, &round_id, &Side::Higher, &100)
+    }))
+    .is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.redeem_shares(&alice, &round_id);
+    kp.redeem_shares(&bob, &round_id);
+    assert_eq!(
+        kp.close_amm(&admin, &round_id),
+        69_315 + 28_093 + 8_028 - 50_000
+    );
+    assert_eq!(tok.balance(&alice), 100_000 - 28_093 + 50_000);
+    assert_eq!(tok.balance(&bob), 100_000 - 8_028);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// Split sets trade as separate shares; winners redeem 1:1, unpaired
+/// leftovers merge back into collateral.
+#[test]
+fn complete_sets_split_trade_and_redeem() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &1_000);
+
+    kp.split_set(&alice, &round_id, &1_000);
+    kp.transfer_shares(&alice, &bob, &round_id, &Side::Higher, &600);
+    assert_eq!(kp.share_balance(&alice, &round_id, &Side::Higher), 400);
+    assert_eq!(kp.share_balance(&bob, &round_id, &Side::Higher), 600);
+    kp.merge_set(&alice, &round_id, &400);
+    assert_eq!(tok.balance(&alice), 400);
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.redeem_set(&bob, &round_id))).is_err());
+    open_claims(&env);
+    assert_eq!(kp.redeem_set(&bob, &round_id), 600);
+    assert_eq!(kp.redeem_set(&alice, &round_id), 0);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+/// A stake moved before resolution is claimed by its new holder.
+#[test]
+fn transferred_stake_pays_new_holder() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&alice, &300);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &300);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+
+    env.ledger().set_sequence_number(cur + 6);
+    kp.transfer_stake(&alice, &carol, &round_id, &100);
+    // landing on the other side of bob's line, it hedges his stake
+    kp.transfer_stake(&carol, &bob, &round_id, &50);
+    assert_eq!(kp.get_stake(&bob, &round_id).unwrap().hedge, 50);
+    kp.transfer_stake(&alice, &carol, &round_id, &200);
+    assert!(kp.get_stake(&alice, &round_id).is_none());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&carol, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&carol), 500);
+    assert_eq!(tok.balance(&bob), 100);
+    assert_eq!(kp.get_status(&round_id), RoundState::Archived);
+}
+
+/// A stake cannot be moved to its own holder, nor to someone barred from
+/// the round.
+#[test]
+fn stake_transfers_refuse_self_and_barred_holders() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&alice, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &300);
+
+    assert!(kp
+        .try_transfer_stake(&alice, &alice, &round_id, &300)
+        .is_err());
+    assert!(kp
+        .try_transfer_stake(&alice, &alice, &round_id, &100)
+        .is_err());
+    assert_eq!(kp.get_stake(&alice, &round_id).unwrap().amount, 300);
+    assert_eq!(kp.get_round(&round_id).high_pool, 300);
+
+    kp.block_account(&admin, &bob, &false);
+    assert!(kp
+        .try_transfer_stake(&alice, &bob, &round_id, &100)
+        .is_err());
+    kp.set_round_allowlist(
+        &admin,
+        &round_id,
+        &Some(RoundAllowlist::Accounts(vec![&env, alice.clone()])),
+    );
+    assert!(kp
+        .try_transfer_stake(&alice, &carol, &round_id, &100)
+        .is_err());
+}
+
+#[test]
+fn epoch_rounds_chain_on_resolution() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let first = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.configure_epochs(
+        &admin,
+        &Some(EpochSchedule {
+            round_id: first,
+            interval_ledgers: 20,
+            line: LineSource::LastResult,
+        }),
+    );
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &first, &140u32);
+    let second = kp.get_epochs().unwrap().round_id;
+    assert_ne!(second, first);
+    let round = kp.get_round(&second);
+    assert_eq!(round.state, RoundState::Open);
+    assert_eq!(round.predicted_count, 140);
+    assert_eq!(round.deadline, Deadline::Ledger(cur + 25));
+    assert_eq!(round.finality, Deadline::Ledger(cur + 30));
+
+    // a cancelled round keeps its line and the chain goes on
+    kp.cancel_round(&admin, &second);
+    let third = kp.get_epochs().unwrap().round_id;
+    assert_eq!(kp.get_round(&third).predicted_count, 140);
+
+    kp.configure_epochs(&admin, &None);
+    env.ledger().set_sequence_number(cur + 50);
+    kp.resolve_round(&admin, &third, &90u32);
+    assert!(kp.get_epochs().is_none());
+}
+
+#[test]
+fn season_pays_top_players() {
+    let (env, mint, tok, kp, admin) = setup_long_lived();
+    let cur = env.ledger().sequence();
+    let season_id = kp.start_season(&admin, &2u32, &(cur + 100));
+    let sponsor = Address::generate(&env);
+    mint.mint(&sponsor, &600);
+    kp.fund_season(&sponsor, &season_id, &600);
+
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    for (player, side, amount) in [
+        (&alice, Side::Higher, 300),
+        (&bob, Side::Lower, 100),
+        (&carol, Side::Higher, 100),
+    ] {
+        mint.mint(player, &amount);
+        kp.bet(player, &round_id, &side, &amount);
+    }
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    for player in [&alice, &bob, &carol] {
+        kp.claim(player, &round_id);
+    }
+    let score = kp.get_season_score(&season_id, &alice);
+    assert_eq!((score.played, score.correct, score.net), (1, 1, 75));
+    assert_eq!(kp.get_season_score(&season_id, &bob).net, -100);
+
+    // rounds opened after the season ended don't count
+    env.ledger().set_sequence_number(cur + 100);
+    let late = kp.start_round(&admin, &100u32, &(cur + 105), &(cur + 110));
+    mint.mint(&bob, &50);
+    kp.bet(&bob, &late, &Side::Lower, &50);
+    assert_eq!(kp.get_season_score(&season_id, &bob).net, -100);
+
+    for player in [&carol, &bob, &alice] {
+        kp.enter_season(&season_id, player);
+    }
+    assert_eq!(
+        kp.get_season(&season_id).board,
+        vec![&env, alice.clone(), carol.clone()]
+    );
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.settle_season(&season_id))).is_err());
+
+    env.ledger()
+        .set_sequence_number(cur + 100 + SEASON_ENTRY_LEDGERS);
+    kp.settle_season(&season_id);
+    assert_eq!(tok.balance(&alice), 375 + 400);
+    assert_eq!(tok.balance(&carol), 125 + 200);
+    assert!(kp.get_season(&season_id).settled);
+}
+
+#[test]
+fn bonded_round_returns_or_forfeits_bond() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let creator = Address::generate(&env);
+    mint.mint(&creator, &140);
+    // admin only until terms are published
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.start_round_bonded(&creator, &100u32, &(cur + 5), &(cur + 10))
+    }))
+    .is_err());
+    kp.set_creator_terms(
+        &admin,
+        &Some(CreatorTerms {
+            bond: 50,
+            listing_fee: 20,
+            fee_curve: FeeCurve {
+                min_bps: 1_000,
+                max_bps: 1_000,
+            },
+            creator_share_bps: 5_000,
+        }),
+    );
+
+    let round_id = kp.start_round_bonded(&creator, &100u32, &(cur + 5), &(cur + 10));
+    let doomed = kp.start_round_bonded(&creator, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(tok.balance(&creator), 0);
+    assert_eq!(tok.balance(&admin), 40);
+
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &300);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &300);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+
+    kp.cancel_round(&admin, &doomed);
+    assert_eq!(tok.balance(&admin), 90);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.reclaim_bond(&doomed))).is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.reclaim_bond(&round_id))).is_err());
+    open_claims(&env);
+    // 10% of the losing pool, half of it to the creator
+    kp.reclaim_bond(&round_id);
+    kp.collect_fee(&admin, &round_id);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&creator), 50 + 5);
+    assert_eq!(tok.balance(&admin), 90 + 5);
+    assert_eq!(tok.balance(&alice), 390);
+    assert!(kp.get_creator_bond(&round_id).unwrap().settled);
+}
+
+#[test]
+fn duel_winner_takes_the_pot() {
+    let (env, mint, tok, kp, _admin) = setup();
+    let cur = env.ledger().sequence();
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &100);
+    mint.mint(&carol, &100);
+    let terms = DuelTerms {
+        opponent: bob.clone(),
+        side: Side::Higher,
+        stake: 100,
+        predicted_count: 100,
+        deadline_ledger: cur + 5,
+        finality_ledger: cur + 10,
+    };
+    let round_id = kp.open_duel(&alice, &terms);
+    let withdrawn = kp.open_duel(&alice, &terms);
+    assert_eq!(tok.balance(&alice), 0);
+
+    // only the opponent may join
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet(&carol, &round_id, &Side::Lower, &100)
+    }))
+    .is_err());
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.accept_duel(&carol, &round_id))).is_err());
+    kp.accept_duel(&bob, &round_id);
+    assert_eq!(kp.get_stake(&bob, &round_id).unwrap().side, Side::Lower);
+    assert!(kp.get_duel(&round_id).accepted);
+
+    kp.cancel_duel(&alice, &withdrawn);
+    kp.claim(&alice, &withdrawn);
+    assert_eq!(tok.balance(&alice), 100);
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&kp.get_admin(), &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 300);
+    assert_eq!(tok.balance(&bob), 0);
+}
+
+/// Duel stakes meet the minimum bet and the risk checks on both sides.
+#[test]
+fn duels_refuse_blocked_players_and_dust() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &300);
+    mint.mint(&bob, &100);
+    kp.set_min_bet(&admin, &50);
+    let mut terms = DuelTerms {
+        opponent: bob.clone(),
+        side: Side::Higher,
+        stake: 40,
+        predicted_count: 100,
+        deadline_ledger: cur + 5,
+        finality_ledger: cur + 10,
+    };
+    assert_eq!(kp.try_open_duel(&alice, &terms), Err(Ok(Error::ZeroAmount)));
+    terms.stake = 100;
+    let round_id = kp.open_duel(&alice, &terms);
+
+    kp.block_account(&admin, &bob, &false);
+    assert_eq!(
+        kp.try_accept_duel(&bob, &round_id),
+        Err(Ok(Error::RiskRejected))
+    );
+    kp.unblock_account(&admin, &bob);
+    kp.block_account(&admin, &alice, &false);
+    assert_eq!(
+        kp.try_open_duel(&alice, &terms),
+        Err(Ok(Error::RiskRejected))
+    );
+    kp.accept_duel(&bob, &round_id);
+}
+
+#[test]
+fn rounds_resolve_on_their_metric() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let oracle = setup_oracle(&env, &kp, &admin, 1_000);
+    let stats = MockOracleClient::new(&env, &oracle);
+    stats.set_stats(&500, &2_000);
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let harvest = Rules {
+        metric: Metric::HarvestVolume,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &finality))
+    };
+    let staked = Rules {
+        metric: Metric::TotalStaked,
+        ..harvest.clone()
+    };
+    let by_harvest = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &finality, &harvest);
+    let by_stake = kp.start_round_with_rules(&admin, &1_800u32, &(cur + 5), &finality, &staked);
+    // harvest volume counts from the round's start, the stake is a level
+    assert_eq!(kp.get_round(&by_harvest).baseline_count, 500);
+    assert_eq!(kp.get_round(&by_stake).baseline_count, 0);
+
+    let player = Address::generate(&env);
+    mint.mint(&player, &20);
+    for round_id in [by_harvest, by_stake] {
+        kp.bet(&player, &round_id, &Side::Higher, &10);
+    }
+    // both sides backed, so the result is not pushed back as unbacked
+    let other = Address::generate(&env);
+    mint.mint(&other, &10);
+    kp.bet(&other, &by_stake, &Side::Lower, &10);
+    stats.set_stats(&650, &1_700);
+    env.ledger()
+        .set_sequence_number(finality + PUBLIC_RESOLVE_LEDGERS + 1);
+    kp.public_resolve(&player, &by_harvest);
+    kp.public_resolve(&player, &by_stake);
+    assert_eq!(kp.get_round(&by_harvest).actual_count, 150);
+    assert_eq!(kp.get_round(&by_harvest).outcome, Outcome::Higher);
+    assert_eq!(kp.get_round(&by_stake).actual_count, 1_700);
+    assert_eq!(kp.get_round(&by_stake).outcome, Outcome::Lower);
+}
+
+#[test]
+fn failed_precondition_voids_round() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let [parent, child, small] =
+        [0; 3].map(|_| kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10)));
+    kp.set_precondition(
+        &admin,
+        &child,
+        &Some(Precondition::Outcome(parent, Outcome::Higher)),
+    );
+    kp.set_precondition(&admin, &small, &Some(Precondition::MinPot(1_000)));
+
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &200);
+    for round_id in [child, small] {
+        kp.bet(&alice, &round_id, &Side::Higher, &100);
+        kp.bet(&bob, &round_id, &Side::Lower, &100);
+    }
+
+    env.ledger().set_sequence_number(cur + 10);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.resolve_round(&admin, &child, &150u32)
+    }))
+    .is_err());
+    kp.resolve_round(&admin, &parent, &50u32);
+    kp.resolve_round(&admin, &child, &150u32);
+    kp.resolve_round(&admin, &small, &150u32);
+    for round_id in [child, small] {
+        assert_eq!(kp.get_round(&round_id).outcome, Outcome::Voided);
+        kp.claim(&alice, &round_id);
+        kp.claim(&bob, &round_id);
+    }
+    assert_eq!(tok.balance(&alice), 200);
+    assert_eq!(tok.balance(&bob), 200);
+}
+
+#[test]
+fn ladder_round_settles_each_rung() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_ladder_round(&admin, &vec![&env, 100, 200], &(cur + 5), &(cur + 10));
+    let [alice, bob, carol, dave] = [0; 4].map(|_| Address::generate(&env));
+    for (player, side, amount) in [
+        (&alice, Side::Over(0), 100),
+        (&bob, Side::Under(0), 50),
+        (&carol, Side::Over(1), 40),
+        (&dave, Side::Under(1), 60),
+    ] {
+        mint.mint(player, &amount);
+        kp.bet(player, &round_id, &side, &amount);
+    }
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.bet(&alice, &round_id, &Side::Over(2), &1)
+    }))
+    .is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Laddered);
+    open_claims(&env);
+    for player in [&alice, &bob, &carol, &dave] {
+        kp.claim(player, &round_id);
+    }
+    // 150 is over the first line and under the second
+    assert_eq!(tok.balance(&alice), 150);
+    assert_eq!(tok.balance(&bob), 0);
+    assert_eq!(tok.balance(&carol), 0);
+    assert_eq!(tok.balance(&dave), 100);
+}
+
+#[test]
+fn series_pays_on_k_of_n() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rounds = [0; 3].map(|_| kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10)));
+    let series_id = kp.start_series(
+        &admin,
+        &vec![&env, rounds[0], rounds[1], rounds[2]],
+        &Side::Higher,
+        &2u32,
+    );
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &50);
+    kp.bet_series(&alice, &series_id, &true, &100);
+    kp.bet_series(&bob, &series_id, &false, &50);
+
+    env.ledger().set_sequence_number(cur + 10);
+    for (round_id, count) in rounds.into_iter().zip([150u32, 50, 150]) {
+        kp.resolve_round(&admin, &round_id, &count);
+    }
+    // not until every round pays out
+    assert!(catch_unwind(AssertUnwindSafe(|| kp.claim_series(&alice, &series_id))).is_err());
+    open_claims(&env);
+    kp.claim_series(&alice, &series_id);
+    kp.claim_series(&bob, &series_id);
+    assert_eq!(tok.balance(&alice), 150);
+    assert_eq!(tok.balance(&bob), 0);
+    assert!(kp.get_series_bet(&series_id, &alice).is_none());
+}
+
+#[test]
+fn template_starts_preset_rounds() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let daily = symbol_short!("daily");
+    let rules = Rules {
+        grace_ledgers: 500,
+        metric: Metric::HarvestVolume,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10)))
+    };
+    kp.set_template(
+        &admin,
+        &daily,
+        &Some(Template {
+            betting_ledgers: 100,
+            finality_ledgers: 20,
+            rules: rules.clone(),
+        }),
+    );
+
+    let round_id = kp.start_from_template(&admin, &daily, &250u32);
+    let round = kp.get_round(&round_id);
+    assert_eq!(round.predicted_count, 250);
+    assert_eq!(round.deadline, Deadline::Ledger(cur + 100));
+    assert_eq!(round.finality, Deadline::Ledger(cur + 120));
+    assert_eq!(round.rules, rules);
+
+    kp.set_template(&admin, &daily, &None);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.start_from_template(&admin, &daily, &250u32)
+    }))
+    .is_err());
+}
+
+#[test]
+fn open_round_cap_is_enforced() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    kp.set_max_open_rounds(&admin, &Some(1));
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(kp.get_open_round_count(), 1);
+    assert!(catch_unwind(AssertUnwindSafe(|| {
+        kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10))
+    }))
+    .is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert_eq!(kp.get_open_round_count(), 0);
+    kp.start_round(&admin, &100u32, &(cur + 15), &(cur + 20));
+    kp.set_max_open_rounds(&admin, &None);
+    kp.start_round(&admin, &100u32, &(cur + 15), &(cur + 20));
+    assert_eq!(kp.get_open_round_count(), 2);
+}
+
+#[test]
+fn bet_pulls_from_allowance() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let wallet = Address::generate(&env);
+    mint.mint(&wallet, &200);
+    tok.approve(&wallet, &kp.address, &100, &(cur + 100));
+
+    kp.bet(&wallet, &round_id, &Side::Higher, &60);
+    assert_eq!(tok.allowance(&wallet, &kp.address), 40);
+    // beyond the allowance the stake is transferred directly
+    kp.bet(&wallet, &round_id, &Side::Higher, &50);
+    assert_eq!(tok.allowance(&wallet, &kp.address), 40);
+    assert_eq!(tok.balance(&wallet), 90);
+    assert_eq!(kp.get_stake(&wallet, &round_id).unwrap().amount, 110);
+}
+
+#[test]
+fn hedged_stake_pays_each_side() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let [won, voided] = [0; 2].map(|_| kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10)));
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &300);
+    mint.mint(&bob, &150);
+    for round_id in [won, voided] {
+        kp.bet(&alice, &round_id, &Side::Higher, &100);
+        kp.bet(&alice, &round_id, &Side::Lower, &50);
+    }
+    kp.bet(&bob, &won, &Side::Lower, &150);
+    let stake = kp.get_stake(&alice, &won).unwrap();
+    assert_eq!(
+        (stake.side, stake.amount, stake.hedge),
+        (Side::Higher, 100, 50)
+    );
+    assert_eq!(kp.get_round(&won).low_pool, 200);
+
+    kp.cancel_round(&admin, &voided);
+    kp.claim(&alice, &voided);
+    assert_eq!(tok.balance(&alice), 150);
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &won, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &won);
+    kp.claim(&bob, &won);
+    // the Higher 100 takes the whole 300 pot; the hedge is lost
+    assert_eq!(tok.balance(&alice), 150 + 300);
+    assert_eq!(tok.balance(&bob), 0);
+}
+
+#[test]
+fn reduced_bet_returns_stake_less_fee() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &200);
+    kp.bet(&alice, &round_id, &Side::Lower, &100);
+    kp.set_cancel_fee_bps(&admin, &100);
+
+    assert_eq!(kp.reduce_bet(&alice, &round_id, &100), 99);
+    assert_eq!(tok.balance(&alice), 99);
+    assert_eq!(tok.balance(&admin), 1);
+    let round = kp.get_round(&round_id);
+    assert_eq!((round.high_pool, round.low_pool), (100, 100));
+
+    // more than the main side holds
+    assert!(kp.try_reduce_bet(&alice, &round_id, &101).is_err());
+
+    // taking the rest leaves the hedge as the stake
+    kp.reduce_bet(&alice, &round_id, &100);
+    let stake = kp.get_stake(&alice, &round_id).unwrap();
+    assert_eq!(
+        (stake.side, stake.amount, stake.hedge),
+        (Side::Lower, 100, 0)
+    );
+    assert_eq!(kp.get_round(&round_id).high_pool, 0);
+
+    env.ledger().set_sequence_number(cur + 6);
+    assert!(kp.try_reduce_bet(&alice, &round_id, &50).is_err());
+}
+
+/// Once every stake is forfeited there is nothing left to share a refund
+/// by; it comes to nothing rather than failing.
+#[test]
+fn refunds_from_a_fully_forfeited_pot_are_zero() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let mut round = kp.get_round(&round_id);
+    round.forfeited = 100;
+    let stake = Stake {
+        amount: 0,
+        side: Side::Higher,
+        hedge: 0,
+    };
+    for outcome in [Outcome::Push, Outcome::Voided, Outcome::Expired] {
+        round.outcome = outcome;
+        assert_eq!(gross_payout(&env, &round, &stake), 0);
+    }
+}
+
+#[test]
+fn cash_out_pays_from_the_pools() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    for (player, side, amount) in [
+        (&alice, Side::Higher, 100),
+        (&bob, Side::Higher, 100),
+        (&carol, Side::Lower, 200),
+    ] {
+        mint.mint(player, &amount);
+        kp.bet(player, &round_id, &side, &amount);
+    }
+
+    // wins 400 * 100 / 200 at the 100 / 300 the rest of the book gives
+    // Higher, less the 2 % default haircut
+    env.ledger().set_sequence_number(cur + 6);
+    assert_eq!(kp.get_cash_out_quote(&alice, &round_id), 65);
+    assert_eq!(kp.cash_out(&alice, &round_id), 65);
+    assert_eq!(tok.balance(&alice), 65);
+    assert!(kp.get_stake(&alice, &round_id).is_none());
+    let round = kp.get_round(&round_id);
+    assert_eq!(
+        (round.high_pool, round.low_pool, round.forfeited),
+        (100, 200, 35)
+    );
+
+    env.ledger().set_sequence_number(cur + 10);
+    assert!(kp.try_cash_out(&bob, &round_id).is_err());
+    kp.resolve_round(&admin, &round_id, &50u32);
+    open_claims(&env);
+    kp.claim(&carol, &round_id);
+    assert_eq!(tok.balance(&carol), 335);
+}
+
+#[test]
+fn bet_caps_limit_stake_and_pot() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    for player in [&alice, &bob, &carol] {
+        mint.mint(player, &500);
+    }
+    kp.set_bet_caps(
+        &admin,
+        &Some(BetCaps {
+            max_stake: 200,
+            max_pool: 350,
+            max_exposure: 0,
+        }),
+    );
+
+    kp.bet(&alice, &round_id, &Side::Higher, &150);
+    kp.bet(&alice, &round_id, &Side::Lower, &50); // hedges count too
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &1).is_err());
+    kp.bet(&bob, &round_id, &Side::Lower, &150);
+    assert!(kp.try_bet(&carol, &round_id, &Side::Lower, &1).is_err());
+
+    kp.set_bet_caps(&admin, &None);
+    kp.bet(&carol, &round_id, &Side::Lower, &500);
+}
+
+/// Fixed‑odds bets count against the same caps, their book standing in
+/// for the pot.
+#[test]
+fn bet_caps_limit_fixed_odds_bets() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let [lp, alice, bob] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&lp, &1_000);
+    mint.mint(&alice, &500);
+    mint.mint(&bob, &500);
+    kp.deposit_vault(&lp, &1_000);
+    let cur = env.ledger().sequence();
+    let odds = FixedOdds {
+        higher_bps: 20_000,
+        lower_bps: 20_000,
+    };
+    let round_id = kp.start_fixed_round(&admin, &100u32, &(cur + 5), &(cur + 10), &odds);
+    kp.set_bet_caps(
+        &admin,
+        &Some(BetCaps {
+            max_stake: 200,
+            max_pool: 350,
+            max_exposure: 0,
+        }),
+    );
+
+    kp.bet_fixed(&alice, &round_id, &Side::Higher, &200);
+    assert_eq!(
+        kp.try_bet_fixed(&alice, &round_id, &Side::Higher, &1),
+        Err(Ok(Error::BetCapExceeded))
+    );
+    kp.bet_fixed(&bob, &round_id, &Side::Lower, &150);
+    assert_eq!(
+        kp.try_bet_fixed(&bob, &round_id, &Side::Lower, &1),
+        Err(Ok(Error::BetCapExceeded))
+    );
+}
+
+#[test]
+fn bets_below_min_bet_are_refused() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &500);
+    assert!(kp.try_set_min_bet(&admin, &0).is_err());
+    kp.set_min_bet(&admin, &100);
+
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &99).is_err());
+    assert!(kp.try_bet_jackpot(&alice, &round_id, &100u32, &99).is_err());
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    assert_eq!(tok.balance(&alice), 400);
+}
+
+#[test]
+fn referrer_earns_share_of_fee() {
+    let (env, mint, tok, kp, admin) = setup();
+    let rules = Rules {
+        grace_ledgers: GRACE_LEDGERS,
+        public_resolve_ledgers: PUBLIC_RESOLVE_LEDGERS,
+        amend_window_ledgers: AMEND_WINDOW_LEDGERS,
+        claim_window_ledgers: 0,
+        keeper_fee_bps: 0,
+        tie_policy: TiePolicy::Push,
+        spread: 0,
+        fee_curve: FeeCurve {
+            min_bps: 100,
+            max_bps: 500,
+        },
+        metric: Metric::Invocations,
+    };
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    let [fav, dog, referrer] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&fav, &30_000);
+    mint.mint(&dog, &10_000);
+    kp.set_referral_bps(&admin, &5_000);
+    assert!(kp
+        .try_bet_with_referrer(&fav, &round_id, &Side::Higher, &30_000, &fav)
+        .is_err());
+    kp.bet_with_referrer(&fav, &round_id, &Side::Higher, &30_000, &referrer);
+    kp.bet(&dog, &round_id, &Side::Lower, &10_000);
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&fav, &round_id);
+    // half of the 400 fee, on three quarters of the pot
+    kp.collect_fee(&admin, &round_id);
+    assert_eq!(tok.balance(&admin), 250);
+    assert_eq!(kp.get_referral_rewards(&referrer), 150);
+    assert_eq!(kp.claim_referral_rewards(&referrer), 150);
+    assert_eq!(tok.balance(&referrer), 150);
+    assert!(kp.try_claim_referral_rewards(&referrer).is_err());
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+#[test]
+fn sponsored_bet_pays_beneficiary() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [sponsor, alice, bob] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&sponsor, &100);
+    mint.mint(&bob, &100);
+    kp.bet_for(&sponsor, &alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    assert_eq!(tok.balance(&sponsor), 0);
+    assert!(kp.get_stake(&sponsor, &round_id).is_none());
+    assert_eq!(kp.get_stake(&alice, &round_id).unwrap().amount, 100);
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    assert!(kp.try_claim(&sponsor, &round_id).is_err());
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 200);
+}
+
+#[test]
+fn bet_many_places_every_leg_or_none() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let [first, second] = [0; 2].map(|_| kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10)));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &300);
+
+    kp.bet_many(
+        &alice,
+        &vec![
+            &env,
+            (first, Side::Higher, 100i128),
+            (second, Side::Lower, 50i128),
+        ],
+    );
+    assert_eq!(kp.get_stake(&alice, &first).unwrap().amount, 100);
+    assert_eq!(kp.get_stake(&alice, &second).unwrap().side, Side::Lower);
+    assert_eq!(tok.balance(&alice), 150);
+
+    // the second leg fails, so the first is undone
+    assert!(kp
+        .try_bet_many(
+            &alice,
+            &vec![
+                &env,
+                (first, Side::Higher, 100i128),
+                (99, Side::Higher, 50i128),
+            ],
+        )
+        .is_err());
+    assert_eq!(kp.get_stake(&alice, &first).unwrap().amount, 100);
+    assert_eq!(tok.balance(&alice), 150);
+}
+
+#[test]
+fn bet_at_odds_refuses_worse_odds() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Lower, &100);
+
+    // 100 more on Higher would pay 200 / 100 = 2×
+    kp.bet_at_odds(&bob, &round_id, &Side::Higher, &100, &20_000);
+    // the next 100 only 300 / 200 = 1.5×
+    let slipped = kp.try_bet_at_odds(&bob, &round_id, &Side::Higher, &100, &16_000);
+    assert!(slipped.is_err());
+    kp.bet_at_odds(&bob, &round_id, &Side::Higher, &100, &15_000);
+    assert_eq!(tok.balance(&bob), 100);
+}
+
+#[test]
+fn imbalance_cap_stops_the_dominant_side() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    mint.mint(&alice, &1_000);
+    mint.mint(&bob, &1_000);
+    kp.set_max_imbalance(&admin, &round_id, &Some(30_000));
+
+    // nothing to compare against until both sides are backed
+    kp.bet(&alice, &round_id, &Side::Higher, &400);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &1).is_err());
+    kp.bet(&bob, &round_id, &Side::Lower, &50);
+    kp.bet(&alice, &round_id, &Side::Higher, &50);
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &1).is_err());
+
+    kp.set_max_imbalance(&admin, &round_id, &None);
+    kp.bet(&alice, &round_id, &Side::Higher, &500);
+}
+
+/// A full round staked in native XLM, at its 7 decimals.
+#[test]
+fn native_xlm_round_cycle() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let xlm = env
+        .deployer()
+        .with_stellar_asset(soroban_sdk::Bytes::from_array(&env, &[0; 4]))
+        .deploy();
+    let admin = Address::generate(&env);
+    let kp = KalePredictionClient::new(&env, &env.register(KalePrediction, (&admin, &xlm)));
+    assert_eq!(
+        kp.get_token_info(),
+        TokenInfo {
+            token: xlm.clone(),
+            decimals: 7,
+            native: true,
+        }
+    );
+
+    let tok = token::Client::new(&env, &xlm);
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    let one_xlm = 10_000_000;
+    fund_native(&env, &xlm, &alice, 30 * one_xlm);
+    fund_native(&env, &xlm, &bob, 10 * one_xlm);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.bet(&alice, &round_id, &Side::Higher, &(30 * one_xlm));
+    kp.bet(&bob, &round_id, &Side::Lower, &(10 * one_xlm));
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 40 * one_xlm);
+    assert_eq!(tok.balance(&kp.address), 0);
+
+    let (_env, _mint, _tok, kp, _admin) = setup();
+    assert!(!kp.get_token_info().native);
+}
+
+#[test]
+fn round_staked_in_allowed_token() {
+    let (env, _mint, tok, kp, admin) = setup();
+    let usdc = env
+        .register_stellar_asset_contract_v2(Address::generate(&env))
+        .address();
+    let usdc_mint = StellarAssetClient::new(&env, &usdc);
+    let usdc_tok = token::Client::new(&env, &usdc);
+    let cur = env.ledger().sequence();
+    let start = |kp: &KalePredictionClient| {
+        kp.try_start_round_in(&admin, &usdc, &100u32, &(cur + 5), &(cur + 10))
+    };
+    assert!(start(&kp).is_err());
+    kp.set_token_allowed(&admin, &usdc, &true);
+    assert_eq!(kp.get_allowed_tokens(), vec![&env, usdc.clone()]);
+    let round_id = start(&kp).unwrap().unwrap();
+
+    let [alice, bob] = [0; 2].map(|_| Address::generate(&env));
+    usdc_mint.mint(&alice, &300);
+    usdc_mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &300);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    assert!(kp.try_bet_jackpot(&bob, &round_id, &100u32, &1).is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(usdc_tok.balance(&alice), 400);
+    assert_eq!(usdc_tok.balance(&kp.address), 0);
+    assert_eq!(tok.balance(&kp.address), 0);
+
+    kp.set_token_allowed(&admin, &usdc, &false);
+    assert!(start(&kp).is_err());
+}
+
+#[test]
+fn sealed_bets_reveal_or_forfeit() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_sealed_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol, dave] = [0; 4].map(|_| Address::generate(&env));
+    let salt = BytesN::from_array(&env, &[7; 32]);
+    let seal = |player: &Address, round_id: u32, side: Side| -> BytesN<32> {
+        env.crypto()
+            .sha256(&(player.clone(), round_id, side, salt.clone()).to_xdr(&env))
+            .into()
+    };
+    for (player, side, amount) in [
+        (&alice, Side::Higher, 100),
+        (&bob, Side::Lower, 100),
+        (&carol, Side::Lower, 50),
+    ] {
+        mint.mint(player, &amount);
+        kp.commit_bet(player, &round_id, &seal(player, round_id, side), &amount);
+    }
+    // dave copies alice's commitment
+    mint.mint(&dave, &50);
+    kp.commit_bet(&dave, &round_id, &seal(&alice, round_id, Side::Higher), &50);
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &1).is_err());
+    let round = kp.get_round(&round_id);
+    assert_eq!(
+        (round.high_pool, round.low_pool, round.forfeited),
+        (0, 0, 300)
+    );
+    assert!(kp
+        .try_reveal_bet(&alice, &round_id, &Side::Higher, &salt)
+        .is_err());
+
+    env.ledger().set_sequence_number(cur + 6);
+    // the other side does not open alice's commitment
+    assert!(kp
+        .try_reveal_bet(&alice, &round_id, &Side::Lower, &salt)
+        .is_err());
+    kp.reveal_bet(&alice, &round_id, &Side::Higher, &salt);
+    kp.reveal_bet(&bob, &round_id, &Side::Lower, &salt);
+    // but it is bound to her, so he cannot open it
+    assert!(kp
+        .try_reveal_bet(&dave, &round_id, &Side::Higher, &salt)
+        .is_err());
+    assert_eq!(
+        kp.get_sealed_bet(&round_id, &carol),
+        Some(SealedBet {
+            commitment: seal(&carol, round_id, Side::Lower),
+            amount: 50,
+        })
+    );
+
+    env.ledger().set_sequence_number(cur + 10);
+    assert!(kp
+        .try_reveal_bet(&carol, &round_id, &Side::Lower, &salt)
+        .is_err());
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    // carol's and dave's unrevealed 100 went to the winners
+    assert_eq!(tok.balance(&alice), 300);
+    assert_eq!(tok.balance(&kp.address), 0);
+}
+
+#[test]
+fn voided_sealed_rounds_return_unrevealed_stakes() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_sealed_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    let salt = BytesN::from_array(&env, &[7; 32]);
+    let seal = |player: &Address, round_id: u32, side: Side| -> BytesN<32> {
+        env.crypto()
+            .sha256(&(player.clone(), round_id, side, salt.clone()).to_xdr(&env))
+            .into()
+    };
+    for (player, side, amount) in [
+        (&alice, Side::Higher, 100),
+        (&bob, Side::Lower, 60),
+        (&carol, Side::Lower, 40),
+    ] {
+        mint.mint(player, &amount);
+        kp.commit_bet(player, &round_id, &seal(player, round_id, side), &amount);
+    }
+
+    // nothing revealed yet is not one‑sided
+    env.ledger().set_sequence_number(cur + 6);
+    assert!(kp.try_void_one_sided(&round_id).is_err());
+    kp.reveal_bet(&alice, &round_id, &Side::Higher, &salt);
+    assert!(kp.try_reclaim_sealed_bet(&bob, &round_id).is_err());
+
+    kp.cancel_round(&admin, &round_id);
+    kp.claim(&alice, &round_id);
+    assert_eq!(kp.reclaim_sealed_bet(&bob, &round_id), 60);
+    assert!(kp.try_reclaim_sealed_bet(&bob, &round_id).is_err());
+    kp.reclaim_sealed_bet(&carol, &round_id);
+    // every stake back, exactly
+    assert_eq!(
+        [&alice, &bob, &carol].map(|p| tok.balance(p)),
+        [100, 60, 40]
+    );
+    assert_eq!(tok.balance(&kp.address), 0);
+
+    // a round nobody revealed in is voided after the reveal window
+    let cur = env.ledger().sequence();
+    let quiet = kp.start_sealed_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.commit_bet(&bob, &quiet, &seal(&bob, quiet, Side::Lower), &60);
+    env.ledger().set_sequence_number(cur + 10);
+    kp.void_one_sided(&quiet);
+    assert_eq!(kp.reclaim_sealed_bet(&bob, &quiet), 60);
+}
+
+#[test]
+fn operator_bets_from_deposit_within_limit() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bot, eve] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&alice, &500);
+    kp.deposit(&alice, &300);
+    kp.set_operator(&alice, &bot, &200);
+
+    assert!(kp
+        .try_operator_bet(&eve, &alice, &round_id, &Side::Higher, &100)
+        .is_err());
+    kp.operator_bet(&bot, &alice, &round_id, &Side::Higher, &150);
+    assert!(kp
+        .try_operator_bet(&bot, &alice, &round_id, &Side::Higher, &51)
+        .is_err());
+    kp.operator_bet(&bot, &alice, &round_id, &Side::Higher, &50);
+    assert_eq!(kp.get_stake(&alice, &round_id).unwrap().amount, 200);
+    assert!(kp.get_stake(&bot, &round_id).is_none());
+    assert_eq!(kp.get_deposit(&alice), 100);
+
+    kp.revoke_operator(&alice);
+    let next = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert!(kp
+        .try_operator_bet(&bot, &alice, &next, &Side::Higher, &50)
+        .is_err());
+    kp.withdraw(&alice, &100);
+    assert_eq!(tok.balance(&alice), 300);
+    assert_eq!(tok.balance(&kp.address), 200);
+}
+
+#[test]
+fn bet_memo_is_evented() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet_with_memo(
+        &alice,
+        &round_id,
+        &Side::Higher,
+        &100,
+        &symbol_short!("discord"),
+    );
+
+    let (contract, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(contract, kp.address);
+    assert_eq!(
+        topics,
+        (
+            symbol_short!("memo"),
+            SCHEMA_VERSION,
+            symbol_short!("discord"),
+            alice.clone()
+        )
+            .into_val(&env)
+    );
+    let data: (u32, Side, i128) = data.try_into_val(&env).unwrap();
+    assert_eq!(data, (round_id, Side::Higher, 100));
+    assert_eq!(kp.get_stake(&alice, &round_id).unwrap().amount, 100);
+}
+
+#[test]
+fn winnings_roll_into_the_next_round() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    let cur = env.ledger().sequence();
+    let next = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    // a quarter of the 400 won rides on, the rest is paid out
+    assert_eq!(
+        kp.claim_and_rebet(&alice, &round_id, &next, &Side::Lower, &2_500),
+        100
+    );
+    assert_eq!(tok.balance(&alice), 300);
+    assert_eq!(kp.get_stake(&alice, &next).unwrap().amount, 100);
+    assert_eq!(kp.get_round(&next).low_pool, 100);
+    assert!(kp.get_stake(&alice, &round_id).is_none());
+
+    // a losing stake has nothing to roll over
+    assert!(kp
+        .try_claim_and_rebet(&bob, &round_id, &next, &Side::Lower, &10_000)
+        .is_err());
+}
+
+#[test]
+fn insured_loser_is_paid_cover_from_reserve() {
+    let (env, mint, tok, kp, admin) = setup();
+    kp.set_insurance_terms(
+        &admin,
+        &Some(InsuranceTerms {
+            premium_bps: 1_000,
+            cover_bps: 5_000,
+        }),
+    );
+    mint.mint(&admin, &40);
+    kp.fund_insurance(&admin, &40);
+
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &220);
+    mint.mint(&bob, &100);
+    kp.bet_insured(&alice, &round_id, &Side::Higher, &100);
+    assert_eq!(tok.balance(&alice), 110);
+    assert_eq!(
+        kp.get_insurance_reserve(),
+        Reserve {
+            assets: 50,
+            exposure: 50
+        }
+    );
+    // no more cover than the reserve holds, and no selling insured stakes
+    assert!(kp
+        .try_bet_insured(&alice, &round_id, &Side::Higher, &100)
+        .is_err());
+    assert!(kp.try_cash_out(&alice, &round_id).is_err());
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &50u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(kp.settle_insurance(&alice, &round_id), 50);
+    assert_eq!(tok.balance(&alice), 160);
+    assert!(kp.get_policy(&round_id, &alice).is_none());
+    assert_eq!(kp.get_insurance_reserve(), Reserve::default());
+    assert!(kp.try_settle_insurance(&alice, &round_id).is_err());
+}
+
+#[test]
+fn winning_policy_releases_cover() {
+    let (env, mint, _tok, kp, admin) = setup();
+    kp.set_insurance_terms(
+        &admin,
+        &Some(InsuranceTerms {
+            premium_bps: 1_000,
+            cover_bps: 5_000,
+        }),
+    );
+    mint.mint(&admin, &100);
+    kp.fund_insurance(&admin, &100);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &110);
+    kp.bet_insured(&alice, &round_id, &Side::Higher, &100);
+    assert_eq!(
+        kp.get_policy(&round_id, &alice),
+        Some(Policy {
+            side: Side::Higher,
+            cover: 50
+        })
+    );
+    // cover is locked until the policy settles
+    assert!(kp.try_withdraw_insurance(&admin, &61).is_err());
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    assert_eq!(kp.settle_insurance(&alice, &round_id), 0);
+    kp.withdraw_insurance(&admin, &110);
+    assert_eq!(kp.get_insurance_reserve(), Reserve::default());
+}
+
+/// Cover and a hedge would both pay on the same result, so a stake may
+/// carry one or the other.
+#[test]
+fn insured_stakes_cannot_be_hedged() {
+    let (env, mint, _tok, kp, admin) = setup();
+    kp.set_insurance_terms(
+        &admin,
+        &Some(InsuranceTerms {
+            premium_bps: 1_000,
+            cover_bps: 5_000,
+        }),
+    );
+    mint.mint(&admin, &100);
+    kp.fund_insurance(&admin, &100);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&alice, &220);
+    mint.mint(&bob, &220);
+    mint.mint(&carol, &220);
+
+    kp.bet_insured(&alice, &round_id, &Side::Higher, &100);
+    assert!(kp.try_bet(&alice, &round_id, &Side::Lower, &100).is_err());
+    kp.bet(&carol, &round_id, &Side::Lower, &100);
+    assert!(kp
+        .try_transfer_stake(&carol, &alice, &round_id, &50)
+        .is_err());
+
+    kp.bet(&bob, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &10);
+    assert!(kp
+        .try_bet_insured(&bob, &round_id, &Side::Higher, &100)
+        .is_err());
+    // nor may cover be bought on the far side of a stake
+    assert!(kp
+        .try_bet_insured(&carol, &round_id, &Side::Higher, &100)
+        .is_err());
+}
+
+#[test]
+fn claim_all_pays_every_round_at_once() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let first = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let second = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &200);
+    for round_id in [first, second] {
+        kp.bet(&alice, &round_id, &Side::Higher, &100);
+        kp.bet(&bob, &round_id, &Side::Lower, &100);
+    }
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &first, &150u32);
+    kp.resolve_round(&admin, &second, &50u32);
+    open_claims(&env);
+
+    // a round listed twice fails the lot
+    assert!(kp.try_claim_all(&alice, &vec![&env, first, first]).is_err());
+    kp.claim_all(&alice, &vec![&env, first, second]);
+    assert_eq!(tok.balance(&alice), 200);
+    assert!(kp.get_stake(&alice, &first).is_none());
+    assert!(kp.get_stake(&alice, &second).is_none());
+}
+
+#[test]
+fn distribute_pays_winners_without_their_claim() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    mint.mint(&carol, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+    kp.bet(&carol, &round_id, &Side::Higher, &100);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    kp.claim(&carol, &round_id);
+    let keeper = Address::generate(&env);
+    assert_eq!(
+        kp.distribute(
+            &round_id,
+            &vec![&env, alice.clone(), bob.clone(), carol.clone(), keeper]
+        ),
+        250
+    );
+    assert_eq!(tok.balance(&alice), 250);
+    assert_eq!(tok.balance(&bob), 0);
+    assert_eq!(kp.get_round(&round_id).state, RoundState::Archived);
+}
+
+#[test]
+fn claim_fee_accrues_to_the_collector() {
+    let (env, mint, tok, kp, admin) = setup();
+    let collector = Address::generate(&env);
+    kp.set_fee_bps(&admin, &500);
+    kp.set_fee_collector(&admin, &collector);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 380); // 400 less 5 %
+    assert_eq!(kp.get_accrued_fees(&tok.address), 20);
+    assert!(kp.try_withdraw_fees(&admin, &tok.address).is_err());
+    assert_eq!(kp.withdraw_fees(&collector, &tok.address), 20);
+    assert_eq!(tok.balance(&collector), 20);
+    assert_eq!(kp.get_accrued_fees(&tok.address), 0);
+}
+
+#[test]
+fn rounding_dust_is_swept_after_the_last_claim() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &100);
+    mint.mint(&carol, &200);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    kp.bet(&carol, &round_id, &Side::Higher, &200);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    kp.claim(&alice, &round_id);
+    kp.claim(&carol, &round_id);
+    assert_eq!(tok.balance(&alice) + tok.balance(&carol), 399);
+    // bob has not claimed yet
+    assert!(kp.try_sweep_dust(&round_id).is_err());
+    kp.claim(&bob, &round_id);
+    assert_eq!(kp.sweep_dust(&round_id), 1);
+    assert_eq!(tok.balance(&admin), 1);
+    assert_eq!(tok.balance(&kp.address), 0);
+    assert!(kp.try_sweep_dust(&round_id).is_err());
+}
+
+/// Refunds count against the pot, so a fully refunded round has no dust.
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn refunded_round_has_no_dust() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &70);
+    mint.mint(&bob, &30);
+    kp.bet(&alice, &round_id, &Side::Higher, &70);
+    kp.bet(&bob, &round_id, &Side::Lower, &30);
+
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    kp.refund(&alice, &round_id);
+    kp.refund(&bob, &round_id);
+    assert_eq!(kp.get_round(&round_id).paid_out, 100);
+    kp.sweep_dust(&round_id);
+}
+
+#[test]
+fn unclaimed_winnings_roll_into_the_jackpot() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10)))
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    assert!(kp.try_sweep_unclaimed(&round_id).is_err());
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 100);
+    assert_eq!(kp.sweep_unclaimed(&round_id), 200);
+    assert_eq!(kp.get_round(&round_id).state, RoundState::Archived);
+    assert!(kp.try_claim(&alice, &round_id).is_err());
+
+    // the next count round's jackpot starts with it
+    let cur = env.ledger().sequence();
+    let next = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(kp.get_jackpot(&next).carried, 200);
+    assert_eq!(tok.balance(&kp.address), 200);
+}
+
+#[test]
+fn claimable_previews_the_claim() {
+    let (env, mint, tok, kp, admin) = setup();
+    kp.set_fee_bps(&admin, &500);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert_eq!(kp.get_claimable(&alice, &round_id), 0); // amend window
+    open_claims(&env);
+
+    assert_eq!(kp.get_claimable(&alice, &round_id), 380);
+    assert_eq!(kp.get_claimable(&bob, &round_id), 0);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 380);
+    assert_eq!(kp.get_claimable(&alice, &round_id), 0);
+}
+
+#[test]
+fn claim_to_pays_the_recipient() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let cold = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    kp.claim_to(&alice, &round_id, &cold);
+    assert_eq!(tok.balance(&cold), 100);
+    assert_eq!(tok.balance(&alice), 0);
+    assert!(kp.try_claim(&alice, &round_id).is_err());
+}
+
+#[test]
+fn unbacked_winning_side_returns_every_stake() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &300);
+    kp.bet(&alice, &round_id, &Side::Lower, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+    env.ledger().set_sequence_number(cur + 11);
+    // Higher wins, but nobody backed it
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Push);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&alice), 100);
+    assert_eq!(tok.balance(&bob), 300);
+}
+
+#[test]
+fn claim_window_applies_to_new_rounds() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let before = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.set_claim_window(&admin, &1_000);
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(kp.get_rules(&before).claim_window_ledgers, 0);
+    assert_eq!(kp.get_rules(&round_id).claim_window_ledgers, 1_000);
+
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 999);
+    assert!(kp.try_sweep_unclaimed(&round_id).is_err());
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 1);
+    assert_eq!(kp.sweep_unclaimed(&round_id), 100);
+}
+
+#[test]
+fn large_pots_pay_without_early_rounding() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    // stake × pot is past i128, the payout is not
+    let huge = 1i128 << 64;
+    mint.mint(&alice, &huge);
+    mint.mint(&bob, &huge);
+    kp.bet(&alice, &round_id, &Side::Higher, &huge);
+    kp.bet(&bob, &round_id, &Side::Lower, &huge);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 2 * huge);
+
+    // a fee curve's rate is not cut to whole basis points first
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        fee_curve: FeeCurve {
+            min_bps: 100,
+            max_bps: 500,
+        },
+        ..kp.get_rules(&round_id)
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    mint.mint(&alice, &1_000_000);
+    mint.mint(&bob, &2_000_000);
+    kp.bet(&alice, &round_id, &Side::Higher, &1_000_000);
+    kp.bet(&bob, &round_id, &Side::Lower, &2_000_000);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    // 2 000 000 × (1 % + 4 % × ⅓), rather than at 233 bps
+    assert_eq!(kp.get_round(&round_id).protocol_fee, 46_666);
+}
+
+#[test]
+fn liabilities_follow_funds_in_and_out() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &350);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &300);
+    kp.deposit(&bob, &50);
+    assert_eq!(kp.get_liabilities(&tok.address), 450);
+    assert_eq!(kp.get_surplus(&tok.address), 0);
+
+    // tokens sent straight to the contract are nobody's
+    mint.mint(&kp.address, &7);
+    assert_eq!(kp.get_surplus(&tok.address), 7);
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.withdraw(&bob, &50);
+    assert_eq!(kp.get_liabilities(&tok.address), 0);
+    assert_eq!(kp.get_surplus(&tok.address), 7);
+}
+
+#[test]
+fn roles_grant_scoped_powers() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let (resolver, manager, pauser) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    kp.grant_role(&admin, &Role::Resolver, &resolver);
+    kp.grant_role(&admin, &Role::FeeManager, &manager);
+    kp.grant_role(&admin, &Role::Pauser, &pauser);
+    assert!(kp.has_role(&Role::Resolver, &resolver));
+    assert!(kp.has_role(&Role::Pauser, &admin));
+    assert!(!kp.has_role(&Role::Resolver, &manager));
+
+    // the fee manager tunes fees but cannot resolve
+    kp.set_fee_bps(&manager, &100);
+    assert!(kp.try_set_fee_bps(&resolver, &100).is_err());
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    env.ledger().set_sequence_number(cur + 11);
+    assert!(kp.try_resolve_round(&manager, &round_id, &150u32).is_err());
+    kp.resolve_round(&resolver, &round_id, &150u32);
+
+    // paused: no new money, but winnings still go out
+    kp.pause(&pauser);
+    assert!(kp.is_paused());
+    let far = cur + 11 + AMEND_WINDOW_LEDGERS;
+    let next = kp.start_round(&admin, &100u32, &(far + 5), &(far + 10));
+    assert!(kp.try_bet(&alice, &next, &Side::Higher, &100).is_err());
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.unpause(&pauser);
+    kp.bet(&alice, &next, &Side::Higher, &100);
+
+    kp.revoke_role(&admin, &Role::Pauser, &pauser);
+    assert!(kp.try_pause(&pauser).is_err());
+}
+
+#[test]
+fn migrate_brings_old_storage_up_to_date() {
+    let (env, mint, tok, kp, admin) = setup();
+    assert_eq!(kp.get_storage_version(), 2);
+    assert!(kp.try_migrate(&admin).is_err());
+
+    // a deployment from before versioning, holding a bet
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    env.as_contract(&kp.address, || {
+        env.storage().instance().remove(&DataKey::Version);
+        env.storage()
+            .instance()
+            .remove(&DataKey::Liabilities(tok.address.clone()));
+        env.storage().instance().remove(&DataKey::Config);
+        env.storage().instance().set(&DataKey::MinBet, &10i128);
+    });
+    assert_eq!(kp.get_storage_version(), 0);
+
+    assert_eq!(kp.migrate(&admin), 2);
+    assert_eq!(kp.get_liabilities(&tok.address), 100);
+    assert_eq!(kp.get_config().min_bet, 10);
+    assert_eq!(kp.get_round(&round_id).high_pool, 100);
+
+    let eve = Address::generate(&env);
+    assert!(kp
+        .try_upgrade(&eve, &BytesN::from_array(&env, &[0; 32]))
+        .is_err());
+}
+
+#[test]
+fn timelock_holds_back_sensitive_changes() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let other = Address::generate(&env);
+    kp.set_timelock(&admin, &100);
+    assert!(kp.try_set_fee_bps(&admin, &100).is_err());
+    assert!(kp.try_set_token_allowed(&admin, &other, &true).is_err());
+    assert!(kp.try_grant_role(&admin, &Role::Resolver, &other).is_err());
+    assert!(kp.try_set_timelock(&admin, &0).is_err());
+
+    let cur = env.ledger().sequence();
+    let listing = kp.queue_change(&admin, &Change::Token(other.clone(), true));
+    let resolver = kp.queue_change(&admin, &Change::Resolver(other.clone(), true));
+    let fee = kp.queue_change(&admin, &Change::Fee(Fee::Claim, 100));
+    assert_eq!(kp.get_queued_change(&listing).unwrap().eta, cur + 100);
+    assert!(kp.try_execute_change(&admin, &listing).is_err());
+
+    env.ledger().set_sequence_number(cur + 100);
+    kp.execute_change(&admin, &listing);
+    kp.execute_change(&admin, &resolver);
+    kp.cancel_change(&admin, &fee);
+    assert_eq!(kp.get_allowed_tokens(), vec![&env, other.clone()]);
+    assert!(kp.has_role(&Role::Resolver, &other));
+    assert_eq!(kp.get_queued_change(&listing), None);
+    assert!(kp.try_execute_change(&admin, &fee).is_err());
+
+    // lifting the lock is itself held back
+    let unlock = kp.queue_change(&admin, &Change::Delay(0));
+    env.ledger().set_sequence_number(cur + 200);
+    kp.execute_change(&admin, &unlock);
+    kp.set_fee_bps(&admin, &100);
+}
+
+#[test]
+fn grace_period_applies_to_new_rounds() {
+    let (env, _mint, _tok, kp, admin) = setup_long_lived();
+    assert!(kp
+        .try_set_grace_ledgers(&admin, &PUBLIC_RESOLVE_LEDGERS)
+        .is_err());
+    kp.set_grace_ledgers(&admin, &17_280);
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+    assert_eq!(kp.get_rules(&round_id).grace_ledgers, 17_280);
+
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    assert_eq!(kp.get_status(&round_id), RoundState::Locked);
+    env.ledger().set_sequence_number(finality + 17_281);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Expired);
+}
+
+#[test]
+fn config_sets_every_tunable_at_once() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let config = Config {
+        min_bet: 10,
+        fee_bps: 100,
+        grace_ledgers: 1_000,
+        max_open_rounds: 1,
+        claim_window_ledgers: 500,
+        insiders_may_bet: false,
+        min_betting_ledgers: 0,
+        min_finality_gap: 1,
+    };
+    kp.set_config(&admin, &config);
+    assert_eq!(kp.get_config(), config);
+    kp.set_min_bet(&admin, &5);
+    assert_eq!(kp.get_config().min_bet, 5);
+    assert!(kp
+        .try_set_config(
+            &admin,
+            &Config {
+                fee_bps: 10_000,
+                ..config.clone()
+            }
+        )
+        .is_err());
+
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let rules = kp.get_rules(&round_id);
+    assert_eq!(
+        (rules.grace_ledgers, rules.claim_window_ledgers),
+        (1_000, 500)
+    );
+    assert!(kp
+        .try_start_round(&admin, &100u32, &(cur + 5), &(cur + 10))
+        .is_err());
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &4);
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &4).is_err());
+}
+
+#[test]
+fn renounced_admin_cannot_act() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let resolver = Address::generate(&env);
+    kp.grant_role(&admin, &Role::Resolver, &resolver);
+    assert!(kp.try_renounce_admin(&admin).is_err());
+
+    setup_oracle(&env, &kp, &admin, 0);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    kp.renounce_admin(&admin);
+    assert_eq!(kp.get_admin(), kp.address);
+    assert!(!kp.has_role(&Role::Resolver, &resolver));
+
+    env.ledger().set_sequence_number(cur + 11);
+    assert!(kp.try_resolve_round(&admin, &round_id, &150u32).is_err());
+    assert!(kp.try_resolve_round(&resolver, &round_id, &150u32).is_err());
+    assert!(kp
+        .try_start_round(&admin, &100u32, &(cur + 15), &(cur + 20))
+        .is_err());
+    assert!(kp.try_set_min_bet(&admin, &5).is_err());
+
+    // the oracle still settles it
+    env.ledger()
+        .set_sequence_number(cur + 11 + PUBLIC_RESOLVE_LEDGERS);
+    kp.public_resolve(&bob, &round_id);
+    assert_eq!(kp.get_round(&round_id).outcome, Outcome::Lower);
+}
+
+#[test]
+fn token_holders_vote_changes_through() {
+    let (env, mint, tok, kp, admin) = setup();
+    kp.set_governance(
+        &admin,
+        &Some(GovernanceConfig {
+            quorum: 100,
+            voting_ledgers: 50,
+        }),
+    );
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &80);
+    mint.mint(&bob, &30);
+
+    let cur = env.ledger().sequence();
+    let grace = kp.propose_change(&alice, &Change::Grace(1_000));
+    let fee = kp.propose_change(&bob, &Change::Fee(Fee::Claim, 100));
+    kp.vote(&alice, &grace, &true, &60);
+    kp.vote(&alice, &grace, &true, &20);
+    kp.vote(&bob, &grace, &false, &30);
+    assert!(kp.try_vote(&bob, &fee, &true, &1).is_err()); // tokens locked
+    assert_eq!(
+        kp.get_ballot(&grace, &alice),
+        Some(Ballot {
+            support: true,
+            amount: 80
+        })
+    );
+    assert_eq!(tok.balance(&kp.address), 110);
+
+    assert!(kp.try_execute_motion(&grace).is_err());
+    env.ledger().set_sequence_number(cur + 51);
+    assert!(kp.try_vote(&alice, &grace, &true, &1).is_err());
+    kp.execute_motion(&grace);
+    assert_eq!(kp.get_config().grace_ledgers, 1_000);
+    assert!(kp.try_execute_motion(&grace).is_err());
+    // nobody voted on the fee
+    assert!(kp.try_execute_motion(&fee).is_err());
+
+    assert_eq!(kp.withdraw_vote(&alice, &grace), 80);
+    assert_eq!(kp.withdraw_vote(&bob, &grace), 30);
+    assert_eq!(tok.balance(&alice), 80);
+    assert_eq!(kp.get_liabilities(&tok.address), 0);
+}
+
+#[test]
+fn surplus_is_withdrawn_only_through_the_timelock() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    mint.mint(&kp.address, &7);
+    assert!(kp.try_withdraw_surplus(&admin, &tok.address, &7).is_err());
+
+    kp.set_timelock(&admin, &100);
+    let too_much = kp.withdraw_surplus(&admin, &tok.address, &8);
+    let id = kp.withdraw_surplus(&admin, &tok.address, &7);
+    env.ledger().set_sequence_number(cur + 100);
+    assert!(kp.try_execute_change(&admin, &too_much).is_err());
+    kp.execute_change(&admin, &id);
+    assert_eq!(tok.balance(&admin), 7);
+    assert_eq!(tok.balance(&kp.address), 100);
+    assert_eq!(kp.get_surplus(&tok.address), 0);
+}
+
+#[test]
+fn insiders_may_bet_only_when_allowed() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let resolver = Address::generate(&env);
+    kp.grant_role(&admin, &Role::Resolver, &resolver);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    mint.mint(&admin, &100);
+    mint.mint(&resolver, &100);
+    assert!(kp
+        .try_bet(&resolver, &round_id, &Side::Higher, &100)
+        .is_err());
+
+    kp.set_config(
+        &admin,
+        &Config {
+            insiders_may_bet: true,
+            ..kp.get_config()
+        },
+    );
+    kp.bet(&admin, &round_id, &Side::Higher, &100);
+    kp.bet(&resolver, &round_id, &Side::Lower, &100);
+}
+
+#[test]
+fn blocked_accounts_cannot_bet_or_claim() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &200);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+
+    kp.block_account(&admin, &alice, &false);
+    kp.block_account(&admin, &bob, &true);
+    assert!(kp.is_blocked(&alice));
+    assert!(kp.try_bet(&alice, &round_id, &Side::Higher, &100).is_err());
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &50u32);
+    open_claims(&env);
+    assert!(kp.try_claim(&bob, &round_id).is_err());
+    assert_eq!(kp.distribute(&round_id, &vec![&env, bob.clone()]), 0);
+    kp.unblock_account(&admin, &bob);
+    kp.claim(&bob, &round_id);
+    assert_eq!(tok.balance(&bob), 300);
+}
+
+/// A frozen account is paid nothing from the side books either, until it
+/// is unblocked.
+#[test]
+fn frozen_accounts_are_not_paid_from_side_books() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let [alice, bob, lp] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&admin, &1_000);
+    mint.mint(&alice, &500);
+    mint.mint(&bob, &100);
+    mint.mint(&lp, &1_000);
+    kp.deposit_vault(&lp, &1_000);
+    kp.set_insurance_terms(
+        &admin,
+        &Some(InsuranceTerms {
+            premium_bps: 1_000,
+            cover_bps: 5_000,
+        }),
+    );
+    kp.fund_insurance(&admin, &100);
+    let odds = FixedOdds {
+        higher_bps: 20_000,
+        lower_bps: 20_000,
+    };
+    let fixed = kp.start_fixed_round(&admin, &100u32, &(cur + 5), &(cur + 10), &odds);
+    let lmsr = kp.start_lmsr_round(&admin, &100u32, &(cur + 5), &(cur + 10), &1_000);
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let series_id = kp.start_series(&admin, &vec![&env, round_id], &Side::Higher, &1u32);
+
+    kp.bet_fixed(&alice, &fixed, &Side::Higher, &100);
+    kp.buy_shares(&alice, &lmsr, &Side::Higher, &100, &100);
+    kp.bet_insured(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    kp.split_set(&alice, &round_id, &20);
+    kp.bet_jackpot(&alice, &round_id, &150u32, &10);
+    kp.bet_series(&alice, &series_id, &true, &10);
+    kp.deposit(&alice, &50);
+
+    env.ledger().set_sequence_number(cur + 11);
+    for round in [fixed, lmsr, round_id] {
+        kp.resolve_round(&admin, &round, &150u32);
+    }
+    open_claims(&env);
+    kp.block_account(&admin, &alice, &true);
+    assert_eq!(
+        kp.try_claim_fixed(&alice, &fixed),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_redeem_shares(&alice, &lmsr),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_claim_jackpot(&alice, &round_id),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_claim_series(&alice, &series_id),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_redeem_set(&alice, &round_id),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_merge_set(&alice, &round_id, &20),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_transfer_shares(&alice, &bob, &round_id, &Side::Higher, &20),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(
+        kp.try_settle_insurance(&alice, &round_id),
+        Err(Ok(Error::RiskRejected))
+    );
+    assert_eq!(kp.try_withdraw(&alice, &50), Err(Ok(Error::RiskRejected)));
+
+    kp.unblock_account(&admin, &alice);
+    kp.claim_fixed(&alice, &fixed);
+    kp.redeem_shares(&alice, &lmsr);
+    kp.claim_jackpot(&alice, &round_id);
+    kp.claim_series(&alice, &series_id);
+    assert_eq!(kp.redeem_set(&alice, &round_id), 20);
+    assert_eq!(kp.settle_insurance(&alice, &round_id), 0);
+    kp.withdraw(&alice, &50);
+}
+
+#[test]
+fn private_rounds_take_only_allowed_bets() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let listed = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let vetted = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &200);
+
+    kp.set_round_allowlist(
+        &admin,
+        &listed,
+        &Some(RoundAllowlist::Accounts(vec![&env, alice.clone()])),
+    );
+    let registry = env.register(MockAllowlist, ());
+    MockAllowlistClient::new(&env, &registry).approve(&bob);
+    kp.set_round_allowlist(&admin, &vetted, &Some(RoundAllowlist::Contract(registry)));
+
+    kp.bet(&alice, &listed, &Side::Higher, &100);
+    assert!(kp.try_bet(&bob, &listed, &Side::Lower, &100).is_err());
+    kp.bet(&bob, &vetted, &Side::Lower, &100);
+    assert!(kp.try_bet(&alice, &vetted, &Side::Higher, &100).is_err());
+
+    kp.set_round_allowlist(&admin, &listed, &None);
+    kp.bet(&bob, &listed, &Side::Lower, &100);
+}
+
+#[test]
+fn exposure_is_capped_across_open_rounds() {
+    let (env, mint, _tok, kp, admin) = setup();
+    kp.set_bet_caps(
+        &admin,
+        &Some(BetCaps {
+            max_stake: 0,
+            max_pool: 0,
+            max_exposure: 150,
+        }),
+    );
+    let cur = env.ledger().sequence();
+    let first = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let second = kp.start_round(&admin, &100u32, &(cur + 15), &(cur + 20));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &500);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &first, &Side::Higher, &100);
+    kp.bet(&bob, &first, &Side::Lower, &100);
+    kp.bet(&alice, &second, &Side::Higher, &50);
+    assert_eq!(kp.get_exposure(&alice), 150);
+    assert!(kp.try_bet(&alice, &second, &Side::Higher, &1).is_err());
+
+    // settled rounds stop counting
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &first, &150u32);
+    assert_eq!(kp.get_exposure(&alice), 50);
+    kp.bet(&alice, &second, &Side::Higher, &100);
+}
+
+#[test]
+fn round_timing_is_checked_against_the_ledger() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    assert!(kp
+        .try_start_round(&admin, &100u32, &cur, &(cur + 10))
+        .is_err());
+    kp.start_round(&admin, &100u32, &(cur + 1), &(cur + 2));
+
+    kp.set_config(
+        &admin,
+        &Config {
+            min_betting_ledgers: 10,
+            min_finality_gap: 20,
+            ..kp.get_config()
+        },
+    );
+    assert!(kp
+        .try_start_round(&admin, &100u32, &(cur + 10), &(cur + 40))
+        .is_err());
+    assert!(kp
+        .try_start_round(&admin, &100u32, &(cur + 11), &(cur + 30))
+        .is_err());
+    kp.start_round(&admin, &100u32, &(cur + 11), &(cur + 31));
+}
+
+/// A repeat bet on the other side is held as a hedge, so whichever side
+/// wins the claim is worked out against that side's pool.
+#[test]
+fn repeat_bet_on_the_other_side_pays_from_its_pool() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let buckets = kp.start_bucket_round(&admin, &vec![&env, 100u32, 200], &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &200);
+    mint.mint(&bob, &50);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&alice, &round_id, &Side::Lower, &50);
+    kp.bet(&bob, &round_id, &Side::Higher, &50);
+    let round = kp.get_round(&round_id);
+    assert_eq!((round.high_pool, round.low_pool), (150, 50));
+
+    // sides with no opposite cannot be mixed
+    kp.bet(&alice, &buckets, &Side::Bucket(0), &10);
+    assert!(kp.try_bet(&alice, &buckets, &Side::Bucket(1), &10).is_err());
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &50u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    // the 50 hedge on Lower takes the whole 200 pot
+    assert_eq!(tok.balance(&alice), 40 + 200);
+}
+
+#[test]
+fn round_and_stake_entries_outlive_a_long_round() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+
+    let ttl =
+        |key: DataKey| env.as_contract(&kp.address, || env.storage().persistent().get_ttl(&key));
+    assert!(ttl(DataKey::Round(round_id)) >= ENTRY_TTL_LEDGERS - 1);
+    assert!(ttl(DataKey::Stake(round_id, alice.clone())) >= ENTRY_TTL_LEDGERS - 1);
+}
+
+#[test]
+fn lmsr_entries_outlive_a_long_round() {
+    let (env, mint, _tok, kp, admin) = setup();
+    mint.mint(&admin, &1_000);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_lmsr_round(&admin, &100u32, &(cur + 5), &(cur + 10), &1_000);
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &1_000);
+    kp.buy_shares(&alice, &round_id, &Side::Higher, &100, &100);
+
+    let ttl =
+        |key: DataKey| env.as_contract(&kp.address, || env.storage().persistent().get_ttl(&key));
+    assert!(ttl(DataKey::Amm(round_id)) >= ENTRY_TTL_LEDGERS - 1);
+    assert!(ttl(DataKey::Holding(round_id, alice.clone())) >= ENTRY_TTL_LEDGERS - 1);
+}
+
+#[test]
+fn instance_is_kept_alive_between_rounds() {
+    let (env, _mint, _tok, kp, _admin) = setup_long_lived();
+    let ttl = || env.as_contract(&kp.address, || env.storage().instance().get_ttl());
+    kp.extend_instance_ttl();
+    assert!(ttl() >= ENTRY_TTL_LEDGERS - 1);
+
+    // just before it would expire, a keeper call tops it up again
+    let cur = env.ledger().sequence();
+    env.ledger().set_sequence_number(cur + ttl() - 100);
+    kp.extend_instance_ttl();
+    assert!(ttl() >= ENTRY_TTL_LEDGERS - 1);
+}
+
+#[test]
+fn short_lived_rounds_keep_stakes_in_temporary_storage() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let plain = kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10));
+    // claims that never close cannot be short‑lived
+    assert!(kp.try_set_short_lived(&admin, &plain).is_err());
+
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&plain)
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    kp.set_short_lived(&admin, &round_id);
+    let until = cur + 10 + rules.grace_ledgers + rules.amend_window_ledgers + 100;
+    assert_eq!(kp.get_stakes_until(&round_id), Some(until));
+
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    // too late once someone has bet
+    assert!(kp.try_set_short_lived(&admin, &round_id).is_err());
+
+    let key = DataKey::Stake(round_id, alice.clone());
+    env.as_contract(&kp.address, || {
+        assert!(!env.storage().persistent().has(&key));
+        assert_eq!(env.storage().temporary().get_ttl(&key), until - cur);
+    });
+    assert_eq!(kp.get_stake(&alice, &round_id).unwrap().amount, 100);
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(tok.balance(&alice), 200);
+    assert!(kp.get_stake(&alice, &round_id).is_none());
+}
+
+#[test]
+fn done_rounds_can_be_pruned_for_their_dust() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10)))
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    let next = kp.start_round(&admin, &100u32, &(cur + 50), &(cur + 60));
+    let (alice, bob, carol) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    mint.mint(&alice, &1);
+    mint.mint(&bob, &1);
+    mint.mint(&carol, &1);
+    kp.bet(&alice, &round_id, &Side::Higher, &1);
+    kp.bet(&bob, &round_id, &Side::Higher, &1);
+    kp.bet(&carol, &round_id, &Side::Lower, &1);
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    kp.claim(&carol, &round_id);
+
+    // archived, but claims have not closed yet
+    let keeper = Address::generate(&env);
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 100);
+    assert_eq!(kp.prune_round(&keeper, &round_id), 1);
+    assert_eq!(tok.balance(&keeper), 1);
+    assert!(kp.try_get_round(&round_id).is_err());
+
+    // keepers step over the gap
+    kp.tick();
+    assert_eq!(kp.get_round(&next).state, RoundState::Locked);
+}
+
+/// A fully refunded round leaves nothing behind for whoever prunes it.
+#[test]
+fn refunded_rounds_prune_without_a_bounty() {
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let finality = cur + 10;
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &finality))
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &finality, &rules);
+    let other = kp.start_round(&admin, &100u32, &(cur + 5), &finality);
+    let (alice, bob, carol) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    mint.mint(&alice, &70);
+    mint.mint(&bob, &30);
+    mint.mint(&carol, &500);
+    kp.bet(&alice, &round_id, &Side::Higher, &70);
+    kp.bet(&bob, &round_id, &Side::Lower, &30);
+    kp.bet(&carol, &other, &Side::Higher, &500);
+
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    kp.refund(&alice, &round_id);
+    kp.refund(&bob, &round_id);
+    assert_eq!(kp.get_status(&round_id), RoundState::Archived);
+
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + AMEND_WINDOW_LEDGERS + 100);
+    let keeper = Address::generate(&env);
+    assert_eq!(kp.prune_round(&keeper, &round_id), 0);
+    assert_eq!(tok.balance(&keeper), 0);
+    // the other round's escrow is untouched
+    assert_eq!(tok.balance(&kp.address), 500);
+}
+
+/// A round stays while outcome shares, insurance, a series, a conditional
+/// round or jackpot hits still need it.
+#[test]
+fn pruning_waits_for_side_books() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10)))
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    let dependent = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let [alice, bob, carol, dave, frank] = [0; 5].map(|_| Address::generate(&env));
+    for who in [&admin, &alice, &bob, &carol, &dave, &frank] {
+        mint.mint(who, &200);
+    }
+    kp.set_insurance_terms(
+        &admin,
+        &Some(InsuranceTerms {
+            premium_bps: 1_000,
+            cover_bps: 5_000,
+        }),
+    );
+    kp.fund_insurance(&admin, &100);
+    kp.set_precondition(
+        &admin,
+        &dependent,
+        &Some(Precondition::Outcome(round_id, Outcome::Higher)),
+    );
+    let series_id = kp.start_series(&admin, &vec![&env, round_id], &Side::Higher, &1u32);
+
+    kp.bet_insured(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    kp.split_set(&carol, &round_id, &20);
+    kp.bet_series(&dave, &series_id, &true, &10);
+    kp.bet_jackpot(&frank, &round_id, &150u32, &10);
+    kp.bet(&frank, &dependent, &Side::Higher, &10);
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    kp.resolve_round(&admin, &dependent, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 100);
+    assert_eq!(kp.get_status(&round_id), RoundState::Archived);
+
+    // each side book lets go of the round in turn
+    let keeper = Address::generate(&env);
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    assert_eq!(kp.redeem_set(&carol, &round_id), 20);
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    assert_eq!(kp.settle_insurance(&alice, &round_id), 0);
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    kp.settle_series(&series_id);
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    kp.claim(&frank, &dependent);
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    kp.claim_jackpot(&frank, &round_id);
+    kp.prune_round(&keeper, &round_id);
+}
+
+/// Stakes left when unclaimed winnings are swept are deleted before the
+/// round can go.
+#[test]
+fn swept_rounds_prune_once_stakes_are_cleared() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        claim_window_ledgers: 100,
+        ..kp.get_rules(&kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10)))
+    };
+    let round_id = kp.start_round_with_rules(&admin, &100u32, &(cur + 5), &(cur + 10), &rules);
+    let [alice, bob, carol] = [0; 3].map(|_| Address::generate(&env));
+    mint.mint(&alice, &10);
+    mint.mint(&bob, &10);
+    kp.bet(&alice, &round_id, &Side::Higher, &10);
+    kp.bet(&bob, &round_id, &Side::Lower, &10);
+
+    env.ledger().set_sequence_number(cur + 11);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 100);
+    assert_eq!(kp.sweep_unclaimed(&round_id), 20);
+
+    let keeper = Address::generate(&env);
+    assert_eq!(
+        kp.try_prune_round(&keeper, &round_id),
+        Err(Ok(Error::WrongMarket))
+    );
+    assert_eq!(
+        kp.clear_stakes(&round_id, &vec![&env, alice.clone(), carol]),
+        1
+    );
+    assert!(kp.get_stake(&alice, &round_id).is_none());
+    assert!(kp.try_prune_round(&keeper, &round_id).is_err());
+    assert_eq!(kp.clear_stakes(&round_id, &vec![&env, bob.clone()]), 1);
+    assert_eq!(kp.prune_round(&keeper, &round_id), 0);
+}
+
+#[test]
+fn player_history_lists_each_round_once() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let first = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let second = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &300);
+    kp.bet(&alice, &second, &Side::Higher, &100);
+    kp.bet(&alice, &first, &Side::Lower, &100);
+    kp.bet(&alice, &second, &Side::Higher, &100);
+    assert_eq!(kp.get_player_rounds(&alice, &0), vec![&env, second, first]);
+    assert_eq!(kp.get_player_round_count(&alice), 2);
+    assert!(kp.get_player_rounds(&alice, &1).is_empty());
+
+    // a transferred position shows up in the receiver's history
+    kp.transfer_stake(&alice, &bob, &first, &50);
+    assert_eq!(kp.get_player_rounds(&bob, &0), vec![&env, first]);
+}
+
+#[test]
+fn open_rounds_are_listed_while_taking_bets() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let short = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let long = kp.start_round(&admin, &100u32, &(cur + 20), &(cur + 30));
+    let cancelled = kp.start_round(&admin, &100u32, &(cur + 20), &(cur + 30));
+    assert_eq!(kp.get_open_rounds(), vec![&env, short, long, cancelled]);
+
+    kp.cancel_round(&admin, &cancelled);
+    assert_eq!(kp.get_open_rounds(), vec![&env, short, long]);
+
+    // betting closes by ledger even with nobody touching the round
+    env.ledger().set_sequence_number(cur + 6);
+    assert_eq!(kp.get_open_rounds(), vec![&env, long]);
+}
+
+#[test]
+fn round_stats_price_each_side() {
+    let (env, mint, _tok, kp, admin) = setup();
+    kp.set_fee_bps(&admin, &100);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob, carol) = (
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    );
+    mint.mint(&alice, &300);
+    mint.mint(&bob, &100);
+    mint.mint(&carol, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &200);
+    kp.bet(&alice, &round_id, &Side::Lower, &100);
+    kp.bet(&bob, &round_id, &Side::Higher, &100);
+    kp.bet(&carol, &round_id, &Side::Lower, &100);
+    // carol backs out; a hedge counts alice on both sides
+    kp.reduce_bet(&carol, &round_id, &100);
+
+    let stats = kp.get_round_stats(&round_id);
+    assert_eq!((stats.total_pool, stats.positions), (400, 2));
+    let (high, low) = (stats.sides.get(0).unwrap(), stats.sides.get(1).unwrap());
+    assert_eq!((high.side, high.pool, high.bettors), (Side::Higher, 300, 2));
+    assert_eq!((low.side, low.pool, low.bettors), (Side::Lower, 100, 1));
+    assert_eq!((high.implied_bps, low.implied_bps), (7_500, 2_500));
+    // 400 / 300 and 400 / 100, less the 1% claim fee
+    assert_eq!((high.multiplier_bps, low.multiplier_bps), (13_200, 39_600));
+}
+
+#[test]
+fn player_stats_track_volume_and_results() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &50);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &50);
+    assert_eq!(kp.get_player_stats(&alice).wagered, 100);
+
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    kp.claim(&bob, &round_id);
+    let (won, lost) = (kp.get_player_stats(&alice), kp.get_player_stats(&bob));
+    assert_eq!((won.wins, won.losses, won.net), (1, 0, 50));
+    assert_eq!(
+        (lost.wagered, lost.wins, lost.losses, lost.net),
+        (50, 0, 1, -50)
+    );
+}
+
+#[test]
+fn lifecycle_actions_are_evented() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let evented = |name: &str| {
+        let topic = Symbol::new(&env, name);
+        env.events()
+            .all()
+            .iter()
+            .filter(|(c, topics, _)| {
+                let first: Result<Symbol, _> = topics.get(0).unwrap().try_into_val(&env);
+                *c == kp.address && first.is_ok_and(|s| s == topic)
+            })
+            .count()
+    };
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    assert_eq!(evented("round_started"), 1);
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    assert_eq!(evented("bet_placed"), 1);
+    // every event carries the schema version, and bets a pool snapshot
+    let (_, topics, data) = env.events().all().last().unwrap();
+    assert_eq!(
+        topics,
+        (
+            Symbol::new(&env, "bet_placed"),
+            SCHEMA_VERSION,
+            round_id,
+            alice.clone()
+        )
+            .into_val(&env)
+    );
+    let data: (Side, i128, i128, i128) = data.try_into_val(&env).unwrap();
+    assert_eq!(data, (Side::Higher, 100, 100, 100));
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &150u32);
+    assert_eq!(evented("round_resolved"), 1);
+    open_claims(&env);
+    kp.claim(&alice, &round_id);
+    assert_eq!(evented("claimed"), 1);
+
+    let cur = env.ledger().sequence();
+    let cancelled = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.cancel_round(&admin, &cancelled);
+    assert_eq!(evented("cancelled"), 1);
+}
+
+// ---------------------------------------------------------------------
+// Error‑coverage tests (one per Error::* variant)
+// ---------------------------------------------------------------------
+
+/// Non‑admin attempts to start and resolve ➜ `Unauthorized` (#1).
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn unauthorized_admin_calls() {
+    let (env, _mint, _tok, kp, _admin) = setup();
+    let eve = Address::generate(&env);
+    let cur = env.ledger().sequence();
+    let deadline = cur + 2;
+    let finality = cur + 4;
+    // eve tries to start
+    kp.start_round(&eve, &1u32, &deadline, &finality);
+}
+
+/// Claiming an unknown round ➜ `RoundNotFound` (#3).
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn round_not_found_claim() {
+    let (env, _mint, _tok, kp, _admin) = setup();
+    let frank = Address::generate(&env); // Higher loser, stake 400
+    kp.claim(&frank, &999u32);
+}
+
+/// Betting after the deadline must fail ➜ `BettingClosed` (#4).
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn bet_after_deadline_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+
+    let cur = env.ledger().sequence();
+    let deadline = cur + 1;
+    let finality = cur + 10;
+    let round_id = kp.start_round(&admin, &10u32, &deadline, &finality);
+
+    let dave = Address::generate(&env);
+    mint.mint(&dave, &10);
+
+    env.ledger().set_sequence_number(deadline + 1); // after deadline
+    kp.bet(&dave, &round_id, &Side::Higher, &10); // should panic
+}
+
+/// Admin resolves twice ➜ `AlreadyResolved` (#5).
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn resolve_twice_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round, &2u32);
+    kp.resolve_round(&admin, &round, &3u32); // second time
+}
+
+/// Cancelling an already resolved round ➜ `AlreadyResolved` (#5).
+#[test]
+#[should_panic(expected = "Error(Contract, #5)")]
+fn cancel_resolved_round_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round, &2u32);
+    kp.cancel_round(&admin, &round);
+}
+
+/// Resolving before finality ➜ `TooEarly` (#6).
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn resolve_too_early_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 5), &(cur + 10));
+    env.ledger().set_sequence_number(cur + 6); // before finality
+    kp.resolve_round(&admin, &round, &0u32);
+}
+
+/// Claim before resolution ➜ `NotResolved` (#7).
+#[test]
+#[should_panic(expected = "Error(Contract, #7)")]
+fn claim_not_resolved_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 3));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &1);
+    kp.bet(&alice, &round, &Side::Higher, &1);
+    env.ledger().set_sequence_number(cur + 2);
+    kp.claim(&alice, &round);
+}
+
+/// Double claim ➜ `AlreadyClaimed` (#8).
+#[test]
+#[should_panic(expected = "Error(Contract, #8)")]
+fn double_claim_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &1);
+    kp.bet(&alice, &round, &Side::Higher, &1);
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round, &2u32);
+    open_claims(&env);
+    kp.claim(&alice, &round);
+    kp.claim(&alice, &round); // second claim
+}
+
+/// Refund attempt before grace period ➜ `RefundNotAvailable` (#9).
+#[test]
+#[should_panic(expected = "Error(Contract, #9)")]
+fn refund_before_grace_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let deadline = cur + 2;
+    let finality = cur + 4;
+    let round = kp.start_round(&admin, &1u32, &deadline, &finality);
+    let frank = Address::generate(&env);
+    mint.mint(&frank, &10);
+    kp.bet(&frank, &round, &Side::Higher, &10);
+
+    // move to just after finality but before grace period ends
+    env.ledger().set_sequence_number(finality + 1);
+    kp.refund(&frank, &round); // should panic with #9
+}
+
+/// Bet amount zero ➜ `ZeroAmount` (#10).
+#[test]
+#[should_panic(expected = "Error(Contract, #10)")]
+fn zero_amount_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let alice = Address::generate(&env);
+    kp.bet(&alice, &round, &Side::Higher, &0);
+}
+
+/// Public resolution without a configured oracle ➜ `OracleNotSet` (#11).
+#[test]
+#[should_panic(expected = "Error(Contract, #11)")]
+fn public_resolve_without_oracle_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let finality = cur + 2;
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &finality);
+    env.ledger()
+        .set_sequence_number(finality + PUBLIC_RESOLVE_LEDGERS + 1);
+    kp.public_resolve(&Address::generate(&env), &round);
+}
+
+/// Public resolution once refunds have opened ➜ `ResolutionExpired` (#12).
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn public_resolve_after_grace_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    setup_oracle(&env, &kp, &admin, 5);
+    let cur = env.ledger().sequence();
+    let finality = cur + 2;
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &finality);
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    kp.public_resolve(&Address::generate(&env), &round);
+}
+
+/// Admin resolution after the hard deadline ➜ `ResolutionExpired` (#12).
+#[test]
+#[should_panic(expected = "Error(Contract, #12)")]
+fn resolve_after_expiry_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let finality = cur + 2;
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &finality);
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    kp.resolve_round(&admin, &round, &1u32);
+}
+
+/// Large bet from a high‑risk player ➜ `RiskRejected` (#13).
+#[test]
+#[should_panic(expected = "Error(Contract, #13)")]
+fn risk_hook_rejects_high_score() {
+    let (env, mint, _tok, kp, admin) = setup();
+    setup_risk(&env, &kp, &admin, 80);
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round, &Side::Higher, &100);
+}
+
+/// Claim during the amend window ➜ `ClaimsNotOpen` (#14).
+#[test]
+#[should_panic(expected = "Error(Contract, #14)")]
+fn claim_during_amend_window_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &1);
+    kp.bet(&alice, &round, &Side::Higher, &1);
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round, &2u32);
+    kp.claim(&alice, &round);
+}
+
+/// Amending after the window ➜ `AmendWindowClosed` (#15).
+#[test]
+#[should_panic(expected = "Error(Contract, #15)")]
+fn amend_after_window_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round, &2u32);
+    open_claims(&env);
+    kp.amend_resolution(&admin, &round, &0u32);
+}
+
+// ---------------------------------------------------------------------
+// Stress harness (run with `cargo test --release -- --ignored stress`)
+// ---------------------------------------------------------------------
+
+/// Distinct stakers in the stress harness's main book.
+const STRESS_STAKERS: u32 = 2_500;
+
+/// Bets each of them places, topping up their stake every time.
+const STRESS_BETS_PER_STAKER: u32 = 4;
+
+/// How many of them also back the round that ends in refunds.
+const STRESS_REFUNDERS: u32 = 500;
+
+/// 10k bets from thousands of stakers in one round: settling must cost the
+/// same per call no matter how big the book is, i.e. no entrypoint scans
+/// the stakers. Every staker claims from the resolved round and a slice of
+/// them refunds from a round the admin abandoned; each call's CPU budget
+/// is checked against the first one and the network's per‑tx ceiling.
+///
+/// The in‑memory host gets slower as the ledger grows, hence `#[ignore]`.
+#[test]
+#[ignore]
+fn stress_ten_thousand_bets() {
+    /// Per‑transaction CPU instruction limit on pubnet.
+    const TX_CPU_LIMIT: u64 = 100_000_000;
+
+    let (env, mint, tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let deadline = cur + 5;
+    let finality = cur + 10;
+    let claimed = kp.start_round(&admin, &100u32, &deadline, &finality);
+    let refunded = kp.start_round(&admin, &100u32, &deadline, &finality);
+
+    let players: std::vec::Vec<Address> = (0..STRESS_STAKERS)
+        .map(|i| {
+            let p = Address::generate(&env);
+            let side = if i % 3 == 0 {
+                Side::Higher
+            } else {
+                Side::Lower
+            };
+            mint.mint(&p, &(10 * i128::from(STRESS_BETS_PER_STAKER + 1)));
+            for _ in 0..STRESS_BETS_PER_STAKER {
+                kp.bet(&p, &claimed, &side, &10);
+            }
+            if i < STRESS_REFUNDERS {
+                kp.bet(&p, &refunded, &side, &10);
+            }
+            p
+        })
+        .collect();
+
+    env.ledger().set_sequence_number(finality + 1);
+    kp.resolve_round(&admin, &claimed, &150u32);
+    open_claims(&env);
+
+    let mut cpu = std::vec::Vec::with_capacity(players.len());
+    for p in &players {
+        kp.claim(p, &claimed);
+        cpu.push(env.cost_estimate().budget().cpu_instruction_cost());
+    }
+
+    env.ledger()
+        .set_sequence_number(finality + GRACE_LEDGERS + 1);
+    // the first refund also settles the round as expired: a one‑off write
+    kp.refund(&players[0], &refunded);
+    let expiring = env.cost_estimate().budget().cpu_instruction_cost();
+    for p in &players[1..STRESS_REFUNDERS as usize] {
+        kp.refund(p, &refunded);
+        cpu.push(env.cost_estimate().budget().cpu_instruction_cost());
+    }
+
+    let first = cpu[0];
+    let worst = *cpu.iter().max().unwrap();
+    println!(
+        "stress: {} bets from {STRESS_STAKERS} stakers, first call {first} insns, worst {worst} insns, expiring refund {expiring} insns",
+        STRESS_STAKERS * STRESS_BETS_PER_STAKER
+    );
+    assert!(worst.max(expiring) < TX_CPU_LIMIT);
+    assert!(worst <= first * 3 / 2, "per-call cost grew with the book");
+
+    // everything that went in came back out, bar rounding dust
+    assert!(tok.balance(&kp.address) < i128::from(STRESS_STAKERS));
+}
+
+/// Committee member repeats their vote ➜ `DuplicateVote` (#16).
+#[test]
+#[should_panic(expected = "Error(Contract, #16)")]
+fn committee_duplicate_vote_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let [r1, _, _] = setup_committee(&env, &kp, &admin);
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&r1, &round, &2u32);
+    kp.resolve_round(&r1, &round, &2u32);
+}
+
+/// Committee member changes their vote ➜ `ConflictingVote` (#17).
+#[test]
+#[should_panic(expected = "Error(Contract, #17)")]
+fn committee_conflicting_vote_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let [r1, _, _] = setup_committee(&env, &kp, &admin);
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&r1, &round, &2u32);
+    kp.resolve_round(&r1, &round, &0u32);
+}
+
+/// Threshold above the member count ➜ `InvalidCommittee` (#18).
+#[test]
+#[should_panic(expected = "Error(Contract, #18)")]
+fn committee_threshold_too_high_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    kp.set_committee(
+        &admin,
+        &Some(Committee {
+            members: vec![&env, Address::generate(&env)],
+            threshold: 2,
+        }),
+    );
+}
+
+/// Any state change after the hand‑over ➜ `Retired` (#20).
+#[test]
+#[should_panic(expected = "Error(Contract, #20)")]
+fn retired_contract_rejects_claims() {
+    let (env, mint, _tok, kp, admin) = setup_long_lived();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &10);
+    kp.bet(&alice, &round, &Side::Higher, &10);
+    kp.cancel_round(&admin, &round);
+
+    let at = kp.schedule_sunset(&admin);
+    env.ledger()
+        .set_sequence_number(at + SUNSET_CLAIM_LEDGERS + 1);
+    kp.sunset_transfer(&admin, &Address::generate(&env));
+    kp.claim(&alice, &round);
+}
+
+/// Scheduling a second sunset ➜ `SunsetActive` (#19).
+#[test]
+#[should_panic(expected = "Error(Contract, #19)")]
+fn schedule_sunset_twice_panics() {
+    let (_env, _mint, _tok, kp, admin) = setup();
+    kp.schedule_sunset(&admin);
+    kp.schedule_sunset(&admin);
+}
+
+/// Keeper fee above the cap ➜ `InvalidFee` (#21).
+#[test]
+#[should_panic(expected = "Error(Contract, #21)")]
+fn keeper_fee_above_cap_panics() {
+    let (_env, _mint, _tok, kp, admin) = setup();
+    kp.set_keeper_fee_bps(&admin, &1_001);
+}
+
+/// Reading below the round's baseline ➜ `InvalidCount` (#22).
+#[test]
+#[should_panic(expected = "Error(Contract, #22)")]
+fn reading_below_baseline_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    setup_oracle(&env, &kp, &admin, 500);
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_round(&admin, &round, &499u32);
+}
+
+/// Public resolve window that never opens ➜ `InvalidRules` (#23).
+#[test]
+#[should_panic(expected = "Error(Contract, #23)")]
+fn public_resolve_after_grace_rules_panic() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let rules = Rules {
+        grace_ledgers: 50,
+        public_resolve_ledgers: 50,
+        amend_window_ledgers: AMEND_WINDOW_LEDGERS,
+        claim_window_ledgers: 0,
+        keeper_fee_bps: 0,
+        tie_policy: TiePolicy::Push,
+        spread: 0,
+        fee_curve: FeeCurve {
+            min_bps: 0,
+            max_bps: 0,
+        },
+        metric: Metric::Invocations,
+    };
+    kp.start_round_with_rules(&admin, &1u32, &(cur + 1), &(cur + 2), &rules);
+}
+
+/// Epoch round without a farm schedule ➜ `FarmNotSet` (#24).
+#[test]
+#[should_panic(expected = "Error(Contract, #24)")]
+fn epoch_round_without_farm_panics() {
+    let (_env, _mint, _tok, kp, admin) = setup();
+    kp.start_epoch_round(&admin, &1u32, &10u32);
+}
+
+/// Attested resolution without an attestor key ➜ `AttestorNotSet` (#25).
+#[test]
+#[should_panic(expected = "Error(Contract, #25)")]
+fn attested_without_attestor_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round = kp.start_round(&admin, &1u32, &(cur + 1), &(cur + 2));
+    env.ledger().set_sequence_number(cur + 3);
+    kp.resolve_attested(&round, &1u32, &BytesN::from_array(&env, &[0; 64]));
+}
+
+/// Proposal while optimistic resolution is off ➜ `OptimisticDisabled` (#26).
+#[test]
+#[should_panic(expected = "Error(Contract, #26)")]
+fn propose_when_disabled_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let round_id = finalised_round(&env, &kp, &admin);
+    kp.propose(&Address::generate(&env), &round_id, &1u32);
+}
+
+/// Second proposal for the same round ➜ `ProposalExists` (#27).
+#[test]
+#[should_panic(expected = "Error(Contract, #27)")]
+fn second_proposal_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    setup_optimistic(&kp, &admin);
+    let round_id = finalised_round(&env, &kp, &admin);
+    let proposer = Address::generate(&env);
+    mint.mint(&proposer, &1_000);
+    kp.propose(&proposer, &round_id, &1u32);
+    kp.propose(&proposer, &round_id, &2u32);
+}
+
+/// Challenge without a proposal ➜ `NoProposal` (#28).
+#[test]
+#[should_panic(expected = "Error(Contract, #28)")]
+fn challenge_without_proposal_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    setup_optimistic(&kp, &admin);
+    let round_id = finalised_round(&env, &kp, &admin);
+    kp.challenge(&Address::generate(&env), &round_id);
+}
+
+/// Challenge after the liveness window ➜ `ChallengeClosed` (#29).
+#[test]
+#[should_panic(expected = "Error(Contract, #29)")]
+fn late_challenge_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    setup_optimistic(&kp, &admin);
+    let round_id = finalised_round(&env, &kp, &admin);
+    let proposer = Address::generate(&env);
+    let challenger = Address::generate(&env);
+    mint.mint(&proposer, &500);
+    mint.mint(&challenger, &500);
+    kp.propose(&proposer, &round_id, &1u32);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 31);
+    kp.challenge(&challenger, &round_id);
+}
+
+/// Settling a disputed proposal ➜ `ProposalDisputed` (#30).
+#[test]
+#[should_panic(expected = "Error(Contract, #30)")]
+fn settle_disputed_proposal_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    setup_optimistic(&kp, &admin);
+    let round_id = finalised_round(&env, &kp, &admin);
+    let proposer = Address::generate(&env);
+    let challenger = Address::generate(&env);
+    mint.mint(&proposer, &500);
+    mint.mint(&challenger, &500);
+    kp.propose(&proposer, &round_id, &1u32);
+    kp.challenge(&challenger, &round_id);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 31);
+    kp.settle_proposal(&round_id);
+}
+
+/// Count resolution on a price round ➜ `WrongMarket` (#32).
+#[test]
+#[should_panic(expected = "Error(Contract, #32)")]
+fn count_resolution_on_price_round_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let (round_id, _oracle) = price_round(&env, &kp, &admin);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 10);
+    kp.resolve_round(&admin, &round_id, &1u32);
+}
+
+/// Price round whose feed has no quote ➜ `PriceUnavailable` (#33).
+#[test]
+#[should_panic(expected = "Error(Contract, #33)")]
+fn price_round_without_quote_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let (round_id, _oracle) = price_round(&env, &kp, &admin);
+    env.ledger()
+        .set_sequence_number(env.ledger().sequence() + 10);
+    kp.resolve_price(&round_id);
+}
+
+/// Feeds further apart than `max_spread` ➜ `OracleDisagreement` (#34).
+#[test]
+#[should_panic(expected = "Error(Contract, #34)")]
+fn diverging_feeds_panic() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let feeds = setup_feeds(&env, &kp, &admin, &[1_000, 1_000, 1_000]);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    MockOracleClient::new(&env, &feeds[2]).set_count(&5_000);
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &round_id, &1_150u32);
+}
+
+/// An empty feed set ➜ `InvalidFeeds` (#35).
+#[test]
+#[should_panic(expected = "Error(Contract, #35)")]
+fn empty_feed_set_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    setup_feeds(&env, &kp, &admin, &[]);
+}
+
+/// Voiding a book with stakes on both sides ➜ `NotOneSided` (#36).
+#[test]
+#[should_panic(expected = "Error(Contract, #36)")]
+fn void_two_sided_round_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    mint.mint(&alice, &100);
+    mint.mint(&bob, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+    kp.bet(&bob, &round_id, &Side::Lower, &100);
+    env.ledger().set_sequence_number(cur + 6);
+    kp.void_one_sided(&round_id);
+}
+
+/// Betting over/under on a bucket round ➜ `InvalidSide` (#37).
+#[test]
+#[should_panic(expected = "Error(Contract, #37)")]
+fn over_under_bet_on_bucket_round_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_bucket_round(&admin, &vec![&env, 100u32], &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+}
+
+/// Bucket edges out of order ➜ `InvalidBuckets` (#38).
+#[test]
+#[should_panic(expected = "Error(Contract, #38)")]
+fn unordered_bucket_edges_panic() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    kp.start_bucket_round(&admin, &vec![&env, 200u32, 100], &(cur + 5), &(cur + 10));
+}
+
+/// A guess past `MAX_GUESSES` distinct ones ➜ `TooManyGuesses` (#39).
+#[test]
+#[should_panic(expected = "Error(Contract, #39)")]
+fn too_many_guesses_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_scalar_round(&admin, &(cur + 5), &(cur + 10));
+    for guess in 0..=MAX_GUESSES {
+        let player = Address::generate(&env);
+        mint.mint(&player, &1);
+        kp.bet(&player, &round_id, &Side::Guess(guess), &1);
+    }
+}
+
+/// A fixed‑odds bet the vault cannot cover ➜ `InsufficientLiquidity` (#40).
+#[test]
+#[should_panic(expected = "Error(Contract, #40)")]
+fn uncovered_fixed_bet_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let odds = FixedOdds {
+        higher_bps: 20_000,
+        lower_bps: 20_000,
+    };
+    let round_id = kp.start_fixed_round(&admin, &100u32, &(cur + 5), &(cur + 10), &odds);
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.bet_fixed(&alice, &round_id, &Side::Higher, &100);
+}
+
+/// A trade costing more than the buyer's cap ➜ `SlippageExceeded` (#41).
+#[test]
+#[should_panic(expected = "Error(Contract, #41)")]
+fn lmsr_slippage_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    mint.mint(&admin, &1_000);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_lmsr_round(&admin, &100u32, &(cur + 5), &(cur + 10), &1_000);
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &1_000);
+    kp.buy_shares(&alice, &round_id, &Side::Higher, &500, &250);
+}
+
+/// Share purchases costing less than the minimum bet are refused.
+#[test]
+fn lmsr_buys_below_min_bet_are_refused() {
+    let (env, mint, _tok, kp, admin) = setup();
+    mint.mint(&admin, &1_000);
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_lmsr_round(&admin, &100u32, &(cur + 5), &(cur + 10), &1_000);
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &1_000);
+    kp.set_min_bet(&admin, &100);
+    assert_eq!(
+        kp.try_buy_shares(&alice, &round_id, &Side::Higher, &10, &100),
+        Err(Ok(Error::ZeroAmount))
+    );
+    assert!(kp.buy_shares(&alice, &round_id, &Side::Higher, &200, &200) >= 100);
+}
+
+/// Moving more shares than held ➜ `InsufficientShares` (#42).
+#[test]
+#[should_panic(expected = "Error(Contract, #42)")]
+fn transfer_unheld_shares_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.split_set(&alice, &round_id, &100);
+    kp.transfer_shares(&alice, &admin, &round_id, &Side::Lower, &101);
+}
+
+/// Resolving before the round it depends on is decided ➜
+/// `PreconditionPending` (#43).
+#[test]
+#[should_panic(expected = "Error(Contract, #43)")]
+fn resolve_with_pending_precondition_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let parent = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 20));
+    let child = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.set_precondition(
+        &admin,
+        &child,
+        &Some(Precondition::Outcome(parent, Outcome::Lower)),
+    );
+    env.ledger().set_sequence_number(cur + 10);
+    kp.resolve_round(&admin, &child, &150u32);
+}
+
+/// Starting a round past the open‑round cap ➜ `TooManyOpenRounds` (#44).
+#[test]
+#[should_panic(expected = "Error(Contract, #44)")]
+fn start_round_over_cap_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    kp.set_max_open_rounds(&admin, &Some(1));
+    kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+}
+
+/// Staking past the per‑player cap ➜ `BetCapExceeded` (#45).
+#[test]
+#[should_panic(expected = "Error(Contract, #45)")]
+fn bet_over_stake_cap_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &500);
+    kp.set_bet_caps(
+        &admin,
+        &Some(BetCaps {
+            max_stake: 100,
+            max_pool: 0,
+            max_exposure: 0,
+        }),
+    );
+    kp.bet(&alice, &round_id, &Side::Higher, &101);
+}
+
+/// Odds that do not fit in i128 ➜ `MathOverflow` (#46).
+#[test]
+#[should_panic(expected = "Error(Contract, #46)")]
+fn odds_overflow_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let (alice, bob) = (Address::generate(&env), Address::generate(&env));
+    let huge = 1i128 << 120;
+    mint.mint(&alice, &huge);
+    mint.mint(&bob, &1);
+    kp.bet(&alice, &round_id, &Side::Higher, &huge);
+    kp.bet_at_odds(&bob, &round_id, &Side::Lower, &1, &0);
+}
+
+/// Betting while the contract is paused ➜ `Paused` (#47).
+#[test]
+#[should_panic(expected = "Error(Contract, #47)")]
+fn bet_while_paused_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    let alice = Address::generate(&env);
+    mint.mint(&alice, &100);
+    kp.pause(&admin);
+    kp.bet(&alice, &round_id, &Side::Higher, &100);
+}
+
+/// The admin betting on their own market ➜ `ConflictOfInterest` (#48).
+#[test]
+#[should_panic(expected = "Error(Contract, #48)")]
+fn admin_bet_panics() {
+    let (env, mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    let round_id = kp.start_round(&admin, &100u32, &(cur + 5), &(cur + 10));
+    mint.mint(&admin, &100);
+    kp.bet(&admin, &round_id, &Side::Higher, &100);
+}
+
+/// A betting deadline already behind the ledger ➜ `DeadlineTooSoon` (#49).
+#[test]
+#[should_panic(expected = "Error(Contract, #49)")]
+fn deadline_in_the_past_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    env.ledger().set_sequence_number(100);
+    kp.start_round(&admin, &100u32, &90, &110);
+}
+
+/// Finality no later than the betting deadline ➜ `FinalityTooSoon` (#50).
+#[test]
+#[should_panic(expected = "Error(Contract, #50)")]
+fn finality_before_deadline_panics() {
+    let (env, _mint, _tok, kp, admin) = setup();
+    let cur = env.ledger().sequence();
+    kp.start_round(&admin, &100u32, &(cur + 10), &(cur + 10));
 }
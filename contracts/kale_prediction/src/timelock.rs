@@ -0,0 +1,197 @@
+//! Timelock on sensitive admin changes. Once the admin sets a delay, fee
+//! rates, the token list, the oracle, resolver roles, code upgrades and the
+//! delay itself can no longer be changed directly (`TooEarly`): a change is
+//! queued, evented, and only executed once the delay has passed, so users
+//! have time to leave first. Committees, feeds and the attestor can only be
+//! changed with no delay set. Surplus tokens — what the contract holds
+//! beyond its liabilities — can only be withdrawn through the timelock.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, token, Address, BytesN, Env};
+
+use crate::events;
+use crate::roles::{self, Role};
+use crate::{
+    allow_token, get_admin, load_config, require_admin, require_live, save_config, store_fee,
+    upgrade, DataKey, Error, KalePrediction, KalePredictionArgs, KalePredictionClient,
+};
+
+/// Longest delay the timelock takes (about 30 days).
+const MAX_TIMELOCK_LEDGERS: u32 = 518_400;
+
+/// A fee rate that can be set.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Fee {
+    Claim = 0,
+    Keeper = 1,
+    Cancel = 2,
+    Referral = 3,
+    CashOutHaircut = 4,
+}
+
+/// A change the timelock holds back.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Change {
+    Fee(Fee, u32),           // rate in basis points
+    Grace(u32),              // `set_grace_ledgers`
+    Token(Address, bool),    // `set_token_allowed`
+    Oracle(Address),         // `set_oracle`
+    Resolver(Address, bool), // grant (true) or revoke the resolver role
+    Upgrade(BytesN<32>),     // `upgrade` to this wasm hash
+    Delay(u32),              // `set_timelock`
+    Surplus(Address, i128),  // `withdraw_surplus` of this token and amount
+}
+
+/// A queued change and the ledger from which it may be executed.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct QueuedChange {
+    pub change: Change,
+    pub eta: u32,
+}
+
+pub(crate) fn delay(e: &Env) -> u32 {
+    e.storage()
+        .instance()
+        .get(&DataKey::TimelockDelay)
+        .unwrap_or(0)
+}
+
+fn set_delay(e: &Env, ledgers: u32) {
+    if ledgers > MAX_TIMELOCK_LEDGERS {
+        panic_with_error!(e, Error::InvalidRules);
+    }
+    e.storage()
+        .instance()
+        .set(&DataKey::TimelockDelay, &ledgers);
+}
+
+/// Direct changes are refused with `TooEarly` while a delay is set.
+pub(crate) fn require_unlocked(e: &Env) {
+    if delay(e) > 0 {
+        panic_with_error!(e, Error::TooEarly);
+    }
+}
+
+pub(crate) fn apply(e: &Env, change: Change) {
+    match change {
+        Change::Fee(fee, bps) => store_fee(e, fee, bps),
+        Change::Grace(ledgers) => {
+            let mut config = load_config(e);
+            config.grace_ledgers = ledgers;
+            save_config(e, &config);
+        }
+        Change::Token(token, allowed) => allow_token(e, token, allowed),
+        Change::Oracle(oracle) => e.storage().instance().set(&DataKey::Oracle, &oracle),
+        Change::Resolver(account, granted) => roles::assign(e, Role::Resolver, account, granted),
+        Change::Upgrade(hash) => upgrade::swap_code(e, hash),
+        Change::Delay(ledgers) => set_delay(e, ledgers),
+        Change::Surplus(token, amount) => take_surplus(e, token, amount),
+    }
+}
+
+/// Send `amount` of `token` beyond the contract's liabilities to the
+/// admin; fails with `InsufficientLiquidity` if there is not that much.
+fn take_surplus(e: &Env, token: Address, amount: i128) {
+    if amount > KalePrediction::get_surplus(e.clone(), token.clone()) {
+        panic_with_error!(e, Error::InsufficientLiquidity);
+    }
+    let this = e.current_contract_address();
+    token::Client::new(e, &token).transfer(&this, &get_admin(e), &amount);
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Hold sensitive changes back `ledgers` after they are queued. With a
+    /// delay already set, the delay too has to be queued (`Change::Delay`).
+    pub fn set_timelock(env: Env, admin: Address, ledgers: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        require_unlocked(&env);
+        set_delay(&env, ledgers);
+    }
+
+    /// Queue `change` to take effect after the delay. Evented as
+    /// `("queued", id) → (change, eta)`. Returns the change's id.
+    pub fn queue_change(env: Env, admin: Address, change: Change) -> u32 {
+        require_live(&env);
+        require_admin(&env, &admin);
+        if let Change::Surplus(_, amount) = change {
+            if amount <= 0 {
+                panic_with_error!(env, Error::ZeroAmount);
+            }
+            // only ever behind a delay
+            if delay(&env) == 0 {
+                panic_with_error!(env, Error::TooEarly);
+            }
+        }
+        let id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::NextChangeId)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::NextChangeId, &(id + 1));
+
+        let eta = env.ledger().sequence() + delay(&env);
+        env.storage().persistent().set(
+            &DataKey::QueuedChange(id),
+            &QueuedChange {
+                change: change.clone(),
+                eta,
+            },
+        );
+        events::publish(&env, "queued", (id,), (change, eta));
+        id
+    }
+
+    /// Apply a queued change once its delay has passed.
+    pub fn execute_change(env: Env, admin: Address, id: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let key = DataKey::QueuedChange(id);
+        let queued: QueuedChange = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::NoProposal));
+        if env.ledger().sequence() < queued.eta {
+            panic_with_error!(env, Error::TooEarly);
+        }
+        env.storage().persistent().remove(&key);
+        apply(&env, queued.change);
+        events::publish(&env, "executed", (id,), ());
+    }
+
+    /// Queue a withdrawal of `amount` of `token` beyond the contract's
+    /// liabilities (see `get_surplus`) to the admin, checked again when it
+    /// is executed. Only possible behind a timelock. Returns the change's
+    /// id.
+    pub fn withdraw_surplus(env: Env, admin: Address, token: Address, amount: i128) -> u32 {
+        Self::queue_change(env, admin, Change::Surplus(token, amount))
+    }
+
+    /// Drop a queued change.
+    pub fn cancel_change(env: Env, admin: Address, id: u32) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let key = DataKey::QueuedChange(id);
+        if !env.storage().persistent().has(&key) {
+            panic_with_error!(env, Error::NoProposal);
+        }
+        env.storage().persistent().remove(&key);
+        events::publish(&env, "dropped", (id,), ());
+    }
+
+    /// The timelock delay in ledgers (0: changes apply directly).
+    pub fn get_timelock(env: Env) -> u32 {
+        delay(&env)
+    }
+
+    /// A queued change, if it is still pending.
+    pub fn get_queued_change(env: Env, id: u32) -> Option<QueuedChange> {
+        env.storage().persistent().get(&DataKey::QueuedChange(id))
+    }
+}
@@ -0,0 +1,113 @@
+//! In‑place upgrades. The admin swaps the contract's code with `upgrade`
+//! and then runs `migrate`, which brings storage written by older code up
+//! to `STORAGE_VERSION` one step at a time, so open rounds, stakes and
+//! balances carry over.
+
+use soroban_sdk::{contractimpl, panic_with_error, token, Address, BytesN, Env};
+
+use crate::events;
+use crate::timelock;
+use crate::{
+    allowed_tokens, default_token, load_config, require_admin, require_live, save_config, DataKey,
+    Error, KalePrediction, KalePredictionArgs, KalePredictionClient,
+};
+
+/// Storage layout this code reads and writes. Deployments from before
+/// versioning count as 0.
+pub(crate) const STORAGE_VERSION: u32 = 2;
+
+fn storage_version(e: &Env) -> u32 {
+    e.storage().instance().get(&DataKey::Version).unwrap_or(0)
+}
+
+/// 0 → 1: liabilities are tracked per token. Older deployments held
+/// nothing of their own, so the whole balance is owed.
+fn track_liabilities(e: &Env) {
+    let this = e.current_contract_address();
+    let mut tokens = allowed_tokens(e);
+    tokens.push_front(default_token(e));
+    for token in tokens.iter() {
+        let balance = token::Client::new(e, &token).balance(&this);
+        e.storage()
+            .instance()
+            .set(&DataKey::Liabilities(token), &balance);
+    }
+}
+
+/// Swap the contract's code for `new_wasm_hash`.
+pub(crate) fn swap_code(e: &Env, new_wasm_hash: BytesN<32>) {
+    e.deployer()
+        .update_current_contract_wasm(new_wasm_hash.clone());
+    events::publish(e, "upgraded", (), new_wasm_hash);
+}
+
+/// 1 → 2: the separately stored tunables move into `Config`.
+fn fold_config(e: &Env) {
+    let store = e.storage().instance();
+    let mut config = load_config(e);
+    if let Some(min_bet) = store.get(&DataKey::MinBet) {
+        config.min_bet = min_bet;
+    }
+    if let Some(bps) = store.get(&DataKey::ClaimFeeBps) {
+        config.fee_bps = bps;
+    }
+    if let Some(ledgers) = store.get(&DataKey::GraceLedgers) {
+        config.grace_ledgers = ledgers;
+    }
+    if let Some(max) = store.get(&DataKey::MaxOpenRounds) {
+        config.max_open_rounds = max;
+    }
+    if let Some(ledgers) = store.get(&DataKey::ClaimWindow) {
+        config.claim_window_ledgers = ledgers;
+    }
+    for key in [
+        DataKey::MinBet,
+        DataKey::ClaimFeeBps,
+        DataKey::GraceLedgers,
+        DataKey::MaxOpenRounds,
+        DataKey::ClaimWindow,
+    ] {
+        store.remove(&key);
+    }
+    save_config(e, &config);
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Replace the contract's code with the uploaded `new_wasm_hash`. Call
+    /// `migrate` straight after.
+    pub fn upgrade(env: Env, admin: Address, new_wasm_hash: BytesN<32>) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        timelock::require_unlocked(&env);
+        swap_code(&env, new_wasm_hash);
+    }
+
+    /// Bring storage up to `STORAGE_VERSION`. Fails with
+    /// `AlreadyInitialised` when it already is. Returns the new version.
+    pub fn migrate(env: Env, admin: Address) -> u32 {
+        require_live(&env);
+        require_admin(&env, &admin);
+        let from = storage_version(&env);
+        if from >= STORAGE_VERSION {
+            panic_with_error!(env, Error::AlreadyInitialised);
+        }
+        for version in from..STORAGE_VERSION {
+            match version {
+                0 => track_liabilities(&env),
+                1 => fold_config(&env),
+                _ => unreachable!(),
+            }
+        }
+        env.storage()
+            .instance()
+            .set(&DataKey::Version, &STORAGE_VERSION);
+        events::publish(&env, "migrated", (from,), STORAGE_VERSION);
+        STORAGE_VERSION
+    }
+
+    /// Storage layout version the deployment is on.
+    pub fn get_storage_version(env: Env) -> u32 {
+        storage_version(&env)
+    }
+}
@@ -0,0 +1,359 @@
+//! Fixed‑odds rounds backed by a house vault. Liquidity providers deposit
+//! into the vault for shares; a fixed‑odds round quotes decimal odds per
+//! side, each bet locks in the odds at the time, and the vault — not the
+//! other side — owes the winnings. A bet is refused when the vault could
+//! no longer cover the worst case of every open book.
+
+use soroban_sdk::{contractimpl, contracttype, panic_with_error, Address, Env};
+
+use crate::state::load_round_advanced;
+use crate::{
+    check_caps, check_risk, checked_add, compliance, create_round, default_rules, extend_instance,
+    extend_read, extend_written, load_round, mul_div, pay, pull, require_admin, require_live,
+    require_min_bet, require_paying, token_client, DataKey, Deadline, Error, KalePrediction,
+    KalePredictionArgs, KalePredictionClient, Market, Round, RoundState, Side,
+};
+
+/// Decimal odds in basis points: a winning stake of 100 at 18 000 is paid
+/// 180. Both must be above 10 000.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct FixedOdds {
+    pub higher_bps: u32,
+    pub lower_bps: u32,
+}
+
+/// Pooled house liquidity. `exposure` is the most the open books could
+/// still cost it, and is never allowed above `assets`.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Vault {
+    pub assets: i128,
+    pub shares: i128,
+    pub exposure: i128,
+}
+
+/// A fixed‑odds round's book against the vault.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Book {
+    pub odds: FixedOdds, // quoted now; bets keep the odds they were placed at
+    pub higher_stakes: i128,
+    pub lower_stakes: i128,
+    pub higher_owed: i128, // paid out if Higher wins
+    pub lower_owed: i128,  // idem, Lower
+    pub closed: bool,      // result booked into the vault
+}
+
+#[contracttype]
+#[derive(Clone, Copy)]
+pub struct FixedBet {
+    pub side: Side,
+    pub amount: i128,
+    pub payout: i128, // if `side` wins
+}
+
+impl Book {
+    /// Worst case for the vault over this book's outcomes.
+    fn exposure(&self, e: &Env) -> i128 {
+        let staked = checked_add(e, self.higher_stakes, self.lower_stakes);
+        checked_add(e, self.higher_owed.max(self.lower_owed), -staked).max(0)
+    }
+}
+
+fn load_vault(e: &Env) -> Vault {
+    e.storage()
+        .instance()
+        .get(&DataKey::Vault)
+        .unwrap_or_default()
+}
+
+fn save_vault(e: &Env, vault: &Vault) {
+    e.storage().instance().set(&DataKey::Vault, vault);
+    extend_instance(e);
+}
+
+fn load_book(e: &Env, round_id: u32) -> Book {
+    let key = DataKey::Book(round_id);
+    let book = e
+        .storage()
+        .persistent()
+        .get(&key)
+        .unwrap_or_else(|| panic_with_error!(e, Error::WrongMarket));
+    extend_read(e, &key);
+    book
+}
+
+fn save_book(e: &Env, round_id: u32, book: &Book) {
+    let key = DataKey::Book(round_id);
+    e.storage().persistent().set(&key, book);
+    extend_written(e, &key);
+}
+
+fn load_shares(e: &Env, owner: &Address) -> i128 {
+    let key = DataKey::VaultShares(owner.clone());
+    let shares = e.storage().persistent().get(&key).unwrap_or(0);
+    if shares > 0 {
+        extend_read(e, &key);
+    }
+    shares
+}
+
+fn save_shares(e: &Env, owner: &Address, shares: i128) {
+    let key = DataKey::VaultShares(owner.clone());
+    e.storage().persistent().set(&key, &shares);
+    extend_written(e, &key);
+}
+
+fn check_odds(e: &Env, odds: &FixedOdds) {
+    if odds.higher_bps <= 10_000 || odds.lower_bps <= 10_000 {
+        panic_with_error!(e, Error::InvalidRules);
+    }
+}
+
+/// Book a settled round's result into the vault and release its exposure.
+fn close_book(e: &Env, round: &Round, book: &mut Book) {
+    let staked = checked_add(e, book.higher_stakes, book.lower_stakes);
+    let owed = match round.outcome.winner() {
+        Some(Side::Higher) => book.higher_owed,
+        Some(Side::Lower) => book.lower_owed,
+        _ => staked, // push, void, expiry: stakes go back
+    };
+    let mut vault = load_vault(e);
+    vault.assets = checked_add(e, vault.assets, checked_add(e, staked, -owed));
+    vault.exposure = checked_add(e, vault.exposure, -book.exposure(e));
+    save_vault(e, &vault);
+    book.closed = true;
+}
+
+#[contractimpl]
+impl KalePrediction {
+    /// Add `amount` of liquidity to the vault for a pro‑rata share of it.
+    /// Fails with `ZeroAmount` if that is not worth a share, and with
+    /// `InsufficientLiquidity` while outstanding shares are worth nothing.
+    pub fn deposit_vault(env: Env, from: Address, amount: i128) -> i128 {
+        require_live(&env);
+        if amount <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        from.require_auth();
+
+        let mut vault = load_vault(&env);
+        let minted = match (vault.shares, vault.assets) {
+            (0, _) => amount,
+            // a wiped‑out vault's shares would claim part of the deposit
+            (_, 0) => panic_with_error!(env, Error::InsufficientLiquidity),
+            (shares, assets) => mul_div(&env, amount, shares, assets),
+        };
+        if minted == 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        pull(&env, &from, amount);
+        vault.assets = checked_add(&env, vault.assets, amount);
+        vault.shares = checked_add(&env, vault.shares, minted);
+        save_vault(&env, &vault);
+
+        let held = load_shares(&env, &from);
+        save_shares(&env, &from, checked_add(&env, held, minted));
+        minted
+    }
+
+    /// Redeem `shares` for their part of the vault, as far as it is not
+    /// needed to cover open books.
+    pub fn withdraw_vault(env: Env, from: Address, shares: i128) -> i128 {
+        require_live(&env);
+        if shares <= 0 {
+            panic_with_error!(env, Error::ZeroAmount);
+        }
+        from.require_auth();
+
+        let held = load_shares(&env, &from);
+        if shares > held {
+            panic_with_error!(env, Error::InsufficientLiquidity);
+        }
+        let mut vault = load_vault(&env);
+        let amount = mul_div(&env, shares, vault.assets, vault.shares);
+        let assets = checked_add(&env, vault.assets, -amount);
+        if assets < vault.exposure {
+            panic_with_error!(env, Error::InsufficientLiquidity);
+        }
+
+        vault.assets = assets;
+        vault.shares = checked_add(&env, vault.shares, -shares);
+        save_vault(&env, &vault);
+        save_shares(&env, &from, checked_add(&env, held, -shares));
+        pay(&env, &token_client(&env), &from, amount);
+        amount
+    }
+
+    /// Start a fixed‑odds round on the invocations from now to finality:
+    /// bets go through `bet_fixed` against the vault at `odds`.
+    pub fn start_fixed_round(
+        env: Env,
+        admin: Address,
+        predicted_count: u32,
+        deadline_ledger: u32,
+        finality_ledger: u32,
+        odds: FixedOdds,
+    ) -> u32 {
+        check_odds(&env, &odds);
+        let rules = default_rules(&env);
+        let round_id = create_round(
+            &env,
+            &admin,
+            Market::Invocations,
+            predicted_count,
+            Deadline::Ledger(deadline_ledger),
+            Deadline::Ledger(finality_ledger),
+            rules,
+        );
+        let book = Book {
+            odds,
+            higher_stakes: 0,
+            lower_stakes: 0,
+            higher_owed: 0,
+            lower_owed: 0,
+            closed: false,
+        };
+        save_book(&env, round_id, &book);
+        round_id
+    }
+
+    /// Requote a fixed‑odds round; bets already placed keep their odds.
+    pub fn set_odds(env: Env, admin: Address, round_id: u32, odds: FixedOdds) {
+        require_live(&env);
+        require_admin(&env, &admin);
+        check_odds(&env, &odds);
+        let round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let mut book = load_book(&env, round_id);
+        book.odds = odds;
+        save_book(&env, round_id, &book);
+    }
+
+    /// Bet on a fixed‑odds round at its current odds. Fails with
+    /// `InsufficientLiquidity` if the vault could not cover the result.
+    pub fn bet_fixed(env: Env, player: Address, round_id: u32, side: Side, amount: i128) {
+        require_live(&env);
+        require_min_bet(&env, amount);
+        player.require_auth();
+
+        let round = load_round_advanced(&env, round_id);
+        if round.state != RoundState::Open {
+            panic_with_error!(env, Error::BettingClosed);
+        }
+        let mut book = load_book(&env, round_id);
+        let before = book.exposure(&env);
+        let pot = checked_add(&env, book.higher_stakes, book.lower_stakes);
+        let odds_bps = match side {
+            Side::Higher => book.odds.higher_bps,
+            Side::Lower => book.odds.lower_bps,
+            _ => panic_with_error!(env, Error::InvalidSide),
+        };
+        let payout = mul_div(&env, amount, i128::from(odds_bps), 10_000);
+        match side {
+            Side::Higher => {
+                book.higher_stakes = checked_add(&env, book.higher_stakes, amount);
+                book.higher_owed = checked_add(&env, book.higher_owed, payout);
+            }
+            _ => {
+                book.lower_stakes = checked_add(&env, book.lower_stakes, amount);
+                book.lower_owed = checked_add(&env, book.lower_owed, payout);
+            }
+        }
+
+        let mut vault = load_vault(&env);
+        vault.exposure = checked_add(&env, vault.exposure, book.exposure(&env) - before);
+        if vault.exposure > vault.assets {
+            panic_with_error!(env, Error::InsufficientLiquidity);
+        }
+        let key = DataKey::FixedBet(round_id, player.clone());
+        let held = match env.storage().persistent().get::<DataKey, FixedBet>(&key) {
+            Some(b) if b.side != side => panic_with_error!(env, Error::InvalidSide),
+            Some(b) => b,
+            None => FixedBet {
+                side,
+                amount: 0,
+                payout: 0,
+            },
+        };
+        check_risk(&env, &player, round_id, amount);
+        let staked = checked_add(&env, held.amount, amount);
+        check_caps(&env, round_id, &round, &player, staked, pot, amount);
+
+        pull(&env, &player, amount);
+        save_vault(&env, &vault);
+        save_book(&env, round_id, &book);
+        env.storage().persistent().set(
+            &key,
+            &FixedBet {
+                side,
+                amount: staked,
+                payout: checked_add(&env, held.payout, payout),
+            },
+        );
+        extend_written(&env, &key);
+    }
+
+    /// Collect a fixed‑odds bet: its locked payout if it won, the stake
+    /// back on a push, void or expiry.
+    pub fn claim_fixed(env: Env, player: Address, round_id: u32) {
+        require_live(&env);
+        player.require_auth();
+        compliance::require_unfrozen(&env, &player);
+
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let mut book = load_book(&env, round_id);
+        if !book.closed {
+            close_book(&env, &round, &mut book);
+            save_book(&env, round_id, &book);
+        }
+
+        let key = DataKey::FixedBet(round_id, player.clone());
+        let bet: FixedBet = env
+            .storage()
+            .persistent()
+            .get(&key)
+            .unwrap_or_else(|| panic_with_error!(env, Error::AlreadyClaimed));
+        env.storage().persistent().remove(&key);
+        let payout = match round.outcome.winner() {
+            Some(side) if side == bet.side => bet.payout,
+            Some(_) => return,
+            None => bet.amount,
+        };
+        pay(&env, &token_client(&env), &player, payout);
+    }
+
+    /// Book a settled fixed‑odds round into the vault, releasing its
+    /// exposure for withdrawals and new bets. Claims do this too.
+    pub fn close_book(env: Env, round_id: u32) {
+        require_live(&env);
+        let round = load_round_advanced(&env, round_id);
+        require_paying(&env, &round);
+        let mut book = load_book(&env, round_id);
+        if book.closed {
+            panic_with_error!(env, Error::AlreadyClaimed);
+        }
+        close_book(&env, &round, &mut book);
+        save_book(&env, round_id, &book);
+    }
+
+    /// Vault totals.
+    pub fn get_vault(env: Env) -> Vault {
+        load_vault(&env)
+    }
+
+    /// Vault shares held by `owner`.
+    pub fn get_vault_shares(env: Env, owner: Address) -> i128 {
+        load_shares(&env, &owner)
+    }
+
+    /// A fixed‑odds round's book.
+    pub fn get_book(env: Env, round_id: u32) -> Book {
+        load_round(&env, round_id);
+        load_book(&env, round_id)
+    }
+}